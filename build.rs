@@ -9,7 +9,14 @@ use std::env;
 use std::path::PathBuf;
 
 fn main() {
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
     let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+
+    // WebAssembly and bare-metal builds have no C consumers, there is no header or shared object to produce.
+    if target_arch == "wasm32" || target_os == "none" {
+        return;
+    }
+
     let include_dir = env!("CARGO_MANIFEST_DIR");
     let package = env!("CARGO_PKG_NAME");
     let lib_name = match target_os.as_str() {
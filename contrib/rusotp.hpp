@@ -11,6 +11,20 @@
 #include <ostream>
 #include <new>
 
+/// RGB colour used for the dark and light modules of a rendered QR code.
+struct QrColor;
+
+/// Counts the failed verification attempts of each account and rejects attempts while an account is throttled.
+///
+/// Accounts are identified by a key chosen by the caller, e.g. the user name. The throttle is shared between
+/// threads by reference; the bookkeeping of an attempt and the verification run under a single lock, so concurrent
+/// guesses cannot slip past the limit.
+///
+/// The failures of an account are forgotten once it has not failed for the longest wait of the configuration
+/// (`lockout` or `max_backoff`) after its last wait ended, so that guesses against arbitrary keys cannot grow the
+/// bookkeeping without bound.
+struct Throttle;
+
 /// FFI-safe result type for operations returning a string.
 ///
 /// # Fields
@@ -61,6 +75,40 @@ struct HotpConfigResult {
   const char *error;
 };
 
+/// Limits applied by a [`Throttle`](crate::throttle::Throttle) to the failed attempts of each account.
+///
+/// # Fields
+///
+/// * `max_failures` - Number of consecutive failures after which the account is locked, `0` to never lock it.
+/// * `backoff` - Seconds to wait after the first failure; the wait doubles with each further failure, `0` to
+///   disable back-off.
+/// * `max_backoff` - Upper bound of the back-off wait in seconds.
+/// * `lockout` - Seconds the account stays locked once `max_failures` is reached; the failures count from zero
+///   again afterwards.
+///
+/// The default waits 1, 2, 4 and 8 seconds after the first four failures and locks the account for 15 minutes after
+/// the fifth, which leaves an attacker about 20 guesses an hour.
+struct ThrottleConfig {
+  uint32_t max_failures;
+  uint64_t backoff;
+  uint64_t max_backoff;
+  uint64_t lockout;
+};
+
+/// FFI-safe result type for throttled verifications.
+///
+/// # Fields
+/// - `success`: Indicates if the verification was performed.
+/// - `data`: Whether the OTP was accepted (valid if `success` is true).
+/// - `retry_after`: Seconds to wait before the next attempt if the account is throttled, `0` otherwise.
+/// - `error`: Pointer to a C string containing the error message (valid if `success` is false).
+struct ThrottledResult {
+  bool success;
+  bool data;
+  unsigned long long retry_after;
+  const char *error;
+};
+
 /// Configuration for TOTP (Time-based One-Time Password).
 ///
 /// # Fields
@@ -89,6 +137,10 @@ struct TotpConfigResult {
   const char *error;
 };
 
+
+
+
+
 extern "C" {
 
 /// Generates an HOTP (HMAC-based One-Time Password) based on the provided configuration and counter.
@@ -209,8 +261,147 @@ StringResult hotp_provisioning_uri(HotpConfig config,
                                    const char *user,
                                    unsigned long long counter);
 
+/// Generates a provisioning QR code for HOTP (HMAC-based One-Time Password) rendered as an SVG document.
+///
+/// # Arguments
+///
+/// * `config` - A `HotpConfig` struct containing the configuration for the HOTP generation.
+/// * `issuer` - A pointer to a C string representing the issuer of the HOTP.
+/// * `user` - A pointer to a C string representing the name of the user or account.
+/// * `counter` - A counter value used in the HOTP generation.
+///
+/// # Returns
+///
+/// A `StringResult` containing success status and the SVG document if success.
+///
+/// # Example
+/// ```
+/// # use inline_c::assert_cxx;
+/// #
+/// # fn main() {
+/// #     (assert_cxx! {
+/// #include <stdio.h>
+/// #include "rusotp.hpp"
+///
+/// int main() {
+///     HotpConfig config = {"SHA1", "12345678901234567890", 6, 10};
+///     unsigned long counter = 2;
+///
+///     StringResult svg = hotp_provisioning_qr_svg(config, "rusotp", "rusotp", counter);
+///     printf("SVG : %s\n", svg.data);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+StringResult hotp_provisioning_qr_svg(HotpConfig config,
+                                      const char *issuer,
+                                      const char *user,
+                                      unsigned long long counter);
+
 HotpConfigResult hotp_from_uri(const char *uri);
 
+/// Returns the default limits of a throttle: back-off of 1, 2, 4 and 8 seconds, then a 15 minutes lockout after the
+/// fifth consecutive failure.
+ThrottleConfig throttle_default_config();
+
+/// Creates a throttle counting failed verifications per account key, see `hotp_verify_throttled` and
+/// `totp_verify_throttled`.
+///
+/// # Arguments
+///
+/// * `config` - A `ThrottleConfig` struct with the limits applied to each account.
+///
+/// # Returns
+///
+/// A pointer to the throttle, which must be released with `throttle_free`. It may be shared between threads.
+///
+/// # Example
+///
+/// ```
+/// # use inline_c::assert_cxx;
+/// #
+/// # fn main() {
+/// #     (assert_cxx! {
+/// #include <stdio.h>
+/// #include "rusotp.hpp"
+///
+/// int main() {
+///     HotpConfig config = {"SHA1", "12345678901234567890", 6, 10};
+///     Throttle *throttle = throttle_new(throttle_default_config());
+///
+///     ThrottledResult rejected = hotp_verify_throttled(throttle, "user@email.mail", config, "000000", 0, 0);
+///     printf("VERIFIED : %s\n", rejected.data ? "true" : "false");
+///
+///     ThrottledResult throttled = hotp_verify_throttled(throttle, "user@email.mail", config, "755224", 0, 0);
+///     printf("RETRY AFTER : %llu\n", throttled.retry_after);
+///
+///     throttle_free(throttle);
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+Throttle *throttle_new(ThrottleConfig config);
+
+/// Releases a throttle created by `throttle_new`.
+///
+/// # Arguments
+///
+/// * `throttle` - A pointer returned by `throttle_new`, or null.
+///
+/// # Safety
+///
+/// `throttle` must not be used after this call, and must not be released twice.
+void throttle_free(Throttle *throttle);
+
+/// Verifies an HOTP like `hotp_verify` unless the account is throttled, counting the outcome.
+///
+/// # Arguments
+///
+/// * `throttle` - A pointer returned by `throttle_new`.
+/// * `key` - A pointer to a C string identifying the account, e.g. the user name.
+/// * `config` - A `HotpConfig` struct containing the configuration for the HOTP verification.
+/// * `otp` - A pointer to a C string representing the OTP to be verified.
+/// * `counter` - A counter value used in the HOTP verification.
+/// * `retries` - The number of retries allowed for the HOTP verification.
+///
+/// # Returns
+///
+/// A `ThrottledResult`; if the account is throttled, `success` is false and `retry_after` holds the seconds to wait.
+ThrottledResult hotp_verify_throttled(const Throttle *throttle,
+                                      const char *key,
+                                      HotpConfig config,
+                                      const char *otp,
+                                      unsigned long long counter,
+                                      unsigned long long retries);
+
+/// Verifies a TOTP at the current time like `totp_verify` unless the account is throttled, counting the outcome.
+///
+/// # Arguments
+///
+/// * `throttle` - A pointer returned by `throttle_new`.
+/// * `key` - A pointer to a C string identifying the account, e.g. the user name.
+/// * `config` - A `TotpConfig` struct containing the configuration for the TOTP verification.
+/// * `otp` - A pointer to a C string representing the OTP to be verified.
+/// * `after` - The timestamp before which OTPs are rejected.
+/// * `drift_ahead` - The allowed drift ahead in seconds.
+/// * `drift_behind` - The allowed drift behind in seconds.
+///
+/// # Returns
+///
+/// A `ThrottledResult`; if the account is throttled, `success` is false and `retry_after` holds the seconds to wait.
+ThrottledResult totp_verify_throttled(const Throttle *throttle,
+                                      const char *key,
+                                      TotpConfig config,
+                                      const char *otp,
+                                      unsigned long long after,
+                                      unsigned long long drift_ahead,
+                                      unsigned long long drift_behind);
+
 /// Generates a TOTP (Time-based One-Time Password) based on the provided configuration for the current time.
 ///
 /// # Arguments
@@ -411,6 +602,44 @@ StringResult totp_provisioning_uri(TotpConfig config,
                                    const char *issuer,
                                    const char *name);
 
+/// Generates a provisioning QR code for TOTP (Time-based One-Time Password) rendered as an SVG document.
+///
+/// # Arguments
+///
+/// * `config` - A `TotpConfig` struct containing the configuration for the TOTP generation.
+/// * `issuer` - A pointer to a C string representing the issuer of the TOTP.
+/// * `name` - A pointer to a C string representing the name of the user or account.
+///
+/// # Returns
+///
+/// A `StringResult` containing success status and the SVG document if success.
+///
+/// # Example
+///
+/// ```
+/// # use inline_c::assert_cxx;
+/// #
+/// # fn main() {
+/// #     (assert_cxx! {
+/// #include <stdio.h>
+/// #include "rusotp.hpp"
+///
+/// int main() {
+///     TotpConfig config = {"SHA1", "12345678901234567890", 6, 10, 30};
+///
+///     StringResult svg = totp_provisioning_qr_svg(config, "rusotp", "user@email.mail");
+///     printf("SVG : %s\n", svg.data);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+///```
+StringResult totp_provisioning_qr_svg(TotpConfig config,
+                                      const char *issuer,
+                                      const char *name);
+
 TotpConfigResult totp_from_uri(const char *uri);
 
 }  // extern "C"
@@ -5,7 +5,7 @@
 //
 // See the file LICENSE for details.
 
-use rusotp::{generate_qr_code_image, generate_qr_code_string, Secret, HOTP};
+use rusotp::{QrRenderer, Secret, HOTP};
 
 fn main() {
     let secret = Secret::new_from_str("1238*&^$*&JHGHJI^&@#^&*%%^*&hj1HJV761298").unwrap();
@@ -14,5 +14,7 @@ fn main() {
 
     let data = hotp.provisioning_uri("Github", "eendroroy@github.com", 0).unwrap();
     println!("{}", data);
-    generate_qr_code_image(data.clone(), "./code.png".to_string());
+    let renderer = QrRenderer::new();
+    println!("{}", renderer.terminal(&data).unwrap());
+    renderer.save(&data, "./code.png").unwrap();
 }
@@ -5,7 +5,7 @@
 //
 // See the file LICENSE for details.
 
-use rusotp::{generate_qr_code_image, Secret, TOTP};
+use rusotp::{QrRenderer, Secret, TOTP};
 
 fn main() {
     let secret = Secret::new_from_str("1238*&^$*&JHGHJI^&@#^&*%%^*&hj1HJV761298").unwrap();
@@ -14,5 +14,5 @@ fn main() {
 
     let data = totp.provisioning_uri("Rusotp", "user@rusotp.com").unwrap();
     println!("{}", data);
-    generate_qr_code_image(data.clone(), "./code.png".to_string());
+    QrRenderer::new().save(&data, "./code.png").unwrap();
 }
@@ -0,0 +1,130 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! Asynchronous verification with persisted counters, enabled with the `async` feature.
+//!
+//! An [`AsyncVerifier`] verifies HOTPs and TOTPs and records, in an [`AsyncOtpStore`], the counter or time step to
+//! expect next, so that each OTP is accepted once. The store is accessed through futures, for services whose
+//! persistence goes through async database drivers. The verifier does not depend on an async runtime.
+//!
+//! When the same OTP is presented twice concurrently, both verifications may match it before either records it; the
+//! one which records it second fails with a [`ReplayedOtpError`](crate::ReplayedOtpError).
+//!
+//! ```
+//! use rusotp::async_verifier::{AsyncVerifier, MemoryAsyncOtpStore};
+//! use rusotp::{OtpResult, Secret, TOTP};
+//!
+//! async fn login(verifier: &AsyncVerifier<MemoryAsyncOtpStore>, user: &str, otp: &str) -> OtpResult<bool> {
+//!     let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+//!     Ok(verifier.verify_totp(user, &totp, otp, 1, 1).await?.is_some())
+//! }
+//! ```
+
+mod store;
+
+pub use store::{AsyncOtpStore, MemoryAsyncOtpStore, StoreFuture};
+
+use crate::util::time::now;
+use crate::{AlgorithmTrait, KeyProvider, OtpResult, ReplayedOtpError, VerifyOutcome, HOTP, TOTP};
+
+/// Verifies OTPs and accepts each of them once, backed by an [`AsyncOtpStore`].
+///
+/// Each key of the store identifies one HOTP or TOTP, e.g. the user name.
+#[derive(Debug)]
+pub struct AsyncVerifier<S: AsyncOtpStore> {
+    store: S,
+}
+
+impl<S: AsyncOtpStore> AsyncVerifier<S> {
+    /// Creates a verifier recording the counters in `store`.
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Returns the store of the counters.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Verifies an HOTP through [`HOTP::verify_window`] against the counter expected for `key`, then records the
+    /// counter following the matched one.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the HOTP in the store; 0 is expected if it has no counter yet.
+    /// * `hotp` - The HOTP of `key`.
+    /// * `otp` - The OTP to be verified.
+    /// * `ahead` - The number of counters after the expected one to accept.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the `VerifyOutcome` if the OTP is accepted, `None` if it is rejected,
+    /// `ReplayedOtpError` if it matched but another verification recorded it first, or the error of the generation or
+    /// of the store.
+    pub async fn verify_hotp<A: AlgorithmTrait, K: KeyProvider>(
+        &self,
+        key: &str,
+        hotp: &HOTP<A, K>,
+        otp: &str,
+        ahead: u64,
+    ) -> OtpResult<Option<VerifyOutcome>> {
+        let counter = self.store.load(key).await?.unwrap_or(0);
+        let outcome = hotp.verify_window(otp, counter, ahead)?;
+        self.record(key, outcome).await
+    }
+
+    /// Verifies a TOTP at the current time, see [`AsyncVerifier::verify_totp_at`].
+    pub async fn verify_totp<A: AlgorithmTrait, K: KeyProvider>(
+        &self,
+        key: &str,
+        totp: &TOTP<A, K>,
+        otp: &str,
+        behind: u64,
+        ahead: u64,
+    ) -> OtpResult<Option<VerifyOutcome>> {
+        self.verify_totp_at(key, totp, otp, now(), behind, ahead).await
+    }
+
+    /// Verifies a TOTP through [`TOTP::verify_window_at`], rejecting the time steps before the one expected for
+    /// `key`, then records the time step following the matched one.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the TOTP in the store.
+    /// * `totp` - The TOTP of `key`.
+    /// * `otp` - The OTP to be verified.
+    /// * `at` - A timestamp value used as the current time.
+    /// * `behind` - The number of time steps before the one of `at` to accept.
+    /// * `ahead` - The number of time steps after the one of `at` to accept.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the `VerifyOutcome` if the OTP is accepted, `None` if it is rejected or was already
+    /// used, `ReplayedOtpError` if it matched but another verification recorded it first, or the error of the
+    /// generation or of the store.
+    pub async fn verify_totp_at<A: AlgorithmTrait, K: KeyProvider>(
+        &self,
+        key: &str,
+        totp: &TOTP<A, K>,
+        otp: &str,
+        at: u64,
+        behind: u64,
+        ahead: u64,
+    ) -> OtpResult<Option<VerifyOutcome>> {
+        let next = self.store.load(key).await?;
+        let outcome = totp.verify_window_at(otp, at, next, behind, ahead)?;
+        self.record(key, outcome).await
+    }
+
+    async fn record(&self, key: &str, outcome: Option<VerifyOutcome>) -> OtpResult<Option<VerifyOutcome>> {
+        match outcome {
+            Some(outcome) if self.store.advance(key, outcome.next).await? => Ok(Some(outcome)),
+            Some(outcome) => Err(Box::new(ReplayedOtpError(outcome.counter))),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{OtpResult, StoreError};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+/// The future returned by the methods of an [`AsyncOtpStore`].
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = OtpResult<T>> + Send + 'a>>;
+
+/// Asynchronous persistence of the counter (HOTP) or time step (TOTP) to expect next for each key.
+///
+/// Implementations return boxed futures, e.g. `Box::pin(async move { ... })` around the queries of an async database
+/// driver. `advance` must be atomic: when the same OTP is presented twice concurrently, exactly one of them may see
+/// `true`. With a database this is an `UPDATE ... WHERE key = ? AND next < ?` (or an insert for the first OTP)
+/// checking the number of affected rows.
+pub trait AsyncOtpStore: Send + Sync {
+    /// Returns the counter or time step to expect next for `key`, `None` if no OTP of it was accepted yet.
+    fn load<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<u64>>;
+
+    /// Records `next` for `key` if it is greater than the stored one, returning `false` otherwise.
+    fn advance<'a>(&'a self, key: &'a str, next: u64) -> StoreFuture<'a, bool>;
+}
+
+/// An [`AsyncOtpStore`] keeping the counters in memory, for tests and single process services.
+#[derive(Debug, Default)]
+pub struct MemoryAsyncOtpStore {
+    counters: Mutex<HashMap<String, u64>>,
+}
+
+impl MemoryAsyncOtpStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> OtpResult<std::sync::MutexGuard<'_, HashMap<String, u64>>> {
+        self.counters
+            .lock()
+            .map_err(|_| Box::new(StoreError("store lock poisoned".to_string())) as _)
+    }
+}
+
+impl AsyncOtpStore for MemoryAsyncOtpStore {
+    fn load<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<u64>> {
+        Box::pin(async move { Ok(self.lock()?.get(key).copied()) })
+    }
+
+    fn advance<'a>(&'a self, key: &'a str, next: u64) -> StoreFuture<'a, bool> {
+        Box::pin(async move {
+            let mut counters = self.lock()?;
+            match counters.get(key) {
+                Some(stored) if *stored >= next => Ok(false),
+                _ => {
+                    counters.insert(key.to_string(), next);
+                    Ok(true)
+                }
+            }
+        })
+    }
+}
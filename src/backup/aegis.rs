@@ -0,0 +1,318 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::backup::{error, field, parse_json, to_json, BackupEntry, BackupImport, ExportedOtp, OtpParams};
+use crate::{BackupError, OtpAccount, OtpError, OtpResult, Secret};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64ct::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Slot type of a key encrypted with a password derived key.
+const PASSWORD_SLOT: u32 = 1;
+const KEY_LENGTH: usize = 32;
+/// Upper bounds of the scrypt parameters read from a password slot.
+const MAX_SCRYPT_N: u64 = 1 << 20;
+const MAX_SCRYPT_R: u32 = 32;
+const MAX_SCRYPT_P: u32 = 16;
+/// Versions written by [`export_aegis`].
+const FILE_VERSION: u32 = 1;
+const DATABASE_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize)]
+struct AegisFile {
+    #[serde(default)]
+    version: u32,
+    header: Header,
+    db: Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    slots: Option<Vec<Slot>>,
+    params: Option<CipherParams>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Slot {
+    #[serde(rename = "type")]
+    kind: u32,
+    key: String,
+    key_params: CipherParams,
+    n: Option<u64>,
+    r: Option<u32>,
+    p: Option<u32>,
+    salt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CipherParams {
+    nonce: String,
+    tag: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Database {
+    #[serde(default)]
+    version: u32,
+    entries: Vec<Value>,
+    #[serde(default)]
+    groups: Vec<Group>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Group {
+    uuid: String,
+    name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uuid: Option<String>,
+    name: String,
+    #[serde(default)]
+    issuer: String,
+    #[serde(default)]
+    note: String,
+    /// Group name, used by database versions before 3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    /// Group UUIDs, used since database version 3.
+    #[serde(default)]
+    groups: Vec<String>,
+    info: Info,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Info {
+    secret: String,
+    algo: String,
+    digits: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    period: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    counter: Option<u64>,
+}
+
+/// Imports an Aegis JSON export (`aegis-export-*.json` or `aegis-backup-*.json`).
+///
+/// # Arguments
+///
+/// * `json` - The content of the export.
+/// * `password` - The password of an encrypted export; ignored for plain exports.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the imported entries and the entries which were skipped (e.g. Steam, mOTP or Yandex
+/// entries), or an error if the file is not an Aegis export, is encrypted and no or a wrong password was given.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::backup::import_aegis;
+///
+/// let json = r#"{"version":1,"header":{"slots":null,"params":null},"db":{"version":3,"entries":[{"type":"totp",
+///     "uuid":"01234567-89ab-cdef-0123-456789abcdef","name":"user@email.mail","issuer":"rusotp","note":"",
+///     "info":{"secret":"GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ","algo":"SHA1","digits":6,"period":30}}],"groups":[]}}"#;
+///
+/// let import = import_aegis(json, None).unwrap();
+/// assert_eq!(import.entries[0].account.label(), "rusotp:user@email.mail");
+/// assert!(import.errors.is_empty());
+/// ```
+pub fn import_aegis(json: &str, password: Option<&str>) -> OtpResult<BackupImport> {
+    let file: AegisFile = parse_json(json)?;
+    let database: Database = match (&file.header.slots, &file.db) {
+        (None, db) => serde_json::from_value(db.clone()).map_err(|_| error("not an Aegis export"))?,
+        (Some(slots), Value::String(db)) => {
+            let password = password.ok_or_else(|| error("the Aegis export is encrypted, a password is required"))?;
+            let params = file
+                .header
+                .params
+                .as_ref()
+                .ok_or_else(|| error("missing encryption parameters"))?;
+            let plain = decrypt(&master_key(slots, password)?, params, &decode_base64(db)?)?;
+            serde_json::from_slice(&plain).map_err(|_| error("not an Aegis export"))?
+        }
+        _ => return Err(error("not an Aegis export")),
+    };
+
+    let groups: HashMap<String, String> = database.groups.into_iter().map(|g| (g.uuid, g.name)).collect();
+    Ok(BackupImport::collect(
+        database.entries,
+        |value| field(value, "name"),
+        |entry: Entry| {
+            let otp = OtpParams {
+                kind: &entry.kind,
+                secret: Secret::new_from_base32(&entry.info.secret)?,
+                algorithm: &entry.info.algo,
+                digits: entry.info.digits,
+                period: entry.info.period.unwrap_or(30),
+                counter: entry.info.counter.unwrap_or_default(),
+            }
+            .into_otp()?;
+            let tags = entry
+                .group
+                .into_iter()
+                .chain(entry.groups.iter().filter_map(|uuid| groups.get(uuid).cloned()))
+                .collect();
+
+            Ok(BackupEntry {
+                account: OtpAccount {
+                    issuer: entry.issuer,
+                    name: entry.name,
+                    otp,
+                },
+                tags,
+                note: Some(entry.note).filter(|note| !note.is_empty()),
+            })
+        },
+    ))
+}
+
+/// Exports accounts as a plain (unencrypted) Aegis JSON export, which Aegis imports with "Import from file".
+///
+/// Tags are written as Aegis groups and notes as entry notes. The UUIDs of entries and groups are derived from their
+/// position and label, so exporting the same accounts twice gives the same file.
+///
+/// # Arguments
+///
+/// * `entries` - The accounts to export.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the JSON document, or `UnsupportedRadixError` if an account uses a radix other than 10.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::backup::{export_aegis, import_aegis, BackupEntry};
+/// use rusotp::{OtpAccount, OtpKind, Secret, TOTP};
+///
+/// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+/// let account = OtpAccount { issuer: "rusotp".to_string(), name: "user@email.mail".to_string(), otp: OtpKind::TOTP(totp) };
+/// let entries = vec![BackupEntry { account, tags: vec!["work".to_string()], note: None }];
+///
+/// let json = export_aegis(&entries).unwrap();
+/// assert_eq!(import_aegis(&json, None).unwrap().entries, entries);
+/// ```
+pub fn export_aegis(entries: &[BackupEntry]) -> OtpResult<String> {
+    let mut groups: Vec<Group> = Vec::new();
+    let mut values = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.iter().enumerate() {
+        let otp = ExportedOtp::new(&entry.account.otp)?;
+        let mut group_uuids = Vec::new();
+        for tag in &entry.tags {
+            let group_uuid = uuid(format!("group:{}", tag).as_bytes());
+            if !groups.iter().any(|group| group.uuid == group_uuid) {
+                groups.push(Group {
+                    uuid: group_uuid.clone(),
+                    name: tag.clone(),
+                });
+            }
+            group_uuids.push(group_uuid);
+        }
+
+        let entry = Entry {
+            kind: otp.kind.to_string(),
+            uuid: Some(uuid(format!("entry:{}:{}", index, entry.account.label()).as_bytes())),
+            name: entry.account.name.clone(),
+            issuer: entry.account.issuer.clone(),
+            note: entry.note.clone().unwrap_or_default(),
+            group: None,
+            groups: group_uuids,
+            info: Info {
+                secret: otp.secret,
+                algo: otp.algorithm,
+                digits: otp.digits,
+                period: otp.period,
+                counter: otp.counter,
+            },
+        };
+        values
+            .push(serde_json::to_value(entry).map_err(|e| Box::new(BackupError(e.to_string())) as Box<dyn OtpError>)?);
+    }
+
+    let database = Database {
+        version: DATABASE_VERSION,
+        entries: values,
+        groups,
+    };
+    to_json(&AegisFile {
+        version: FILE_VERSION,
+        header: Header {
+            slots: None,
+            params: None,
+        },
+        db: serde_json::to_value(database).map_err(|e| Box::new(BackupError(e.to_string())) as Box<dyn OtpError>)?,
+    })
+}
+
+/// Formats the first 16 bytes of the SHA-256 digest of `seed` as a version 4 UUID.
+fn uuid(seed: &[u8]) -> String {
+    let digest = Sha256::digest(seed);
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// Unlocks the master key with the first password slot accepting `password`.
+fn master_key(slots: &[Slot], password: &str) -> OtpResult<Vec<u8>> {
+    for slot in slots.iter().filter(|slot| slot.kind == PASSWORD_SLOT) {
+        let (Some(n), Some(r), Some(p), Some(salt)) = (slot.n, slot.r, slot.p, &slot.salt) else {
+            return Err(error("invalid password slot"));
+        };
+        if !n.is_power_of_two() || n > MAX_SCRYPT_N || r > MAX_SCRYPT_R || p > MAX_SCRYPT_P {
+            return Err(error("invalid password slot"));
+        }
+
+        let params = scrypt::Params::new(n.trailing_zeros() as u8, r, p, KEY_LENGTH)
+            .map_err(|_| error("invalid password slot"))?;
+        let mut key = [0u8; KEY_LENGTH];
+        scrypt::scrypt(password.as_bytes(), &decode_hex(salt)?, &params, &mut key)
+            .map_err(|_| error("invalid password slot"))?;
+
+        if let Ok(master) = decrypt(&key, &slot.key_params, &decode_hex(&slot.key)?) {
+            return Ok(master);
+        }
+    }
+    Err(error("wrong password"))
+}
+
+/// Decrypts AES-256-GCM `ciphertext`, whose tag is stored separately in `params`.
+fn decrypt(key: &[u8], params: &CipherParams, ciphertext: &[u8]) -> OtpResult<Vec<u8>> {
+    let nonce = decode_hex(&params.nonce)?;
+    if nonce.len() != 12 {
+        return Err(error("invalid nonce"));
+    }
+    let mut data = ciphertext.to_vec();
+    data.extend(decode_hex(&params.tag)?);
+
+    Aes256Gcm::new_from_slice(key)
+        .map_err(|_| error("invalid key"))?
+        .decrypt(Nonce::from_slice(&nonce), data.as_slice())
+        .map_err(|_| error("decryption failed"))
+}
+
+fn decode_hex(text: &str) -> OtpResult<Vec<u8>> {
+    Secret::new_from_hex(text)
+        .map(Secret::get)
+        .map_err(|_| error("invalid hex field"))
+}
+
+fn decode_base64(text: &str) -> OtpResult<Vec<u8>> {
+    Base64::decode_vec(text).map_err(|_| error("invalid base64 field"))
+}
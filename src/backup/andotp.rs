@@ -0,0 +1,134 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::backup::{field, parse_json, split_label, to_json, BackupEntry, BackupImport, ExportedOtp, OtpParams};
+use crate::{OtpAccount, OtpResult, Secret};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Thumbnail shown by andOTP for exported entries.
+const THUMBNAIL: &str = "Default";
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    secret: String,
+    #[serde(default)]
+    issuer: String,
+    #[serde(default)]
+    label: String,
+    digits: u8,
+    #[serde(rename = "type")]
+    kind: String,
+    algorithm: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    period: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    counter: Option<u64>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<String>,
+}
+
+/// Imports a plain andOTP JSON backup (`otp_accounts.json`).
+///
+/// Encrypted andOTP backups (`.json.aes`) must be decrypted with andOTP first.
+///
+/// # Arguments
+///
+/// * `json` - The content of the backup.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the imported entries and the entries which were skipped (e.g. Steam entries), or an
+/// error if the content is not an andOTP backup.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::backup::import_andotp;
+///
+/// let json = r#"[{"secret":"GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ","issuer":"rusotp","label":"user@email.mail",
+///     "digits":6,"type":"TOTP","algorithm":"SHA1","thumbnail":"Default","last_used":0,"used_frequency":0,
+///     "period":30,"tags":["work"]}]"#;
+///
+/// let import = import_andotp(json).unwrap();
+/// assert_eq!(import.entries[0].account.label(), "rusotp:user@email.mail");
+/// assert_eq!(import.entries[0].tags, vec!["work".to_string()]);
+/// ```
+pub fn import_andotp(json: &str) -> OtpResult<BackupImport> {
+    let entries: Vec<Value> = parse_json(json)?;
+    Ok(BackupImport::collect(
+        entries,
+        |value| field(value, "label"),
+        |entry: Entry| {
+            let otp = OtpParams {
+                kind: &entry.kind,
+                secret: Secret::new_from_base32(&entry.secret)?,
+                algorithm: &entry.algorithm,
+                digits: entry.digits,
+                period: entry.period.unwrap_or(30),
+                counter: entry.counter.unwrap_or_default(),
+            }
+            .into_otp()?;
+            let (issuer, name) = split_label(entry.issuer, entry.label);
+
+            Ok(BackupEntry {
+                account: OtpAccount { issuer, name, otp },
+                tags: entry.tags,
+                note: None,
+            })
+        },
+    ))
+}
+
+/// Exports accounts as a plain andOTP JSON backup, which andOTP restores with "Restore (plain text)".
+///
+/// andOTP has no notes, so the `note` of the entries is not written.
+///
+/// # Arguments
+///
+/// * `entries` - The accounts to export.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the JSON document, or `UnsupportedRadixError` if an account uses a radix other than 10.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::backup::{export_andotp, import_andotp, BackupEntry};
+/// use rusotp::{OtpAccount, OtpKind, Secret, HOTP};
+///
+/// let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+/// let account = OtpAccount { issuer: "rusotp".to_string(), name: "counter".to_string(), otp: OtpKind::HOTP(hotp, 2) };
+/// let entries = vec![BackupEntry { account, tags: Vec::new(), note: None }];
+///
+/// let json = export_andotp(&entries).unwrap();
+/// assert_eq!(import_andotp(&json).unwrap().entries, entries);
+/// ```
+pub fn export_andotp(entries: &[BackupEntry]) -> OtpResult<String> {
+    let entries = entries
+        .iter()
+        .map(|entry| {
+            let otp = ExportedOtp::new(&entry.account.otp)?;
+            Ok(Entry {
+                secret: otp.secret,
+                issuer: entry.account.issuer.clone(),
+                label: entry.account.name.clone(),
+                digits: otp.digits,
+                kind: otp.kind.to_ascii_uppercase(),
+                algorithm: otp.algorithm,
+                period: otp.period,
+                counter: otp.counter,
+                tags: entry.tags.clone(),
+                thumbnail: Some(THUMBNAIL.to_string()),
+            })
+        })
+        .collect::<OtpResult<Vec<Entry>>>()?;
+    to_json(&entries)
+}
@@ -0,0 +1,83 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::backup::{field, parse_json, split_label, BackupEntry, BackupImport, OtpParams};
+use crate::{OtpAccount, OtpResult, Secret};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize)]
+struct FreeOtpFile {
+    tokens: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Token {
+    algo: String,
+    #[serde(default)]
+    counter: u64,
+    digits: u8,
+    #[serde(default)]
+    issuer_ext: String,
+    #[serde(default)]
+    label: String,
+    period: Option<u64>,
+    /// The raw secret, serialized by Java as signed bytes.
+    secret: Vec<i8>,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// Imports a FreeOTP+ JSON backup (`freeotp-backup.json`).
+///
+/// # Arguments
+///
+/// * `json` - The content of the backup.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the imported entries and the entries which were skipped (e.g. Steam entries), or an
+/// error if the content is not a FreeOTP+ backup.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::backup::import_freeotp_plus;
+///
+/// let json = r#"{"tokenOrder":["rusotp:user@email.mail"],"tokens":[{"algo":"SHA1","counter":0,"digits":6,
+///     "issuerExt":"rusotp","issuerInt":"rusotp","label":"user@email.mail","period":30,
+///     "secret":[49,50,51,52,53,54,55,56,57,48,49,50,51,52,53,54,55,56,57,48],"type":"TOTP"}]}"#;
+///
+/// let import = import_freeotp_plus(json).unwrap();
+/// assert_eq!(import.entries[0].account.label(), "rusotp:user@email.mail");
+/// ```
+pub fn import_freeotp_plus(json: &str) -> OtpResult<BackupImport> {
+    let file: FreeOtpFile = parse_json(json)?;
+    Ok(BackupImport::collect(
+        file.tokens,
+        |value| field(value, "label"),
+        |token: Token| {
+            let otp = OtpParams {
+                kind: &token.kind,
+                secret: Secret::new_from_vec(token.secret.iter().map(|byte| *byte as u8).collect()),
+                algorithm: &token.algo,
+                digits: token.digits,
+                period: token.period.unwrap_or(30),
+                counter: token.counter,
+            }
+            .into_otp()?;
+            let (issuer, name) = split_label(token.issuer_ext, token.label);
+
+            Ok(BackupEntry {
+                account: OtpAccount { issuer, name, otp },
+                tags: Vec::new(),
+                note: None,
+            })
+        },
+    ))
+}
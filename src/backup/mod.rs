@@ -0,0 +1,203 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! Import and export of the backup files of authenticator apps, enabled with the `backup` feature.
+//!
+//! Supported for import are the JSON exports of Aegis (plain and password encrypted `vault.json`), andOTP (plain
+//! JSON), 2FAS (`.2fas`) and FreeOTP+ (JSON), as well as lists of `otpauth://` URIs. Entries are read one by one: an
+//! entry which cannot be represented as a `HOTP`/`TOTP` account (e.g. Steam or mOTP entries) is reported in
+//! [`BackupImport::errors`] while the remaining entries are still imported.
+//!
+//! Accounts are exported as plain Aegis JSON, andOTP JSON or a newline separated list of full parameter `otpauth://`
+//! URIs, each readable by the matching importer.
+//!
+//! ```no_run
+//! use rusotp::backup::import_aegis;
+//!
+//! let json = std::fs::read_to_string("aegis-export.json").unwrap();
+//! let import = import_aegis(&json, Some("password")).unwrap();
+//! for entry in &import.entries {
+//!     println!("{} {:?}", entry.account.label(), entry.tags);
+//! }
+//! for error in &import.errors {
+//!     eprintln!("skipped {}: {}", error.name, error.error);
+//! }
+//! ```
+
+mod aegis;
+mod andotp;
+mod freeotp;
+mod twofas;
+mod uris;
+
+pub use aegis::{export_aegis, import_aegis};
+pub use andotp::{export_andotp, import_andotp};
+pub use freeotp::import_freeotp_plus;
+pub use twofas::import_2fas;
+pub use uris::{export_uris, import_uris};
+
+use crate::otp::algorithm::{Algorithm, AlgorithmTrait};
+use crate::{BackupError, OtpAccount, OtpError, OtpKind, OtpResult, Radix, Secret, UnsupportedRadixError, HOTP, TOTP};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::num::{NonZeroU64, NonZeroU8};
+
+/// An account read from or written to a backup file.
+///
+/// # Fields
+///
+/// * `account` - The issuer, account name and generator.
+/// * `tags` - The groups or tags of the entry in the app.
+/// * `note` - The free form note of the entry, if the app supports notes.
+#[derive(Debug, PartialEq)]
+pub struct BackupEntry {
+    pub account: OtpAccount,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+/// An entry of a backup file which could not be imported.
+///
+/// # Fields
+///
+/// * `index` - The position of the entry in the backup file, starting at 0.
+/// * `name` - The label of the entry, as far as it could be read.
+/// * `error` - The reason, e.g. an unsupported OTP type or an invalid secret.
+#[derive(Debug)]
+pub struct BackupEntryError {
+    pub index: usize,
+    pub name: String,
+    pub error: Box<dyn OtpError>,
+}
+
+/// The result of importing a backup file.
+///
+/// # Fields
+///
+/// * `entries` - The imported accounts, in the order of the backup file.
+/// * `errors` - The entries which were skipped.
+#[derive(Debug, Default)]
+pub struct BackupImport {
+    pub entries: Vec<BackupEntry>,
+    pub errors: Vec<BackupEntryError>,
+}
+
+pub(crate) fn error(message: &str) -> Box<dyn OtpError> {
+    Box::new(BackupError(message.to_string()))
+}
+
+pub(crate) fn parse_json<T: DeserializeOwned>(json: &str) -> OtpResult<T> {
+    serde_json::from_str(json).map_err(|e| Box::new(BackupError(e.to_string())) as _)
+}
+
+/// The generator parameters shared by all backup formats.
+pub(crate) struct OtpParams<'a> {
+    pub(crate) kind: &'a str,
+    pub(crate) secret: Secret,
+    pub(crate) algorithm: &'a str,
+    pub(crate) digits: u8,
+    pub(crate) period: u64,
+    pub(crate) counter: u64,
+}
+
+impl OtpParams<'_> {
+    pub(crate) fn into_otp(self) -> OtpResult<OtpKind> {
+        let algorithm = Algorithm::from_string(self.algorithm.to_ascii_uppercase())
+            .ok_or_else(|| Box::new(BackupError(format!("unsupported algorithm `{}`", self.algorithm))) as _)?;
+        let length = NonZeroU8::new(self.digits).ok_or_else(|| error("invalid digits"))?;
+        let radix = Radix::new(10).unwrap();
+
+        match self.kind.to_ascii_lowercase().as_str() {
+            "hotp" => Ok(OtpKind::HOTP(HOTP::new(algorithm, self.secret, length, radix), self.counter)),
+            "totp" => {
+                let interval = NonZeroU64::new(self.period).ok_or_else(|| error("invalid period"))?;
+                Ok(OtpKind::TOTP(TOTP::new(algorithm, self.secret, length, radix, interval)))
+            }
+            _ => Err(Box::new(BackupError(format!("unsupported OTP type `{}`", self.kind)))),
+        }
+    }
+}
+
+/// The generator parameters of an account, as written by the exporters.
+pub(crate) struct ExportedOtp {
+    pub(crate) kind: &'static str,
+    pub(crate) secret: String,
+    pub(crate) algorithm: String,
+    pub(crate) digits: u8,
+    pub(crate) period: Option<u64>,
+    pub(crate) counter: Option<u64>,
+}
+
+impl ExportedOtp {
+    /// Returns `UnsupportedRadixError` for generators with a radix other than 10, which no app can represent.
+    pub(crate) fn new(otp: &OtpKind) -> OtpResult<Self> {
+        let otp = otp.parameters();
+        if otp.radix.get() != 10 {
+            return Err(Box::new(UnsupportedRadixError(otp.radix.get())));
+        }
+        Ok(Self {
+            kind: otp.kind,
+            secret: otp.secret.to_base32(),
+            algorithm: otp.algorithm.to_string(),
+            digits: otp.length.get(),
+            period: otp.period,
+            counter: otp.counter,
+        })
+    }
+}
+
+impl BackupImport {
+    /// Converts every raw entry with `convert`, collecting the failures instead of aborting.
+    ///
+    /// `name` extracts a label from the raw entry for error reports, even if it does not deserialize.
+    pub(crate) fn collect<T, F>(values: Vec<Value>, name: fn(&Value) -> String, convert: F) -> Self
+    where
+        T: DeserializeOwned,
+        F: Fn(T) -> OtpResult<BackupEntry>,
+    {
+        let mut import = BackupImport::default();
+        for (index, value) in values.into_iter().enumerate() {
+            let name_of = name(&value);
+            let entry = serde_json::from_value::<T>(value)
+                .map_err(|e| Box::new(BackupError(e.to_string())) as Box<dyn OtpError>)
+                .and_then(&convert);
+            match entry {
+                Ok(entry) => import.entries.push(entry),
+                Err(error) => import.errors.push(BackupEntryError {
+                    index,
+                    name: name_of,
+                    error,
+                }),
+            }
+        }
+        import
+    }
+}
+
+pub(crate) fn to_json<T: serde::Serialize>(value: &T) -> OtpResult<String> {
+    serde_json::to_string_pretty(value).map_err(|e| Box::new(BackupError(e.to_string())) as _)
+}
+
+/// Reads a string field for error reports.
+pub(crate) fn field(value: &Value, key: &str) -> String {
+    value.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+/// Splits an `issuer:name` label if no separate issuer is known.
+pub(crate) fn split_label(issuer: String, label: String) -> (String, String) {
+    if !issuer.is_empty() {
+        let name = match label.split_once(':') {
+            Some((prefix, name)) if prefix == issuer => name.trim_start().to_string(),
+            _ => label,
+        };
+        return (issuer, name);
+    }
+    match label.split_once(':') {
+        Some((issuer, name)) => (issuer.to_string(), name.trim_start().to_string()),
+        None => (issuer, label),
+    }
+}
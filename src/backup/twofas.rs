@@ -0,0 +1,118 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::backup::{error, field, parse_json, BackupEntry, BackupImport, OtpParams};
+use crate::{OtpAccount, OtpResult, Secret};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TwoFasFile {
+    #[serde(default)]
+    services: Vec<Value>,
+    #[serde(default)]
+    groups: Vec<Group>,
+    services_encrypted: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Group {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Service {
+    name: String,
+    secret: String,
+    otp: Otp,
+    group_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Otp {
+    account: Option<String>,
+    issuer: Option<String>,
+    digits: Option<u8>,
+    period: Option<u64>,
+    algorithm: Option<String>,
+    token_type: Option<String>,
+    #[serde(default)]
+    counter: u64,
+}
+
+/// Imports a 2FAS backup (`.2fas`).
+///
+/// Backups protected with a password (`servicesEncrypted`) are not supported; export them without password.
+///
+/// # Arguments
+///
+/// * `json` - The content of the backup.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the imported entries and the entries which were skipped (e.g. Steam entries), or an
+/// error if the content is not a 2FAS backup or is encrypted.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::backup::import_2fas;
+///
+/// let json = r#"{"services":[{"name":"rusotp","secret":"GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ",
+///     "otp":{"account":"user@email.mail","issuer":"rusotp","digits":6,"period":30,"algorithm":"SHA1",
+///     "tokenType":"TOTP"},"order":{"position":0}}],"groups":[],"schemaVersion":4}"#;
+///
+/// let import = import_2fas(json).unwrap();
+/// assert_eq!(import.entries[0].account.label(), "rusotp:user@email.mail");
+/// ```
+pub fn import_2fas(json: &str) -> OtpResult<BackupImport> {
+    let file: TwoFasFile = parse_json(json)?;
+    if file.services.is_empty() && file.services_encrypted.is_some() {
+        return Err(error("encrypted 2FAS backups are not supported"));
+    }
+
+    let groups: HashMap<String, String> = file.groups.into_iter().map(|g| (g.id, g.name)).collect();
+    Ok(BackupImport::collect(
+        file.services,
+        |value| field(value, "name"),
+        |service: Service| {
+            let otp = OtpParams {
+                kind: service.otp.token_type.as_deref().unwrap_or("TOTP"),
+                secret: Secret::new_from_base32(&service.secret)?,
+                algorithm: service.otp.algorithm.as_deref().unwrap_or("SHA1"),
+                digits: service.otp.digits.unwrap_or(6),
+                period: service.otp.period.unwrap_or(30),
+                counter: service.otp.counter,
+            }
+            .into_otp()?;
+            let issuer = service
+                .otp
+                .issuer
+                .filter(|issuer| !issuer.is_empty())
+                .unwrap_or(service.name);
+
+            Ok(BackupEntry {
+                account: OtpAccount {
+                    issuer,
+                    name: service.otp.account.unwrap_or_default(),
+                    otp,
+                },
+                tags: service
+                    .group_id
+                    .and_then(|id| groups.get(&id).cloned())
+                    .into_iter()
+                    .collect(),
+                note: None,
+            })
+        },
+    ))
+}
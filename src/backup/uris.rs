@@ -0,0 +1,105 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::backup::{BackupEntry, BackupEntryError, BackupImport};
+use crate::{OtpAccount, OtpResult};
+
+/// Imports a list of `otpauth://` and `otpauth-migration://` URIs, one per line.
+///
+/// Blank lines and lines starting with `#` are skipped; the `index` of an error is the line number, starting at 0. The
+/// `name` of an error is the decoded label of the URI, or its scheme, so that secrets are not reported.
+///
+/// # Arguments
+///
+/// * `text` - The list of URIs.
+///
+/// # Returns
+///
+/// The imported entries, without tags or notes, and the lines which could not be parsed.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::backup::import_uris;
+///
+/// let text = "otpauth://totp/rusotp%3Auser?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=rusotp\nnot a uri\n";
+/// let import = import_uris(text);
+///
+/// assert_eq!(import.entries[0].account.label(), "rusotp:user");
+/// assert_eq!(import.errors[0].index, 1);
+/// ```
+pub fn import_uris(text: &str) -> BackupImport {
+    let mut import = BackupImport::default();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match OtpAccount::parse(line) {
+            Ok(accounts) => import.entries.extend(accounts.into_iter().map(|account| BackupEntry {
+                account,
+                tags: Vec::new(),
+                note: None,
+            })),
+            Err(error) => import.errors.push(BackupEntryError {
+                index,
+                name: uri_label(line),
+                error,
+            }),
+        }
+    }
+    import
+}
+
+/// Returns the decoded label of an `otpauth://` URI, or `scheme://…` for a URI without label, e.g. a migration URI.
+fn uri_label(uri: &str) -> String {
+    let (scheme, rest) = uri.split_once("://").unwrap_or(("", uri));
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    match path.split_once('/') {
+        Some((_, label)) if !label.is_empty() => urlencoding::decode(label)
+            .map(|label| label.into_owned())
+            .unwrap_or_else(|_| label.to_string()),
+        _ => format!("{}://…", scheme),
+    }
+}
+
+/// Exports accounts as a newline separated list of full parameter `otpauth://` URIs, see [`OtpAccount::to_uri`].
+///
+/// Tags and notes are not written.
+///
+/// # Arguments
+///
+/// * `entries` - The accounts to export.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the list, ending with a newline, or `UnsupportedRadixError` if an account uses a radix
+/// other than 10.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::backup::{export_uris, BackupEntry};
+/// use rusotp::{OtpAccount, OtpKind, Secret, TOTP};
+///
+/// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+/// let account = OtpAccount { issuer: "rusotp".to_string(), name: "user".to_string(), otp: OtpKind::TOTP(totp) };
+///
+/// let text = export_uris(&[BackupEntry { account, tags: Vec::new(), note: None }]).unwrap();
+/// assert_eq!(
+///     text,
+///     "otpauth://totp/rusotp%3Auser?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=rusotp&algorithm=SHA1&digits=6&period=30\n"
+/// );
+/// ```
+pub fn export_uris(entries: &[BackupEntry]) -> OtpResult<String> {
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&entry.account.to_uri()?);
+        text.push('\n');
+    }
+    Ok(text)
+}
@@ -0,0 +1,356 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::cli::Args;
+use crate::error::{ApiError, ApiResult};
+use crate::store::{AccountStore, StoredAccount};
+use rand_core::{OsRng, RngCore};
+use rusotp::throttle::{Throttle, ThrottleConfig};
+use rusotp::{Algorithm, AlgorithmTrait, OtpAccount, OtpKind, ProvisioningQr, Radix, Secret, HOTP, TOTP};
+use serde_json::{json, Value};
+use std::num::{NonZeroU64, NonZeroU8};
+
+/// Length in bytes of generated secrets, the 160 bits recommended by RFC 4226.
+const SECRET_LENGTH: usize = 20;
+
+/// Answer to a request.
+///
+/// # Fields
+///
+/// * `status` - The HTTP status code.
+/// * `body` - The JSON body, `None` for `204 No Content`.
+/// * `retry_after` - Seconds until a rate limited account may be tried again, sent as `Retry-After`.
+#[derive(Debug)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Option<Value>,
+    pub retry_after: Option<u64>,
+}
+
+impl ApiResponse {
+    fn json(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            body: Some(body),
+            retry_after: None,
+        }
+    }
+}
+
+impl From<ApiError> for ApiResponse {
+    fn from(error: ApiError) -> Self {
+        let retry_after = match error {
+            ApiError::RateLimited(retry_after) => Some(retry_after),
+            _ => None,
+        };
+        Self {
+            status: error.status(),
+            body: Some(error.body()),
+            retry_after,
+        }
+    }
+}
+
+/// The REST endpoints, backed by an [`AccountStore`].
+pub struct Api {
+    store: Box<dyn AccountStore>,
+    throttle: Throttle,
+    token: String,
+    issuer: String,
+    window: u64,
+    look_ahead: u64,
+    resync_window: u64,
+}
+
+impl Api {
+    pub fn new(store: Box<dyn AccountStore>, args: &Args) -> Self {
+        Self {
+            store,
+            throttle: Throttle::new(ThrottleConfig {
+                max_failures: args.max_failures,
+                backoff: args.backoff,
+                max_backoff: args.max_backoff,
+                lockout: args.lockout,
+            }),
+            token: args.token.clone(),
+            issuer: args.issuer.clone(),
+            window: args.window,
+            look_ahead: args.look_ahead,
+            resync_window: args.resync_window,
+        }
+    }
+
+    /// Routes a request to its endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `authorization` - The `Authorization` header, required by the enroll, resync and delete endpoints.
+    /// * `now` - The current Unix timestamp.
+    pub fn handle(
+        &mut self,
+        method: &str,
+        url: &str,
+        authorization: Option<&str>,
+        body: &str,
+        now: u64,
+    ) -> ApiResponse {
+        self.route(method, url, authorization, body, now)
+            .unwrap_or_else(ApiResponse::from)
+    }
+
+    fn route(
+        &mut self,
+        method: &str,
+        url: &str,
+        authorization: Option<&str>,
+        body: &str,
+        now: u64,
+    ) -> ApiResult<ApiResponse> {
+        let path = url.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (method, segments.as_slice()) {
+            ("GET", ["health"]) => Ok(ApiResponse::json(200, json!({ "status": "ok" }))),
+            ("POST", ["accounts"]) => {
+                self.authorize(authorization)?;
+                self.enroll(&parse(body)?)
+            }
+            ("POST", ["accounts", id, "verify"]) => self.verify(&decode(id)?, &parse(body)?, now),
+            ("POST", ["accounts", id, "resync"]) => {
+                self.authorize(authorization)?;
+                self.resync(&decode(id)?, &parse(body)?, now)
+            }
+            ("DELETE", ["accounts", id]) => {
+                self.authorize(authorization)?;
+                self.delete(&decode(id)?)
+            }
+            (_, ["health"] | ["accounts"] | ["accounts", _] | ["accounts", _, "verify" | "resync"]) => {
+                Err(ApiError::MethodNotAllowed)
+            }
+            _ => Err(ApiError::NotFound(format!("no endpoint `{}`", path))),
+        }
+    }
+
+    /// Checks that `authorization` carries the bearer token of the server, comparing it in constant time.
+    fn authorize(&self, authorization: Option<&str>) -> ApiResult<()> {
+        let token = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or_default()
+            .as_bytes();
+        let expected = self.token.as_bytes();
+        let difference = token
+            .iter()
+            .zip(expected)
+            .fold(token.len() ^ expected.len(), |difference, (a, b)| difference | usize::from(a ^ b));
+        match difference {
+            0 => Ok(()),
+            _ => Err(ApiError::Unauthorized),
+        }
+    }
+
+    /// `POST /accounts`: creates an account, with a random secret unless one is given.
+    fn enroll(&mut self, body: &Value) -> ApiResult<ApiResponse> {
+        let id = string(body, "id")?
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| ApiError::BadRequest("`id` is required".to_string()))?;
+        let algorithm = match string(body, "algorithm")? {
+            Some(name) => Algorithm::from_string(name.to_uppercase())
+                .ok_or_else(|| ApiError::BadRequest(format!("unsupported algorithm `{}`", name)))?,
+            None => Algorithm::SHA1,
+        };
+        let digits = number(body, "digits")?.unwrap_or(6);
+        let length = u8::try_from(digits)
+            .ok()
+            .and_then(NonZeroU8::new)
+            .ok_or_else(|| ApiError::BadRequest(format!("unsupported number of digits {}", digits)))?;
+        let secret = match string(body, "secret")? {
+            Some(secret) => Secret::new_from_base32(&secret)?,
+            None => {
+                let mut secret = vec![0u8; SECRET_LENGTH];
+                OsRng.fill_bytes(&mut secret);
+                Secret::new_from_vec(secret)
+            }
+        };
+        let radix = Radix::default();
+
+        let otp = match string(body, "type")?.as_deref() {
+            None | Some("totp") => {
+                let period = number(body, "period")?.unwrap_or(30);
+                let interval = NonZeroU64::new(period)
+                    .ok_or_else(|| ApiError::BadRequest("`period` must be greater than 0".to_string()))?;
+                let totp = TOTP::new(algorithm, secret, length, radix, interval);
+                totp.generate_at(0)?;
+                OtpKind::TOTP(totp)
+            }
+            Some("hotp") => {
+                let counter = number(body, "counter")?.unwrap_or(0);
+                let hotp = HOTP::new(algorithm, secret, length, radix);
+                hotp.generate(counter)?;
+                OtpKind::HOTP(hotp, counter)
+            }
+            Some(other) => return Err(ApiError::BadRequest(format!("unsupported type `{}`", other))),
+        };
+        let account = OtpAccount {
+            issuer: string(body, "issuer")?.unwrap_or_else(|| self.issuer.clone()),
+            name: string(body, "name")?.unwrap_or_else(|| id.clone()),
+            otp,
+        };
+
+        let uri = account.to_uri()?;
+        let qr_svg = ProvisioningQr::new(uri.clone()).svg()?;
+        let secret = match &account.otp {
+            OtpKind::HOTP(hotp, _) => hotp.secret().to_base32(),
+            OtpKind::TOTP(totp) => totp.secret().to_base32(),
+        };
+        if !self.store.insert(&id, StoredAccount::new(account))? {
+            return Err(ApiError::Conflict(format!("account `{}` is already enrolled", id)));
+        }
+        Ok(ApiResponse::json(201, json!({ "id": id, "secret": secret, "uri": uri, "qr_svg": qr_svg })))
+    }
+
+    /// `POST /accounts/{id}/verify`: checks `code`, advancing the HOTP counter or the last used TOTP time step.
+    fn verify(&mut self, id: &str, body: &Value, now: u64) -> ApiResult<ApiResponse> {
+        let mut stored = self.account(id)?;
+        let code = string(body, "code")?.ok_or_else(|| ApiError::BadRequest("`code` is required".to_string()))?;
+        self.throttle.check(id)?;
+
+        let matched = match &stored.account.otp {
+            OtpKind::HOTP(hotp, counter) => self.verify_hotp(hotp, *counter, &code)?,
+            OtpKind::TOTP(totp) => self.verify_totp(totp, &stored, &code, now)?,
+        };
+        let Some(matched) = matched else {
+            self.throttle.record_failure(id);
+            return Ok(ApiResponse::json(200, json!({ "valid": false })));
+        };
+
+        match &mut stored.account.otp {
+            OtpKind::HOTP(_, counter) => *counter = matched + 1,
+            OtpKind::TOTP(_) => stored.last_step = Some(matched),
+        }
+        self.store.update(id, stored)?;
+        self.throttle.record_success(id);
+        Ok(ApiResponse::json(200, json!({ "valid": true })))
+    }
+
+    /// Returns the matched counter, failing with `Replayed` for the code of the previous counter.
+    fn verify_hotp(&self, hotp: &HOTP, counter: u64, code: &str) -> ApiResult<Option<u64>> {
+        match hotp.verify(code, counter, self.look_ahead)? {
+            Some(matched) => Ok(Some(matched)),
+            None if counter > 0 && hotp.generate(counter - 1)? == code => Err(ApiError::Replayed),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the matched time step, failing with `Replayed` if it is not after the last accepted one.
+    fn verify_totp(&self, totp: &TOTP, stored: &StoredAccount, code: &str, now: u64) -> ApiResult<Option<u64>> {
+        let interval = totp.interval().get();
+        let at = now.saturating_add_signed(stored.drift.saturating_mul(interval as i64));
+        let window = self.window * interval;
+        match totp.verify_at(code, at, None, window, window.min(at.saturating_sub(1)))? {
+            Some(timestamp) if stored.last_step.is_some_and(|last| timestamp / interval <= last) => {
+                Err(ApiError::Replayed)
+            }
+            Some(timestamp) => Ok(Some(timestamp / interval)),
+            None => Ok(None),
+        }
+    }
+
+    /// `POST /accounts/{id}/resync`: searches two consecutive `codes` further away than `verify` does, then stores
+    /// the HOTP counter following them, or the TOTP clock drift of the authenticator.
+    fn resync(&mut self, id: &str, body: &Value, now: u64) -> ApiResult<ApiResponse> {
+        let mut stored = self.account(id)?;
+        let codes: Vec<&str> = body["codes"]
+            .as_array()
+            .map(|codes| codes.iter().filter_map(Value::as_str).collect())
+            .filter(|codes: &Vec<&str>| codes.len() == 2)
+            .ok_or_else(|| ApiError::BadRequest("`codes` must hold two consecutive codes".to_string()))?;
+        self.throttle.check(id)?;
+
+        let matched = match &stored.account.otp {
+            OtpKind::HOTP(hotp, counter) => hotp.resync(codes[0], codes[1], *counter, self.resync_window)?,
+            OtpKind::TOTP(totp) => {
+                let after = stored
+                    .last_step
+                    .map(|last| (last + 1).saturating_mul(totp.interval().get()));
+                totp.resync_at(codes[0], codes[1], now, after, self.resync_window)?
+            }
+        };
+        let Some(matched) = matched else {
+            self.throttle.record_failure(id);
+            return Ok(ApiResponse::json(200, json!({ "synchronized": false })));
+        };
+
+        let response = match &mut stored.account.otp {
+            OtpKind::HOTP(_, counter) => {
+                *counter = matched + 1;
+                json!({ "synchronized": true, "counter": matched + 1 })
+            }
+            OtpKind::TOTP(totp) => {
+                stored.drift = matched as i64 - (now / totp.interval().get()) as i64;
+                stored.last_step = Some(matched);
+                json!({ "synchronized": true, "drift": stored.drift })
+            }
+        };
+        self.store.update(id, stored)?;
+        self.throttle.record_success(id);
+        Ok(ApiResponse::json(200, response))
+    }
+
+    /// `DELETE /accounts/{id}`: removes the account.
+    fn delete(&mut self, id: &str) -> ApiResult<ApiResponse> {
+        if !self.store.remove(id)? {
+            return Err(not_enrolled(id));
+        }
+        self.throttle.record_success(id);
+        Ok(ApiResponse {
+            status: 204,
+            body: None,
+            retry_after: None,
+        })
+    }
+
+    fn account(&self, id: &str) -> ApiResult<StoredAccount> {
+        self.store.get(id)?.ok_or_else(|| not_enrolled(id))
+    }
+}
+
+fn not_enrolled(id: &str) -> ApiError {
+    ApiError::NotFound(format!("account `{}` is not enrolled", id))
+}
+
+/// Parses a request body, which must be a JSON object.
+fn parse(body: &str) -> ApiResult<Value> {
+    match serde_json::from_str(body) {
+        Ok(value @ Value::Object(_)) => Ok(value),
+        Ok(_) => Err(ApiError::BadRequest("the body must be a JSON object".to_string())),
+        Err(e) => Err(ApiError::BadRequest(format!("invalid JSON body: {}", e))),
+    }
+}
+
+/// Percent-decodes an account id taken from the path.
+fn decode(id: &str) -> ApiResult<String> {
+    urlencoding::decode(id)
+        .map(|id| id.into_owned())
+        .map_err(|_| ApiError::BadRequest(format!("invalid account id `{}`", id)))
+}
+
+fn string(body: &Value, key: &str) -> ApiResult<Option<String>> {
+    match &body[key] {
+        Value::Null => Ok(None),
+        Value::String(value) => Ok(Some(value.clone())),
+        _ => Err(ApiError::BadRequest(format!("`{}` must be a string", key))),
+    }
+}
+
+fn number(body: &Value, key: &str) -> ApiResult<Option<u64>> {
+    match &body[key] {
+        Value::Null => Ok(None),
+        value => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| ApiError::BadRequest(format!("`{}` must be a non-negative integer", key))),
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use clap::Parser;
+use std::path::PathBuf;
+
+/// HTTP service enrolling HOTP/TOTP accounts and verifying their one-time passwords.
+///
+/// Endpoints: `POST /accounts`, `POST /accounts/{id}/verify`, `POST /accounts/{id}/resync`,
+/// `DELETE /accounts/{id}` and `GET /health`. Requests and responses are JSON.
+#[derive(Debug, Parser)]
+#[command(name = "rusotp-server", version)]
+pub struct Args {
+    /// Address to listen on; with port 0 a free port is picked and printed
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub listen: String,
+
+    /// Bearer token required in the `Authorization` header of the enroll, resync and delete requests
+    #[arg(long)]
+    pub token: String,
+
+    /// JSON file to persist the accounts to; accounts are kept in memory only if not given
+    #[arg(long)]
+    pub store: Option<PathBuf>,
+
+    /// Issuer of enrolled accounts which do not name one
+    #[arg(long, default_value = "rusotp")]
+    pub issuer: String,
+
+    /// Number of TOTP time steps before and after the current one to accept
+    #[arg(long, default_value_t = 1)]
+    pub window: u64,
+
+    /// Number of HOTP counters after the expected one to accept
+    #[arg(long, default_value_t = 10)]
+    pub look_ahead: u64,
+
+    /// Number of HOTP counters, or TOTP time steps in both directions, searched by a resync
+    #[arg(long, default_value_t = 100)]
+    pub resync_window: u64,
+
+    /// Number of consecutive failed attempts after which an account is locked, 0 to never lock
+    #[arg(long, default_value_t = 5)]
+    pub max_failures: u32,
+
+    /// Number of seconds to wait after a failed attempt, doubling with each further one; 0 to disable
+    #[arg(long, default_value_t = 1)]
+    pub backoff: u64,
+
+    /// Maximum number of seconds to wait after a failed attempt
+    #[arg(long, default_value_t = 60)]
+    pub max_backoff: u64,
+
+    /// Number of seconds an account stays locked
+    #[arg(long, default_value_t = 900)]
+    pub lockout: u64,
+}
@@ -0,0 +1,101 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use rusotp::OtpError;
+use serde_json::{json, Value};
+use std::fmt::{Display, Formatter};
+
+/// Error of a request, answered with its HTTP status and a JSON body `{"error": <kind>, "message": <text>}`.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The body is malformed or the OTP parameters are not supported, e.g. an `OtpError` of the library.
+    BadRequest(String),
+    /// The request needs the bearer token given to the server and has none or another one.
+    Unauthorized,
+    NotFound(String),
+    MethodNotAllowed,
+    /// The body is longer than the server accepts.
+    PayloadTooLarge(u64),
+    /// An account with the same id is already enrolled.
+    Conflict(String),
+    /// The code was already accepted for its time step or counter.
+    Replayed,
+    /// Too many failed attempts; the account may be tried again after the given number of seconds.
+    RateLimited(u64),
+    /// The account store failed.
+    Internal(String),
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+impl ApiError {
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::BadRequest(_) => 400,
+            ApiError::Unauthorized => 401,
+            ApiError::NotFound(_) => 404,
+            ApiError::MethodNotAllowed => 405,
+            ApiError::Conflict(_) | ApiError::Replayed => 409,
+            ApiError::PayloadTooLarge(_) => 413,
+            ApiError::RateLimited(_) => 429,
+            ApiError::Internal(_) => 500,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::MethodNotAllowed => "method_not_allowed",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Replayed => "replayed",
+            ApiError::RateLimited(_) => "rate_limited",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn body(&self) -> Value {
+        let mut body = json!({ "error": self.kind(), "message": self.to_string() });
+        if let ApiError::RateLimited(retry_after) = self {
+            body["retry_after"] = json!(retry_after);
+        }
+        body
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::BadRequest(message)
+            | ApiError::NotFound(message)
+            | ApiError::Conflict(message)
+            | ApiError::Internal(message) => write!(f, "{}", message),
+            ApiError::Unauthorized => write!(f, "a valid bearer token is required"),
+            ApiError::MethodNotAllowed => write!(f, "method not allowed"),
+            ApiError::PayloadTooLarge(limit) => write!(f, "the body must not exceed {} bytes", limit),
+            ApiError::Replayed => write!(f, "the code was already used"),
+            ApiError::RateLimited(retry_after) => write!(f, "too many failed attempts, retry in {}s", retry_after),
+        }
+    }
+}
+
+impl From<Box<dyn OtpError>> for ApiError {
+    fn from(error: Box<dyn OtpError>) -> Self {
+        match error.retry_after() {
+            Some(retry_after) => ApiError::RateLimited(retry_after),
+            None => ApiError::BadRequest(error.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(error: std::io::Error) -> Self {
+        ApiError::Internal(error.to_string())
+    }
+}
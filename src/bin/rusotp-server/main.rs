@@ -0,0 +1,103 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+mod api;
+mod cli;
+mod error;
+mod store;
+
+use crate::api::{Api, ApiResponse};
+use crate::error::ApiError;
+use crate::store::{AccountStore, FileAccountStore, MemoryAccountStore};
+use clap::Parser;
+use rusotp::throttle::{Clock, SystemClock};
+use std::io::{Cursor, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::process::ExitCode;
+use tiny_http::{Header, Request, Response, Server};
+
+/// The longest request body accepted, in bytes.
+const MAX_BODY_LENGTH: u64 = 64 * 1024;
+
+fn main() -> ExitCode {
+    let args = cli::Args::parse();
+
+    let store: Box<dyn AccountStore> = match &args.store {
+        Some(path) => match FileAccountStore::open(path) {
+            Ok(store) => Box::new(store),
+            Err(error) => {
+                eprintln!("rusotp-server: {}", error);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(MemoryAccountStore::new()),
+    };
+    let server = match Server::http(&args.listen) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("rusotp-server: {}: {}", args.listen, error);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("listening on http://{}", server.server_addr());
+
+    let mut api = Api::new(store, &args);
+    for mut request in server.incoming_requests() {
+        let authorization = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Authorization"))
+            .map(|header| header.value.to_string());
+        let response = match read_body(&mut request) {
+            Ok(body) => {
+                let (method, url) = (request.method().to_string(), request.url().to_string());
+                // A bug in a handler answers its request with an error instead of stopping the server.
+                panic::catch_unwind(AssertUnwindSafe(|| {
+                    api.handle(&method, &url, authorization.as_deref(), &body, SystemClock.now())
+                }))
+                .unwrap_or_else(|_| ApiError::Internal("the request could not be handled".to_string()).into())
+            }
+            Err(error) => error.into(),
+        };
+        if let Err(error) = request.respond(http_response(response)) {
+            eprintln!("rusotp-server: {}", error);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Reads the body of a request, rejecting bodies longer than `MAX_BODY_LENGTH` without buffering them.
+fn read_body(request: &mut Request) -> Result<String, ApiError> {
+    if request
+        .body_length()
+        .is_some_and(|length| length as u64 > MAX_BODY_LENGTH)
+    {
+        return Err(ApiError::PayloadTooLarge(MAX_BODY_LENGTH));
+    }
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_LENGTH + 1)
+        .read_to_string(&mut body)
+        .map_err(|error| ApiError::BadRequest(format!("unreadable body: {}", error)))?;
+    if body.len() as u64 > MAX_BODY_LENGTH {
+        return Err(ApiError::PayloadTooLarge(MAX_BODY_LENGTH));
+    }
+    Ok(body)
+}
+
+fn http_response(response: ApiResponse) -> Response<Cursor<Vec<u8>>> {
+    let body = response.body.map(|body| body.to_string()).unwrap_or_default();
+    let mut http = Response::from_string(body).with_status_code(response.status);
+    if response.status != 204 {
+        http.add_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap());
+    }
+    if let Some(retry_after) = response.retry_after {
+        http.add_header(Header::from_bytes(&b"Retry-After"[..], retry_after.to_string().as_bytes()).unwrap());
+    }
+    http
+}
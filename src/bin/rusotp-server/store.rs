@@ -0,0 +1,188 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::error::{ApiError, ApiResult};
+use rusotp::OtpAccount;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// An enrolled account together with the verification state kept for it.
+///
+/// # Fields
+///
+/// * `account` - The account; for HOTP its counter is the next counter expected from the token.
+/// * `last_step` - The last accepted TOTP time step, codes of this step or earlier are replays.
+/// * `drift` - The TOTP clock drift of the authenticator in time steps, as measured by a resync.
+#[derive(Debug, Clone)]
+pub struct StoredAccount {
+    pub account: OtpAccount,
+    pub last_step: Option<u64>,
+    pub drift: i64,
+}
+
+impl StoredAccount {
+    pub fn new(account: OtpAccount) -> Self {
+        Self {
+            account,
+            last_step: None,
+            drift: 0,
+        }
+    }
+}
+
+/// Persistence of the enrolled accounts, keyed by the id chosen at enrollment.
+///
+/// Requests are served one at a time, so implementations need no locking of their own.
+pub trait AccountStore: Send {
+    /// Returns the account `id`, `None` if it is not enrolled.
+    fn get(&self, id: &str) -> ApiResult<Option<StoredAccount>>;
+
+    /// Adds the account `id`, returning `false` without changes if it is already enrolled.
+    fn insert(&mut self, id: &str, account: StoredAccount) -> ApiResult<bool>;
+
+    /// Replaces the enrolled account `id`, e.g. after its counter advanced.
+    fn update(&mut self, id: &str, account: StoredAccount) -> ApiResult<()>;
+
+    /// Removes the account `id`, returning `false` if it was not enrolled.
+    fn remove(&mut self, id: &str) -> ApiResult<bool>;
+}
+
+/// An [`AccountStore`] keeping the accounts in memory; they are lost when the server stops.
+#[derive(Debug, Default)]
+pub struct MemoryAccountStore {
+    accounts: HashMap<String, StoredAccount>,
+}
+
+impl MemoryAccountStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AccountStore for MemoryAccountStore {
+    fn get(&self, id: &str) -> ApiResult<Option<StoredAccount>> {
+        Ok(self.accounts.get(id).cloned())
+    }
+
+    fn insert(&mut self, id: &str, account: StoredAccount) -> ApiResult<bool> {
+        if self.accounts.contains_key(id) {
+            return Ok(false);
+        }
+        self.accounts.insert(id.to_string(), account);
+        Ok(true)
+    }
+
+    fn update(&mut self, id: &str, account: StoredAccount) -> ApiResult<()> {
+        self.accounts.insert(id.to_string(), account);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> ApiResult<bool> {
+        Ok(self.accounts.remove(id).is_some())
+    }
+}
+
+/// An [`AccountStore`] persisting the accounts to a JSON file, rewritten after every change.
+///
+/// The file maps each id to `{"uri": ..., "last_step": ..., "drift": ...}`, where `uri` is the full `otpauth` URI of
+/// the account. It contains the secrets in plain text and is created readable by the owner only.
+#[derive(Debug)]
+pub struct FileAccountStore {
+    path: PathBuf,
+    accounts: MemoryAccountStore,
+}
+
+impl FileAccountStore {
+    /// Loads the accounts of `path`, starting empty if the file does not exist yet.
+    pub fn open(path: &Path) -> ApiResult<Self> {
+        let mut accounts = MemoryAccountStore::new();
+        if path.exists() {
+            let invalid = |message: String| ApiError::Internal(format!("{}: {}", path.display(), message));
+            let content = fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+            let records: Map<String, Value> = serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+            for (id, record) in records {
+                let account = OtpAccount::from_uri(record["uri"].as_str().unwrap_or_default())
+                    .map_err(|e| invalid(format!("account `{}`: {}", id, e)))?;
+                let stored = StoredAccount {
+                    account,
+                    last_step: record["last_step"].as_u64(),
+                    drift: record["drift"].as_i64().unwrap_or_default(),
+                };
+                accounts.update(&id, stored)?;
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            accounts,
+        })
+    }
+
+    /// Atomically replaces the file with the current accounts.
+    fn save(&self) -> ApiResult<()> {
+        let mut records = Map::new();
+        for (id, stored) in &self.accounts.accounts {
+            let record = json!({
+                "uri": stored.account.to_uri()?,
+                "last_step": stored.last_step,
+                "drift": stored.drift,
+            });
+            records.insert(id.clone(), record);
+        }
+        let content =
+            serde_json::to_vec_pretty(&Value::Object(records)).map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temporary)?;
+        file.write_all(&content)
+            .and_then(|_| file.sync_all())
+            .and_then(|_| fs::rename(&temporary, &self.path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temporary);
+                ApiError::Internal(format!("{}: {}", self.path.display(), e))
+            })
+    }
+}
+
+impl AccountStore for FileAccountStore {
+    fn get(&self, id: &str) -> ApiResult<Option<StoredAccount>> {
+        self.accounts.get(id)
+    }
+
+    fn insert(&mut self, id: &str, account: StoredAccount) -> ApiResult<bool> {
+        let inserted = self.accounts.insert(id, account)?;
+        if inserted {
+            self.save()?;
+        }
+        Ok(inserted)
+    }
+
+    fn update(&mut self, id: &str, account: StoredAccount) -> ApiResult<()> {
+        self.accounts.update(id, account)?;
+        self.save()
+    }
+
+    fn remove(&mut self, id: &str) -> ApiResult<bool> {
+        let removed = self.accounts.remove(id)?;
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rusotp::Algorithm;
+use std::path::PathBuf;
+
+/// Generate, verify and enroll HOTP/TOTP one-time passwords.
+///
+/// Exit codes: 0 success, 1 OTP rejected by `verify`, 2 invalid usage, 3 invalid input, 4 I/O error.
+#[derive(Debug, Parser)]
+#[command(name = "rusotp", version)]
+pub struct Cli {
+    /// Print machine-readable JSON instead of plain text
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Generate an OTP
+    Generate {
+        #[command(flatten)]
+        otp: OtpArgs,
+        #[command(flatten)]
+        secret: SecretArgs,
+        /// Unix timestamp to generate a TOTP for, instead of the current time
+        #[arg(long)]
+        timestamp: Option<u64>,
+    },
+    /// Verify an OTP
+    Verify {
+        /// The OTP to verify
+        code: String,
+        #[command(flatten)]
+        otp: OtpArgs,
+        #[command(flatten)]
+        secret: SecretArgs,
+        /// Unix timestamp to verify a TOTP at, instead of the current time
+        #[arg(long)]
+        timestamp: Option<u64>,
+        /// Number of following counters to try (HOTP)
+        #[arg(long, default_value_t = 0)]
+        retries: u64,
+        /// Number of time steps ahead of the current time to accept (TOTP)
+        #[arg(long, default_value_t = 0)]
+        drift_ahead: u64,
+        /// Number of time steps behind the current time to accept (TOTP)
+        #[arg(long, default_value_t = 0)]
+        drift_behind: u64,
+        /// Reject OTPs of time steps at or before this timestamp, e.g. the last successful login (TOTP)
+        #[arg(long)]
+        after: Option<u64>,
+    },
+    /// Print the provisioning URI of an account
+    Uri {
+        #[command(flatten)]
+        otp: OtpArgs,
+        #[command(flatten)]
+        secret: SecretArgs,
+        #[command(flatten)]
+        label: LabelArgs,
+    },
+    /// Render the provisioning QR code of an account
+    Qr {
+        #[command(flatten)]
+        otp: OtpArgs,
+        #[command(flatten)]
+        secret: SecretArgs,
+        #[command(flatten)]
+        label: LabelArgs,
+        /// Output format of the QR code
+        #[arg(long, value_enum, default_value_t = QrFormat::Terminal)]
+        format: QrFormat,
+        /// Write the QR code to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Parse an otpauth:// or otpauth-migration:// URI
+    ParseUri {
+        /// The URI to parse
+        uri: String,
+    },
+    /// Read accounts from a file and print them as otpauth:// URIs
+    Import {
+        /// File to read, `-` for stdin
+        #[arg(default_value = "-")]
+        file: PathBuf,
+        /// Format of the file
+        #[arg(long, value_enum, default_value_t = ImportFormat::Uris)]
+        format: ImportFormat,
+    },
+    /// Read otpauth:// URIs, one per line, and write them in another format
+    Export {
+        /// File to read, `-` for stdin
+        #[arg(default_value = "-")]
+        file: PathBuf,
+        /// Format to write
+        #[arg(long, value_enum, default_value_t = ExportFormat::Uris)]
+        format: ExportFormat,
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct OtpArgs {
+    /// Type of the OTP
+    #[arg(long = "type", value_enum, default_value_t = OtpType::Totp)]
+    pub kind: OtpType,
+    /// Hashing algorithm
+    #[arg(long, value_enum, default_value_t = AlgorithmArg::Sha1)]
+    pub algorithm: AlgorithmArg,
+    /// Number of digits of the OTP
+    #[arg(long, default_value_t = 6, value_parser = clap::value_parser!(u8).range(1..))]
+    pub length: u8,
+    /// Radix (base) of the OTP, between 2 and 36
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(2..=36))]
+    pub radix: u8,
+    /// Time step in seconds (TOTP)
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+    /// Counter (HOTP)
+    #[arg(long, default_value_t = 0)]
+    pub counter: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct SecretArgs {
+    /// Read the secret from this environment variable instead of stdin
+    #[arg(long, value_name = "VAR", conflicts_with = "secret_file")]
+    pub secret_env: Option<String>,
+    /// Read the secret from this file instead of stdin
+    #[arg(long, value_name = "PATH")]
+    pub secret_file: Option<PathBuf>,
+    /// Encoding of the secret
+    #[arg(long, value_enum, default_value_t = SecretEncoding::Base32)]
+    pub secret_encoding: SecretEncoding,
+}
+
+#[derive(Debug, Args)]
+pub struct LabelArgs {
+    /// Issuer of the account
+    #[arg(long)]
+    pub issuer: String,
+    /// Name of the user or account
+    #[arg(long)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OtpType {
+    Hotp,
+    Totp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum AlgorithmArg {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    #[cfg(feature = "sha3")]
+    #[value(name = "sha3-224")]
+    Sha3_224,
+    #[cfg(feature = "sha3")]
+    #[value(name = "sha3-256")]
+    Sha3_256,
+    #[cfg(feature = "sha3")]
+    #[value(name = "sha3-384")]
+    Sha3_384,
+    #[cfg(feature = "sha3")]
+    #[value(name = "sha3-512")]
+    Sha3_512,
+    #[cfg(feature = "blake2")]
+    Blake2b,
+    #[cfg(feature = "blake2")]
+    Blake2s,
+}
+
+impl From<AlgorithmArg> for Algorithm {
+    fn from(algorithm: AlgorithmArg) -> Self {
+        match algorithm {
+            AlgorithmArg::Sha1 => Algorithm::SHA1,
+            AlgorithmArg::Sha224 => Algorithm::SHA224,
+            AlgorithmArg::Sha256 => Algorithm::SHA256,
+            AlgorithmArg::Sha384 => Algorithm::SHA384,
+            AlgorithmArg::Sha512 => Algorithm::SHA512,
+            #[cfg(feature = "sha3")]
+            AlgorithmArg::Sha3_224 => Algorithm::SHA3_224,
+            #[cfg(feature = "sha3")]
+            AlgorithmArg::Sha3_256 => Algorithm::SHA3_256,
+            #[cfg(feature = "sha3")]
+            AlgorithmArg::Sha3_384 => Algorithm::SHA3_384,
+            #[cfg(feature = "sha3")]
+            AlgorithmArg::Sha3_512 => Algorithm::SHA3_512,
+            #[cfg(feature = "blake2")]
+            AlgorithmArg::Blake2b => Algorithm::BLAKE2B512,
+            #[cfg(feature = "blake2")]
+            AlgorithmArg::Blake2s => Algorithm::BLAKE2S256,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SecretEncoding {
+    Base32,
+    Hex,
+    Raw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum QrFormat {
+    Terminal,
+    Svg,
+    #[cfg(feature = "qr-image")]
+    Png,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ImportFormat {
+    /// otpauth:// and otpauth-migration:// URIs, one per line
+    Uris,
+    /// Image containing otpauth QR codes
+    #[cfg(feature = "qr-decode")]
+    Qr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    /// otpauth:// URIs, one per line
+    Uris,
+    /// JSON array of accounts
+    Json,
+}
@@ -0,0 +1,184 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::cli::{Command, ExportFormat, ImportFormat, LabelArgs, OtpArgs, OtpType, QrFormat};
+use crate::error::{CliError, CliResult, EXIT_REJECTED};
+use crate::input::{parse_uri_list, read_secret, read_text};
+use crate::output::{account_json, account_text, print, write_output};
+use rusotp::{OtpAccount, OtpKind, QrRenderer, Radix, Secret, HOTP, TOTP};
+use serde_json::{json, Value};
+use std::num::{NonZeroU64, NonZeroU8};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn run(command: &Command, json: bool) -> CliResult<ExitCode> {
+    match command {
+        Command::Generate { otp, secret, timestamp } => generate(json, build(otp, read_secret(secret)?), *timestamp),
+        Command::Verify {
+            code,
+            otp,
+            secret,
+            timestamp,
+            retries,
+            drift_ahead,
+            drift_behind,
+            after,
+        } => {
+            let otp = build(otp, read_secret(secret)?);
+            let matched = match &otp {
+                OtpKind::HOTP(hotp, counter) => hotp.verify(code, *counter, *retries)?,
+                OtpKind::TOTP(totp) => {
+                    // The drifts count time steps, and `after` rejects its own time step too.
+                    let interval = totp.interval().get();
+                    let next = after.map(|after| after / interval + 1);
+                    totp.verify_window_at(code, timestamp.unwrap_or_else(now), next, *drift_behind, *drift_ahead)?
+                        .map(|outcome| outcome.counter * interval)
+                }
+            };
+            let key = if matches!(otp, OtpKind::HOTP(..)) { "counter" } else { "timestamp" };
+            print(
+                json,
+                if matched.is_some() { "valid" } else { "invalid" },
+                json!({ "valid": matched.is_some(), key: matched }),
+            );
+            Ok(if matched.is_some() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::from(EXIT_REJECTED)
+            })
+        }
+        Command::Uri { otp, secret, label } => {
+            let uri = account(label, build(otp, read_secret(secret)?)).to_uri()?;
+            print(json, &uri, json!({ "uri": uri }));
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Qr {
+            otp,
+            secret,
+            label,
+            format,
+            output,
+        } => {
+            let uri = account(label, build(otp, read_secret(secret)?)).to_uri()?;
+            let renderer = QrRenderer::new();
+            let bytes = match format {
+                QrFormat::Terminal => renderer.terminal(&uri)?.into_bytes(),
+                QrFormat::Svg => renderer.svg(&uri)?.into_bytes(),
+                #[cfg(feature = "qr-image")]
+                QrFormat::Png => renderer.png(&uri)?,
+            };
+            if json {
+                let path = output
+                    .as_deref()
+                    .ok_or_else(|| CliError::InvalidInput("--json requires --output for QR codes".to_string()))?;
+                write_output(Some(path), &bytes)?;
+                print(json, "", json!({ "uri": uri, "output": path.display().to_string() }));
+            } else {
+                write_output(output.as_deref(), &bytes)?;
+                if output.is_none() && *format != QrFormat::Terminal {
+                    println!();
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::ParseUri { uri } => {
+            print_accounts(json, &OtpAccount::parse(uri)?, |accounts| {
+                Ok(accounts.iter().map(account_text).collect::<Vec<_>>().join("\n\n"))
+            })?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Import { file, format } => {
+            let accounts = match format {
+                ImportFormat::Uris => parse_uri_list(&read_text(file)?)?,
+                #[cfg(feature = "qr-decode")]
+                ImportFormat::Qr => rusotp::decode_qr_bytes(&crate::input::read_input(file)?)?,
+            };
+            print_accounts(json, &accounts, uri_list)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Export { file, format, output } => {
+            let accounts = parse_uri_list(&read_text(file)?)?;
+            let mut text = match format {
+                ExportFormat::Uris => uri_list(&accounts)?,
+                ExportFormat::Json => {
+                    serde_json::to_string_pretty(&accounts.iter().map(account_json).collect::<Value>())
+                        .map_err(|e| CliError::InvalidInput(e.to_string()))?
+                }
+            };
+            text.push('\n');
+            write_output(output.as_deref(), text.as_bytes())?;
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn generate(json: bool, otp: OtpKind, timestamp: Option<u64>) -> CliResult<ExitCode> {
+    match otp {
+        OtpKind::HOTP(hotp, counter) => {
+            let code = hotp.generate(counter)?;
+            print(json, &code, json!({ "otp": code, "counter": counter }));
+        }
+        OtpKind::TOTP(totp) => {
+            let timestamp = timestamp.unwrap_or_else(now);
+            let code = totp.generate_at(timestamp)?;
+            let interval = totp.interval().get();
+            let valid_for = interval - timestamp % interval;
+            print(json, &code, json!({ "otp": code, "timestamp": timestamp, "valid_for": valid_for }));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_accounts<F>(json: bool, accounts: &[OtpAccount], plain: F) -> CliResult<()>
+where
+    F: Fn(&[OtpAccount]) -> CliResult<String>,
+{
+    if json {
+        print(json, "", accounts.iter().map(account_json).collect());
+    } else {
+        println!("{}", plain(accounts)?);
+    }
+    Ok(())
+}
+
+fn uri_list(accounts: &[OtpAccount]) -> CliResult<String> {
+    Ok(accounts
+        .iter()
+        .map(|account| account.to_uri())
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n"))
+}
+
+fn build(otp: &OtpArgs, secret: Secret) -> OtpKind {
+    let length = NonZeroU8::new(otp.length).unwrap();
+    let radix = Radix::new(otp.radix).unwrap();
+    match otp.kind {
+        OtpType::Hotp => OtpKind::HOTP(HOTP::new(otp.algorithm.into(), secret, length, radix), otp.counter),
+        OtpType::Totp => OtpKind::TOTP(TOTP::new(
+            otp.algorithm.into(),
+            secret,
+            length,
+            radix,
+            NonZeroU64::new(otp.interval).unwrap(),
+        )),
+    }
+}
+
+fn account(label: &LabelArgs, otp: OtpKind) -> OtpAccount {
+    OtpAccount {
+        issuer: label.issuer.clone(),
+        name: label.name.clone(),
+        otp,
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
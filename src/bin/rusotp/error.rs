@@ -0,0 +1,62 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use rusotp::OtpError;
+use std::fmt::{Display, Formatter};
+use std::process::ExitCode;
+
+/// The OTP given to `verify` was rejected.
+pub const EXIT_REJECTED: u8 = 1;
+/// The secret, URI or file content could not be parsed, or the parameters are not supported.
+pub const EXIT_INVALID_INPUT: u8 = 3;
+/// A file, environment variable or stream could not be read or written.
+pub const EXIT_IO: u8 = 4;
+
+/// Error of a CLI command, carrying the exit code of the process.
+#[derive(Debug)]
+pub enum CliError {
+    InvalidInput(String),
+    Io(String),
+}
+
+pub type CliResult<T> = Result<T, CliError>;
+
+impl CliError {
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            CliError::InvalidInput(_) => EXIT_INVALID_INPUT,
+            CliError::Io(_) => EXIT_IO,
+        })
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::InvalidInput(_) => "invalid_input",
+            CliError::Io(_) => "io",
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::InvalidInput(message) | CliError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<Box<dyn OtpError>> for CliError {
+    fn from(error: Box<dyn OtpError>) -> Self {
+        CliError::InvalidInput(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(error: std::io::Error) -> Self {
+        CliError::Io(error.to_string())
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::cli::{SecretArgs, SecretEncoding};
+use crate::error::{CliError, CliResult};
+use rusotp::{OtpAccount, Secret};
+use std::io::Read;
+use std::path::Path;
+
+/// Reads a file, or stdin if the path is `-`.
+pub fn read_input(path: &Path) -> CliResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    if path == Path::new("-") {
+        std::io::stdin().read_to_end(&mut bytes)?;
+    } else {
+        bytes = std::fs::read(path).map_err(|e| CliError::Io(format!("{}: {}", path.display(), e)))?;
+    }
+    Ok(bytes)
+}
+
+/// Reads a file, or stdin if the path is `-`, as UTF-8 text.
+pub fn read_text(path: &Path) -> CliResult<String> {
+    String::from_utf8(read_input(path)?)
+        .map_err(|_| CliError::InvalidInput(format!("{}: not valid UTF-8", path.display())))
+}
+
+/// Reads the secret from the environment variable, the file or stdin, and decodes it.
+pub fn read_secret(args: &SecretArgs) -> CliResult<Secret> {
+    let text = match (&args.secret_env, &args.secret_file) {
+        (Some(var), _) => {
+            std::env::var(var).map_err(|_| CliError::Io(format!("environment variable `{}` is not set", var)))?
+        }
+        (None, Some(path)) => read_text(path)?,
+        (None, None) => read_text(Path::new("-"))?,
+    };
+    let text = text.trim_end_matches(['\r', '\n']);
+
+    Ok(match args.secret_encoding {
+        SecretEncoding::Base32 => Secret::new_from_base32(text)?,
+        SecretEncoding::Hex => Secret::new_from_hex(text)?,
+        SecretEncoding::Raw => {
+            Secret::new_from_str(text).map_err(|e| CliError::InvalidInput(format!("secret {}", e)))?
+        }
+    })
+}
+
+/// Parses `otpauth://` and `otpauth-migration://` URIs, one per line; blank lines and `#` comments are skipped.
+pub fn parse_uri_list(text: &str) -> CliResult<Vec<OtpAccount>> {
+    let mut accounts = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parsed =
+            OtpAccount::parse(line).map_err(|e| CliError::InvalidInput(format!("line {}: {}", index + 1, e)))?;
+        accounts.extend(parsed);
+    }
+    Ok(accounts)
+}
@@ -0,0 +1,27 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+mod cli;
+mod command;
+mod error;
+mod input;
+mod output;
+
+use clap::Parser;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let cli = cli::Cli::parse();
+
+    match command::run(&cli.command, cli.json) {
+        Ok(code) => code,
+        Err(error) => {
+            output::print_error(cli.json, &error);
+            error.exit_code()
+        }
+    }
+}
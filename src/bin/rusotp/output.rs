@@ -0,0 +1,90 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::error::{CliError, CliResult};
+use rusotp::{AlgorithmTrait, OtpAccount, OtpKind};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+
+/// Prints `plain` as text, or `value` as a single line of JSON when `--json` is given.
+pub fn print(json: bool, plain: &str, value: Value) {
+    if json {
+        println!("{}", value);
+    } else {
+        println!("{}", plain);
+    }
+}
+
+/// Prints an error to stderr, as a JSON object when `--json` is given.
+pub fn print_error(json: bool, error: &CliError) {
+    if json {
+        eprintln!("{}", json!({ "error": error.kind(), "message": error.to_string() }));
+    } else {
+        eprintln!("rusotp: {}", error);
+    }
+}
+
+/// Writes `bytes` to the file, or stdout if there is none.
+pub fn write_output(path: Option<&Path>, bytes: &[u8]) -> CliResult<()> {
+    match path {
+        Some(path) => std::fs::write(path, bytes).map_err(|e| CliError::Io(format!("{}: {}", path.display(), e))),
+        None => Ok(std::io::stdout().write_all(bytes)?),
+    }
+}
+
+/// Describes an account as a JSON object.
+pub fn account_json(account: &OtpAccount) -> Value {
+    match &account.otp {
+        OtpKind::HOTP(hotp, counter) => json!({
+            "type": "hotp",
+            "issuer": account.issuer,
+            "name": account.name,
+            "secret": hotp.secret().to_base32(),
+            "algorithm": hotp.algorithm().to_string(),
+            "digits": hotp.length().get(),
+            "radix": hotp.radix().get(),
+            "counter": counter,
+        }),
+        OtpKind::TOTP(totp) => json!({
+            "type": "totp",
+            "issuer": account.issuer,
+            "name": account.name,
+            "secret": totp.secret().to_base32(),
+            "algorithm": totp.algorithm().to_string(),
+            "digits": totp.length().get(),
+            "radix": totp.radix().get(),
+            "period": totp.interval().get(),
+        }),
+    }
+}
+
+/// Describes an account as `key: value` lines.
+pub fn account_text(account: &OtpAccount) -> String {
+    let value = account_json(account);
+    let fields = [
+        "type",
+        "issuer",
+        "name",
+        "secret",
+        "algorithm",
+        "digits",
+        "radix",
+        "counter",
+        "period",
+    ];
+    fields
+        .iter()
+        .filter_map(|field| {
+            value.get(field).map(|v| match v {
+                Value::String(s) => format!("{}: {}", field, s),
+                v => format!("{}: {}", field, v),
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -6,6 +6,7 @@
 // See the file LICENSE for details.
 
 use crate::ffi::{HotpConfig, TotpConfig};
+use crate::throttle::Throttle;
 use crate::{Algorithm, AlgorithmTrait, Radix, Secret, HOTP, TOTP};
 use std::ffi::CStr;
 use std::num::{NonZeroU64, NonZeroU8};
@@ -23,6 +24,10 @@ pub(crate) fn to_str(ptr: *const c_char) -> &'static str {
     unsafe { CStr::from_ptr(ptr).to_str().unwrap() }
 }
 
+pub(crate) fn to_throttle(ptr: *const Throttle) -> &'static Throttle {
+    unsafe { &*ptr }
+}
+
 pub(crate) fn to_hotp(config: HotpConfig) -> HOTP {
     if config.secret.is_null() {
         panic!("Secret is null");
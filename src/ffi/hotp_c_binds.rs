@@ -5,13 +5,20 @@
 //
 // See the file LICENSE for details.
 
-use crate::ffi::converter::{to_cstr, to_hotp, to_str};
+use crate::ffi::converter::{to_hotp, to_str};
 use crate::ffi::{
-    error_bool_result, error_hotp_config_result, error_string_result, success_bool_result, success_hotp_config_result,
-    success_string_result, BoolResult, HotpConfig, HotpConfigResult, StringResult,
+    error_bool_result, error_string_result, success_bool_result, success_string_result, BoolResult, HotpConfig,
+    StringResult,
 };
-use crate::{AlgorithmTrait, HOTP};
-use std::ffi::{c_ulonglong, c_ushort};
+#[cfg(feature = "uri")]
+use crate::{
+    ffi::converter::to_cstr,
+    ffi::{error_hotp_config_result, success_hotp_config_result, HotpConfigResult},
+    AlgorithmTrait, HOTP,
+};
+use std::ffi::c_ulonglong;
+#[cfg(feature = "uri")]
+use std::ffi::c_ushort;
 use std::os::raw::c_char;
 
 /// Generates an HOTP (HMAC-based One-Time Password) based on the provided configuration and counter.
@@ -144,6 +151,7 @@ pub extern "C" fn hotp_verify(
 /// #    .success();
 /// # }
 /// ```
+#[cfg(feature = "uri")]
 #[no_mangle]
 pub extern "C" fn hotp_provisioning_uri(
     config: HotpConfig,
@@ -161,6 +169,65 @@ pub extern "C" fn hotp_provisioning_uri(
     }
 }
 
+/// Generates a provisioning QR code for HOTP (HMAC-based One-Time Password) rendered as an SVG document.
+///
+/// # Arguments
+///
+/// * `config` - A `HotpConfig` struct containing the configuration for the HOTP generation.
+/// * `issuer` - A pointer to a C string representing the issuer of the HOTP.
+/// * `user` - A pointer to a C string representing the name of the user or account.
+/// * `counter` - A counter value used in the HOTP generation.
+///
+/// # Returns
+///
+/// A `StringResult` containing success status and the SVG document if success.
+///
+/// # Example
+/// ```
+/// # use inline_c::assert_cxx;
+/// #
+/// # fn main() {
+/// #     (assert_cxx! {
+/// #include <stdio.h>
+/// #include "rusotp.hpp"
+///
+/// int main() {
+///     HotpConfig config = {"SHA1", "12345678901234567890", 6, 10};
+///     unsigned long counter = 2;
+///
+///     StringResult svg = hotp_provisioning_qr_svg(config, "rusotp", "rusotp", counter);
+///     printf("SVG : %s\n", svg.data);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[cfg(all(feature = "uri", feature = "qr"))]
+#[no_mangle]
+pub extern "C" fn hotp_provisioning_qr_svg(
+    config: HotpConfig,
+    issuer: *const c_char,
+    user: *const c_char,
+    counter: c_ulonglong,
+) -> StringResult {
+    if issuer.is_null() {
+        error_string_result("Issuer is null")
+    } else if user.is_null() {
+        error_string_result("Name is null")
+    } else {
+        match to_hotp(config)
+            .provisioning_qr(to_str(issuer), to_str(user), counter)
+            .and_then(|qr| qr.svg())
+        {
+            Ok(svg) => success_string_result(svg.as_str()),
+            Err(e) => error_string_result(e.to_string().as_str()),
+        }
+    }
+}
+
+#[cfg(feature = "uri")]
 #[no_mangle]
 pub extern "C" fn hotp_from_uri(uri: *const c_char) -> HotpConfigResult {
     if uri.is_null() {
@@ -28,6 +28,7 @@ fn test_hotp_generate() {
 }
 
 #[test]
+#[cfg(feature = "uri")]
 fn test_hotp_provisioning_uri() {
     let config = make_config();
     let issuer = CString::new("testissuer").unwrap();
@@ -38,6 +39,23 @@ fn test_hotp_provisioning_uri() {
 }
 
 #[test]
+#[cfg(all(feature = "uri", feature = "qr"))]
+fn test_hotp_provisioning_qr_svg() {
+    let config = make_config();
+    let issuer = CString::new("testissuer").unwrap();
+    let user = CString::new("testuser").unwrap();
+    let svg = hotp_provisioning_qr_svg(config, issuer.as_ptr(), user.as_ptr(), 0);
+    assert!(svg.success);
+    assert!(svg.error.is_null());
+    assert!(to_string(svg.data).contains("<svg"));
+
+    let fail_result = hotp_provisioning_qr_svg(config, issuer.as_ptr(), null(), 0);
+    assert!(!fail_result.success);
+    assert_eq!(to_string(fail_result.error), "Name is null");
+}
+
+#[test]
+#[cfg(feature = "uri")]
 fn test_hotp_from_uri() {
     let config = make_config();
     let issuer = CString::new("testissuer").unwrap();
@@ -77,6 +95,7 @@ fn test_hotp_verify_null_otp() {
 }
 
 #[test]
+#[cfg(feature = "uri")]
 fn test_hotp_provisioning_uri_null_name() {
     let config = make_config();
     let data = hotp_provisioning_uri(config, std::ptr::null(), std::ptr::null(), 0);
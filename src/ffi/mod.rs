@@ -8,10 +8,12 @@
 mod converter;
 mod hotp_c_binds;
 mod r#struct;
+mod throttle_c_binds;
 mod totp_c_binds;
 
 pub use hotp_c_binds::*;
 pub use r#struct::hotp_config::HotpConfig;
 pub use r#struct::result::*;
 pub use r#struct::totp_config::TotpConfig;
+pub use throttle_c_binds::*;
 pub use totp_c_binds::*;
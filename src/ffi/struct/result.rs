@@ -7,7 +7,8 @@
 
 use crate::ffi::converter::to_cstr;
 use crate::ffi::{HotpConfig, TotpConfig};
-use std::ffi::c_char;
+use crate::OtpError;
+use std::ffi::{c_char, c_ulonglong};
 use std::ptr::null;
 
 /// FFI-safe result type for operations returning a string.
@@ -70,6 +71,40 @@ pub(crate) fn success_bool_result(data: bool) -> BoolResult {
     }
 }
 
+/// FFI-safe result type for throttled verifications.
+///
+/// # Fields
+/// - `success`: Indicates if the verification was performed.
+/// - `data`: Whether the OTP was accepted (valid if `success` is true).
+/// - `retry_after`: Seconds to wait before the next attempt if the account is throttled, `0` otherwise.
+/// - `error`: Pointer to a C string containing the error message (valid if `success` is false).
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ThrottledResult {
+    pub success: bool,
+    pub data: bool,
+    pub retry_after: c_ulonglong,
+    pub error: *const c_char,
+}
+
+pub(crate) fn error_throttled_result(error: &dyn OtpError) -> ThrottledResult {
+    ThrottledResult {
+        success: false,
+        data: false,
+        retry_after: error.retry_after().unwrap_or(0),
+        error: to_cstr(&error.to_string()),
+    }
+}
+
+pub(crate) fn success_throttled_result(data: bool) -> ThrottledResult {
+    ThrottledResult {
+        success: true,
+        data,
+        retry_after: 0,
+        error: null(),
+    }
+}
+
 /// FFI-safe result type for operations returning a `HotpConfig` pointer.
 ///
 /// # Fields
@@ -84,6 +119,7 @@ pub struct HotpConfigResult {
     pub error: *const c_char,
 }
 
+#[cfg(feature = "uri")]
 pub(crate) fn error_hotp_config_result(error: &str) -> HotpConfigResult {
     HotpConfigResult {
         success: false,
@@ -92,6 +128,7 @@ pub(crate) fn error_hotp_config_result(error: &str) -> HotpConfigResult {
     }
 }
 
+#[cfg(feature = "uri")]
 pub(crate) fn success_hotp_config_result(data: HotpConfig) -> HotpConfigResult {
     HotpConfigResult {
         success: true,
@@ -114,6 +151,7 @@ pub struct TotpConfigResult {
     pub error: *const c_char,
 }
 
+#[cfg(feature = "uri")]
 pub(crate) fn error_totp_config_result(error: &str) -> TotpConfigResult {
     TotpConfigResult {
         success: false,
@@ -122,6 +160,7 @@ pub(crate) fn error_totp_config_result(error: &str) -> TotpConfigResult {
     }
 }
 
+#[cfg(feature = "uri")]
 pub(crate) fn success_totp_config_result(data: TotpConfig) -> TotpConfigResult {
     TotpConfigResult {
         success: true,
@@ -0,0 +1,161 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::ffi::converter::{to_hotp, to_str, to_throttle, to_totp};
+use crate::ffi::{error_throttled_result, success_throttled_result, HotpConfig, ThrottledResult, TotpConfig};
+use crate::throttle::{Throttle, ThrottleConfig};
+use crate::OtpGenericError;
+use std::ffi::{c_char, c_ulonglong};
+
+/// Returns the default limits of a throttle: back-off of 1, 2, 4 and 8 seconds, then a 15 minutes lockout after the
+/// fifth consecutive failure.
+#[no_mangle]
+pub extern "C" fn throttle_default_config() -> ThrottleConfig {
+    ThrottleConfig::default()
+}
+
+/// Creates a throttle counting failed verifications per account key, see `hotp_verify_throttled` and
+/// `totp_verify_throttled`.
+///
+/// # Arguments
+///
+/// * `config` - A `ThrottleConfig` struct with the limits applied to each account.
+///
+/// # Returns
+///
+/// A pointer to the throttle, which must be released with `throttle_free`. It may be shared between threads.
+///
+/// # Example
+///
+/// ```
+/// # use inline_c::assert_cxx;
+/// #
+/// # fn main() {
+/// #     (assert_cxx! {
+/// #include <stdio.h>
+/// #include "rusotp.hpp"
+///
+/// int main() {
+///     HotpConfig config = {"SHA1", "12345678901234567890", 6, 10};
+///     Throttle *throttle = throttle_new(throttle_default_config());
+///
+///     ThrottledResult rejected = hotp_verify_throttled(throttle, "user@email.mail", config, "000000", 0, 0);
+///     printf("VERIFIED : %s\n", rejected.data ? "true" : "false");
+///
+///     ThrottledResult throttled = hotp_verify_throttled(throttle, "user@email.mail", config, "755224", 0, 0);
+///     printf("RETRY AFTER : %llu\n", throttled.retry_after);
+///
+///     throttle_free(throttle);
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[no_mangle]
+pub extern "C" fn throttle_new(config: ThrottleConfig) -> *mut Throttle {
+    Box::into_raw(Box::new(Throttle::new(config)))
+}
+
+/// Releases a throttle created by `throttle_new`.
+///
+/// # Arguments
+///
+/// * `throttle` - A pointer returned by `throttle_new`, or null.
+///
+/// # Safety
+///
+/// `throttle` must not be used after this call, and must not be released twice.
+#[no_mangle]
+pub unsafe extern "C" fn throttle_free(throttle: *mut Throttle) {
+    if !throttle.is_null() {
+        drop(Box::from_raw(throttle));
+    }
+}
+
+/// Verifies an HOTP like `hotp_verify` unless the account is throttled, counting the outcome.
+///
+/// # Arguments
+///
+/// * `throttle` - A pointer returned by `throttle_new`.
+/// * `key` - A pointer to a C string identifying the account, e.g. the user name.
+/// * `config` - A `HotpConfig` struct containing the configuration for the HOTP verification.
+/// * `otp` - A pointer to a C string representing the OTP to be verified.
+/// * `counter` - A counter value used in the HOTP verification.
+/// * `retries` - The number of retries allowed for the HOTP verification.
+///
+/// # Returns
+///
+/// A `ThrottledResult`; if the account is throttled, `success` is false and `retry_after` holds the seconds to wait.
+#[no_mangle]
+pub extern "C" fn hotp_verify_throttled(
+    throttle: *const Throttle,
+    key: *const c_char,
+    config: HotpConfig,
+    otp: *const c_char,
+    counter: c_ulonglong,
+    retries: c_ulonglong,
+) -> ThrottledResult {
+    if let Some(error) = null_argument(throttle, key, otp) {
+        return error_throttled_result(&error);
+    }
+    match to_throttle(throttle).verify_hotp(to_str(key), &to_hotp(config), to_str(otp), counter, retries) {
+        Ok(verified) => success_throttled_result(verified.is_some()),
+        Err(e) => error_throttled_result(e.as_ref()),
+    }
+}
+
+/// Verifies a TOTP at the current time like `totp_verify` unless the account is throttled, counting the outcome.
+///
+/// # Arguments
+///
+/// * `throttle` - A pointer returned by `throttle_new`.
+/// * `key` - A pointer to a C string identifying the account, e.g. the user name.
+/// * `config` - A `TotpConfig` struct containing the configuration for the TOTP verification.
+/// * `otp` - A pointer to a C string representing the OTP to be verified.
+/// * `after` - The timestamp before which OTPs are rejected.
+/// * `drift_ahead` - The allowed drift ahead in seconds.
+/// * `drift_behind` - The allowed drift behind in seconds.
+///
+/// # Returns
+///
+/// A `ThrottledResult`; if the account is throttled, `success` is false and `retry_after` holds the seconds to wait.
+#[no_mangle]
+pub extern "C" fn totp_verify_throttled(
+    throttle: *const Throttle,
+    key: *const c_char,
+    config: TotpConfig,
+    otp: *const c_char,
+    after: c_ulonglong,
+    drift_ahead: c_ulonglong,
+    drift_behind: c_ulonglong,
+) -> ThrottledResult {
+    if let Some(error) = null_argument(throttle, key, otp) {
+        return error_throttled_result(&error);
+    }
+    let totp = to_totp(config);
+    match to_throttle(throttle).verify_totp(to_str(key), &totp, to_str(otp), Some(after), drift_ahead, drift_behind) {
+        Ok(verified) => success_throttled_result(verified.is_some()),
+        Err(e) => error_throttled_result(e.as_ref()),
+    }
+}
+
+fn null_argument(throttle: *const Throttle, key: *const c_char, otp: *const c_char) -> Option<OtpGenericError> {
+    let name = if throttle.is_null() {
+        "Throttle"
+    } else if key.is_null() {
+        "Key"
+    } else if otp.is_null() {
+        "OTP"
+    } else {
+        return None;
+    };
+    Some(OtpGenericError(format!("{} is null", name)))
+}
+
+#[cfg(test)]
+mod throttle_c_bind_tests;
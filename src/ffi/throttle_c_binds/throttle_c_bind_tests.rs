@@ -0,0 +1,92 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use super::*;
+use crate::ffi::converter::to_string;
+use crate::ffi::{hotp_generate, totp_generate};
+use std::ffi::CString;
+use std::ptr::null;
+
+fn make_hotp_config() -> HotpConfig {
+    HotpConfig {
+        algorithm: CString::new("SHA1").unwrap().into_raw(),
+        secret: CString::new("12345678901234567890").unwrap().into_raw(),
+        length: 6,
+        radix: 10,
+    }
+}
+
+fn make_totp_config() -> TotpConfig {
+    TotpConfig {
+        algorithm: CString::new("SHA1").unwrap().into_raw(),
+        secret: CString::new("12345678901234567890").unwrap().into_raw(),
+        length: 6,
+        radix: 10,
+        interval: 30,
+    }
+}
+
+#[test]
+fn test_hotp_verify_throttled() {
+    let throttle = throttle_new(ThrottleConfig {
+        backoff: 60,
+        ..throttle_default_config()
+    });
+    let key = CString::new("user@email.mail").unwrap();
+    let wrong = CString::new("000000").unwrap();
+    let otp = hotp_generate(make_hotp_config(), 0);
+
+    let verified = hotp_verify_throttled(throttle, key.as_ptr(), make_hotp_config(), otp.data, 0, 0);
+    assert!(verified.success);
+    assert!(verified.data);
+
+    let rejected = hotp_verify_throttled(throttle, key.as_ptr(), make_hotp_config(), wrong.as_ptr(), 0, 0);
+    assert!(rejected.success);
+    assert!(!rejected.data);
+    assert_eq!(rejected.retry_after, 0);
+
+    let throttled = hotp_verify_throttled(throttle, key.as_ptr(), make_hotp_config(), otp.data, 0, 0);
+    assert!(!throttled.success);
+    assert!(throttled.retry_after > 0);
+    assert!(to_string(throttled.error).starts_with("Too many failed attempts"));
+
+    unsafe { throttle_free(throttle) };
+}
+
+#[test]
+fn test_totp_verify_throttled() {
+    let throttle = throttle_new(throttle_default_config());
+    let key = CString::new("user@email.mail").unwrap();
+    let otp = totp_generate(make_totp_config());
+
+    let verified = totp_verify_throttled(throttle, key.as_ptr(), make_totp_config(), otp.data, 0, 30, 30);
+    assert!(verified.success);
+    assert!(verified.data);
+
+    unsafe { throttle_free(throttle) };
+}
+
+#[test]
+fn test_verify_throttled_null_arguments() {
+    let throttle = throttle_new(throttle_default_config());
+    let key = CString::new("user@email.mail").unwrap();
+    let otp = CString::new("755224").unwrap();
+
+    let result = hotp_verify_throttled(null(), key.as_ptr(), make_hotp_config(), otp.as_ptr(), 0, 0);
+    assert_eq!(to_string(result.error), "Throttle is null");
+
+    let result = totp_verify_throttled(throttle, null(), make_totp_config(), otp.as_ptr(), 0, 0, 0);
+    assert_eq!(to_string(result.error), "Key is null");
+
+    let result = hotp_verify_throttled(throttle, key.as_ptr(), make_hotp_config(), null(), 0, 0);
+    assert!(!result.success);
+    assert_eq!(result.retry_after, 0);
+    assert_eq!(to_string(result.error), "OTP is null");
+
+    unsafe { throttle_free(throttle) };
+    unsafe { throttle_free(std::ptr::null_mut()) };
+}
@@ -5,14 +5,21 @@
 //
 // See the file LICENSE for details.
 
-use crate::ffi::converter::{to_cstr, to_str, to_totp};
+use crate::ffi::converter::{to_str, to_totp};
 use crate::ffi::{
-    error_bool_result, error_string_result, error_totp_config_result, success_bool_result, success_string_result,
-    success_totp_config_result, BoolResult, StringResult, TotpConfig, TotpConfigResult,
+    error_bool_result, error_string_result, success_bool_result, success_string_result, BoolResult, StringResult,
+    TotpConfig,
 };
-use crate::otp::algorithm::AlgorithmTrait;
-use crate::TOTP;
-use std::ffi::{c_char, c_ulonglong, c_ushort};
+#[cfg(feature = "uri")]
+use crate::{
+    ffi::converter::to_cstr,
+    ffi::{error_totp_config_result, success_totp_config_result, TotpConfigResult},
+    otp::algorithm::AlgorithmTrait,
+    TOTP,
+};
+#[cfg(feature = "uri")]
+use std::ffi::c_ushort;
+use std::ffi::{c_char, c_ulonglong};
 
 /// Generates a TOTP (Time-based One-Time Password) based on the provided configuration for the current time.
 ///
@@ -247,6 +254,7 @@ pub extern "C" fn totp_verify_at(
 /// #    .success();
 /// # }
 ///```
+#[cfg(feature = "uri")]
 #[no_mangle]
 pub extern "C" fn totp_provisioning_uri(
     config: TotpConfig,
@@ -265,6 +273,63 @@ pub extern "C" fn totp_provisioning_uri(
     }
 }
 
+/// Generates a provisioning QR code for TOTP (Time-based One-Time Password) rendered as an SVG document.
+///
+/// # Arguments
+///
+/// * `config` - A `TotpConfig` struct containing the configuration for the TOTP generation.
+/// * `issuer` - A pointer to a C string representing the issuer of the TOTP.
+/// * `name` - A pointer to a C string representing the name of the user or account.
+///
+/// # Returns
+///
+/// A `StringResult` containing success status and the SVG document if success.
+///
+/// # Example
+///
+/// ```
+/// # use inline_c::assert_cxx;
+/// #
+/// # fn main() {
+/// #     (assert_cxx! {
+/// #include <stdio.h>
+/// #include "rusotp.hpp"
+///
+/// int main() {
+///     TotpConfig config = {"SHA1", "12345678901234567890", 6, 10, 30};
+///
+///     StringResult svg = totp_provisioning_qr_svg(config, "rusotp", "user@email.mail");
+///     printf("SVG : %s\n", svg.data);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+///```
+#[cfg(all(feature = "uri", feature = "qr"))]
+#[no_mangle]
+pub extern "C" fn totp_provisioning_qr_svg(
+    config: TotpConfig,
+    issuer: *const c_char,
+    name: *const c_char,
+) -> StringResult {
+    if issuer.is_null() {
+        error_string_result("Issuer is null")
+    } else if name.is_null() {
+        error_string_result("Name is null")
+    } else {
+        match to_totp(config)
+            .provisioning_qr(to_str(issuer), to_str(name))
+            .and_then(|qr| qr.svg())
+        {
+            Ok(svg) => success_string_result(svg.as_str()),
+            Err(e) => error_string_result(e.to_string().as_str()),
+        }
+    }
+}
+
+#[cfg(feature = "uri")]
 #[no_mangle]
 pub extern "C" fn totp_from_uri(uri: *const c_char) -> TotpConfigResult {
     if uri.is_null() {
@@ -55,6 +55,7 @@ fn test_totp_verify_at() {
 }
 
 #[test]
+#[cfg(feature = "uri")]
 fn test_totp_provisioning_uri() {
     let config = make_config();
     let issuer = CString::new("TestIssuer").unwrap();
@@ -69,6 +70,23 @@ fn test_totp_provisioning_uri() {
 }
 
 #[test]
+#[cfg(all(feature = "uri", feature = "qr"))]
+fn test_totp_provisioning_qr_svg() {
+    let config = make_config();
+    let issuer = CString::new("TestIssuer").unwrap();
+    let name = CString::new("TestUser").unwrap();
+    let svg = totp_provisioning_qr_svg(config, issuer.as_ptr(), name.as_ptr());
+    assert!(svg.success);
+    assert!(svg.error.is_null());
+    assert!(to_string(svg.data).contains("<svg"));
+
+    let fail_result = totp_provisioning_qr_svg(config, null(), name.as_ptr());
+    assert!(!fail_result.success);
+    assert_eq!(to_string(fail_result.error), "Issuer is null");
+}
+
+#[test]
+#[cfg(feature = "uri")]
 fn test_totp_from_uri() {
     let config = make_config();
     let issuer = CString::new("testissuer").unwrap();
@@ -89,6 +107,7 @@ fn test_totp_from_uri() {
 }
 
 #[test]
+#[cfg(feature = "uri")]
 fn test_totp_from_uri_fail() {
     let config = make_config();
     let issuer = CString::new("testissuer").unwrap();
@@ -5,18 +5,66 @@
 //
 // See the file LICENSE for details.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 mod otp;
 mod r#type;
 mod util;
 
+#[cfg(feature = "async")]
+pub mod async_verifier;
+#[cfg(feature = "backup")]
+pub mod backup;
+#[cfg(feature = "std")]
 pub mod ffi;
+#[cfg(feature = "pkcs11")]
+pub mod pkcs11;
+#[cfg(feature = "pskc")]
+pub mod pskc;
+#[cfg(feature = "recovery")]
+pub mod recovery;
+#[cfg(feature = "skey")]
+pub mod skey;
+#[cfg(feature = "std")]
+pub mod throttle;
+#[cfg(feature = "vault")]
+pub mod vault;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "yubico")]
+pub mod yubico;
 
+#[cfg(feature = "uri")]
+pub use otp::account::{OtpAccount, OtpKind};
 pub use otp::algorithm::Algorithm;
 pub use otp::algorithm::AlgorithmTrait;
+#[cfg(feature = "log")]
+pub use otp::event::LogEventSink;
+pub use otp::event::{NoopEventSink, OtpEvent, OtpEventSink, OtpEventSource};
 pub use otp::hotp::HOTP;
+#[cfg(feature = "motp")]
+pub use otp::motp::MOtp;
 pub use otp::totp::TOTP;
+#[cfg(feature = "std")]
+pub use otp::watch::AsyncTotpWatch;
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", feature = "wasm"))))]
+pub use otp::watch::TotpWatch;
+#[cfg(feature = "yandex")]
+pub use otp::yandex::YandexOtp;
+pub use r#type::key_provider::KeyProvider;
 pub use r#type::otp_error::*;
 pub use r#type::radix::*;
 pub use r#type::secret::*;
-pub use util::qr_code::generate_qr_code_string;
+pub use r#type::verify_outcome::VerifyOutcome;
+#[cfg(feature = "qr-image")]
+#[allow(deprecated)]
 pub use util::qr_code::generate_qr_code_image;
+#[cfg(feature = "qr")]
+#[allow(deprecated)]
+pub use util::qr_code::generate_qr_code_string;
+#[cfg(feature = "qr")]
+pub use util::qr_code::{ProvisioningQr, QrColor, QrErrorCorrection, QrRenderer};
+#[cfg(feature = "qr-decode")]
+pub use util::qr_decode::{decode_qr_bytes, decode_qr_file};
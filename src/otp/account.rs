@@ -0,0 +1,322 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::otp::algorithm::{Algorithm, AlgorithmTrait};
+use crate::util::migration::{decode_migration_uri, MigrationEntry};
+use crate::{InvalidUriError, OtpError, OtpResult, Radix, Secret, UnsupportedRadixError, HOTP, TOTP};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::num::{NonZeroU64, NonZeroU8};
+
+/// The generator of an [`OtpAccount`].
+///
+/// # Variants
+///
+/// * `HOTP` - A counter based generator together with its current counter.
+/// * `TOTP` - A time based generator.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OtpKind {
+    HOTP(HOTP, u64),
+    TOTP(TOTP),
+}
+
+/// The generator parameters shared by the `otpauth` URI, the backup exporters and the vault.
+///
+/// # Fields
+///
+/// * `kind` - `hotp` or `totp`.
+/// * `period` - The interval of a `TOTP` generator, `None` for `HOTP`.
+/// * `counter` - The counter of a `HOTP` generator, `None` for `TOTP`.
+pub(crate) struct OtpParameters<'a> {
+    pub(crate) kind: &'static str,
+    pub(crate) algorithm: Algorithm,
+    pub(crate) secret: &'a Secret,
+    pub(crate) length: NonZeroU8,
+    pub(crate) radix: Radix,
+    pub(crate) period: Option<u64>,
+    pub(crate) counter: Option<u64>,
+}
+
+impl OtpKind {
+    /// Returns the parameters of the generator, with the counter of a `HOTP` generator.
+    pub(crate) fn parameters(&self) -> OtpParameters<'_> {
+        match self {
+            OtpKind::HOTP(hotp, counter) => OtpParameters {
+                kind: "hotp",
+                algorithm: hotp.algorithm,
+                secret: &hotp.secret,
+                length: hotp.length,
+                radix: hotp.radix,
+                period: None,
+                counter: Some(*counter),
+            },
+            OtpKind::TOTP(totp) => OtpParameters {
+                kind: "totp",
+                algorithm: totp.algorithm,
+                secret: &totp.secret,
+                length: totp.length,
+                radix: totp.radix,
+                period: Some(totp.interval.get()),
+                counter: None,
+            },
+        }
+    }
+}
+
+/// An enrolled OTP account: a `HOTP`/`TOTP` generator together with its issuer and account name.
+///
+/// # Fields
+///
+/// * `issuer` - The issuer of the account, empty if unknown.
+/// * `name` - The name of the user or account.
+/// * `otp` - The generator of the account.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::{OtpAccount, OtpKind};
+///
+/// let uri = "otpauth://totp/rusotp%3Auser%40email.mail?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&issuer=rusotp";
+/// let account = OtpAccount::from_uri(uri).unwrap();
+///
+/// assert_eq!(account.issuer, "rusotp");
+/// assert_eq!(account.name, "user@email.mail");
+/// assert!(matches!(account.otp, OtpKind::TOTP(_)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtpAccount {
+    pub issuer: String,
+    pub name: String,
+    pub otp: OtpKind,
+}
+
+impl OtpAccount {
+    /// Parses an `otpauth://hotp/...` or `otpauth://totp/...` provisioning URI.
+    ///
+    /// The secret is handed to `HOTP::from_uri`/`TOTP::from_uri`, the issuer and account name are read from the
+    /// label and the `issuer` parameter, and the `counter` parameter is kept for HOTP accounts. The optional
+    /// `algorithm`, `digits` and `period` parameters override the RFC defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - A provisioning URI string in the `otpauth` format.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the parsed `OtpAccount`, or an error if the URI is not a valid `otpauth` URI.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{OtpAccount, OtpKind};
+    ///
+    /// let uri = "otpauth://hotp/rusotp%3Arusotp?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&counter=2&issuer=rusotp";
+    /// let account = OtpAccount::from_uri(uri).unwrap();
+    ///
+    /// if let OtpKind::HOTP(hotp, counter) = account.otp {
+    ///     assert_eq!(hotp.generate(counter).unwrap(), "359152");
+    /// }
+    /// ```
+    pub fn from_uri(uri: &str) -> OtpResult<OtpAccount> {
+        let rest = uri.strip_prefix("otpauth://").ok_or_else(|| unsupported_scheme(uri))?;
+        let (kind, rest) = rest.split_once('/').unwrap_or((rest, ""));
+        let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let label =
+            urlencoding::decode(label).map_err(|e| Box::new(InvalidUriError(e.to_string())) as Box<dyn OtpError>)?;
+        let (mut issuer, name) = match label.split_once(':') {
+            Some((issuer, name)) => (issuer.to_owned(), name.trim_start().to_owned()),
+            None => (String::new(), label.into_owned()),
+        };
+
+        let invalid = |key: &str, value: &str| Box::new(InvalidUriError(format!("invalid {} `{}`", key, value))) as _;
+
+        let mut counter = 0;
+        let mut algorithm = Algorithm::SHA1;
+        let mut length = NonZeroU8::new(6).unwrap();
+        let mut interval = NonZeroU64::new(30).unwrap();
+        for (key, value) in query.split('&').filter_map(|param| param.split_once('=')) {
+            match key {
+                "issuer" if !value.is_empty() => {
+                    issuer = urlencoding::decode(value)
+                        .map_err(|e| Box::new(InvalidUriError(e.to_string())) as Box<dyn OtpError>)?
+                        .into_owned()
+                }
+                "counter" => counter = value.parse().map_err(|_| invalid(key, value))?,
+                "algorithm" => {
+                    algorithm = Algorithm::from_string(value.to_ascii_uppercase()).ok_or_else(|| invalid(key, value))?
+                }
+                "digits" => length = value.parse().map_err(|_| invalid(key, value))?,
+                "period" => interval = value.parse().map_err(|_| invalid(key, value))?,
+                _ => {}
+            }
+        }
+
+        let radix = Radix::new(10).unwrap();
+        let otp = match kind.to_ascii_lowercase().as_str() {
+            "hotp" => OtpKind::HOTP(HOTP::new(algorithm, HOTP::from_uri(uri)?.secret, length, radix), counter),
+            "totp" => OtpKind::TOTP(TOTP::new(algorithm, TOTP::from_uri(uri)?.secret, length, radix, interval)),
+            _ => return Err(Box::new(InvalidUriError(format!("unsupported OTP type `{}`", kind)))),
+        };
+
+        Ok(OtpAccount { issuer, name, otp })
+    }
+
+    /// Returns the label of the account: `issuer:name`, or only `name` if the issuer is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{OtpAccount, OtpKind, Secret, TOTP};
+    ///
+    /// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let account = OtpAccount { issuer: "rusotp".to_string(), name: "user@email.mail".to_string(), otp: OtpKind::TOTP(totp) };
+    /// assert_eq!(account.label(), "rusotp:user@email.mail");
+    /// ```
+    pub fn label(&self) -> String {
+        if self.issuer.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}:{}", self.issuer, self.name)
+        }
+    }
+
+    /// Builds an `otpauth://` URI carrying every parameter of the account, including `algorithm`, `digits` and
+    /// `period`.
+    ///
+    /// Unlike `HOTP::provisioning_uri`/`TOTP::provisioning_uri`, which only accept the parameters supported by
+    /// Google Authenticator, any algorithm, length and interval is written; the result is parsed back by `from_uri`.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the URI, or `UnsupportedRadixError` if the radix is not 10.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroU8;
+    /// use rusotp::{Algorithm, OtpAccount, OtpKind, Radix, Secret, HOTP};
+    ///
+    /// let hotp = HOTP::new(
+    ///     Algorithm::SHA256,
+    ///     Secret::new_from_str("12345678901234567890").unwrap(),
+    ///     NonZeroU8::new(8).unwrap(),
+    ///     Radix::new(10).unwrap(),
+    /// );
+    /// let account = OtpAccount { issuer: "rusotp".to_string(), name: "rusotp".to_string(), otp: OtpKind::HOTP(hotp, 2) };
+    ///
+    /// let uri = account.to_uri().unwrap();
+    /// assert_eq!(
+    ///     uri,
+    ///     "otpauth://hotp/rusotp%3Arusotp?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=rusotp&algorithm=SHA256&digits=8&counter=2"
+    /// );
+    /// assert_eq!(OtpAccount::from_uri(&uri).unwrap(), account);
+    /// ```
+    pub fn to_uri(&self) -> OtpResult<String> {
+        let otp = self.otp.parameters();
+        if otp.radix.get() != 10 {
+            return Err(Box::new(UnsupportedRadixError(otp.radix.get())));
+        }
+
+        let mut uri = format!(
+            "otpauth://{}/{}?secret={}&issuer={}&algorithm={}&digits={}",
+            otp.kind,
+            urlencoding::encode(&self.label()),
+            otp.secret.to_base32(),
+            urlencoding::encode(&self.issuer),
+            otp.algorithm.to_string(),
+            otp.length
+        );
+        if let Some(counter) = otp.counter {
+            uri.push_str(&format!("&counter={}", counter));
+        }
+        if let Some(period) = otp.period {
+            uri.push_str(&format!("&period={}", period));
+        }
+        Ok(uri)
+    }
+
+    /// Parses a Google Authenticator `otpauth-migration://offline?data=...` export URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - A migration URI string, as encoded in the export QR codes of Google Authenticator.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing every account of the payload, or an error if the payload is malformed or contains
+    /// an account which cannot be represented (e.g. MD5 based).
+    pub fn from_migration_uri(uri: &str) -> OtpResult<Vec<OtpAccount>> {
+        if !uri.starts_with("otpauth-migration://") {
+            return Err(unsupported_scheme(uri));
+        }
+        decode_migration_uri(uri)?
+            .into_iter()
+            .map(OtpAccount::from_migration_entry)
+            .collect()
+    }
+
+    /// Parses either an `otpauth://` or an `otpauth-migration://` URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI, e.g. the payload of a scanned QR code.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing all accounts encoded in the URI; a single one for `otpauth://` URIs.
+    pub fn parse(uri: &str) -> OtpResult<Vec<OtpAccount>> {
+        let uri = uri.trim();
+        if uri.starts_with("otpauth-migration://") {
+            OtpAccount::from_migration_uri(uri)
+        } else {
+            OtpAccount::from_uri(uri).map(|account| alloc::vec![account])
+        }
+    }
+
+    fn from_migration_entry(entry: MigrationEntry) -> OtpResult<OtpAccount> {
+        let algorithm = match entry.algorithm {
+            0 | 1 => Algorithm::SHA1,
+            2 => Algorithm::SHA256,
+            3 => Algorithm::SHA512,
+            _ => return Err(Box::new(InvalidUriError(format!("unsupported algorithm in `{}`", entry.name)))),
+        };
+        let length = match entry.digits {
+            0 | 1 => NonZeroU8::new(6).unwrap(),
+            2 => NonZeroU8::new(8).unwrap(),
+            _ => return Err(Box::new(InvalidUriError(format!("unsupported digits in `{}`", entry.name)))),
+        };
+        let secret = Secret::new_from_vec(entry.secret);
+        let radix = Radix::new(10).unwrap();
+
+        let otp = match entry.kind {
+            1 => OtpKind::HOTP(HOTP::new(algorithm, secret, length, radix), entry.counter),
+            2 => OtpKind::TOTP(TOTP::new(algorithm, secret, length, radix, NonZeroU64::new(30).unwrap())),
+            _ => return Err(Box::new(InvalidUriError(format!("unsupported OTP type in `{}`", entry.name)))),
+        };
+
+        let name = match entry.name.split_once(':') {
+            Some((issuer, name)) if issuer == entry.issuer => name.trim_start().to_owned(),
+            _ => entry.name,
+        };
+
+        Ok(OtpAccount {
+            issuer: entry.issuer,
+            name,
+            otp,
+        })
+    }
+}
+
+/// Returns an `InvalidUriError` naming only the scheme of `uri`, which may contain a secret.
+pub(crate) fn unsupported_scheme(uri: &str) -> Box<dyn OtpError> {
+    let scheme = uri.split_once("://").map_or("", |(scheme, _)| scheme);
+    Box::new(InvalidUriError(format!("unsupported scheme `{}`", scheme)))
+}
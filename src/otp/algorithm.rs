@@ -5,9 +5,16 @@
 //
 // See the file LICENSE for details.
 
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "blake2")]
+use blake2::{Blake2b512, Blake2s256};
+use hmac::digest::KeyInit;
 use hmac::Mac;
 use sha1::Sha1;
-use sha2::{Sha256, Sha512};
+use sha2::{Sha224, Sha256, Sha384, Sha512};
+#[cfg(feature = "sha3")]
+use sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
 
 /// Trait for defining common behavior for different hashing algorithms.
 ///
@@ -15,17 +22,51 @@ use sha2::{Sha256, Sha512};
 ///
 /// * `to_string` - Converts the algorithm to its string representation.
 /// * `from_string` - Creates an algorithm instance from its string representation.
-/// * `hash` - Hashes the given secret and data using the algorithm.
+/// * `hash` - Computes the HMAC of a message with the given key.
+///
+/// `HOTP` and `TOTP` are generic over this trait, so an HMAC computed elsewhere, e.g. by an HSM or a FIPS validated
+/// module, can be plugged in. The MAC must be at least 20 bytes long for the dynamic truncation of RFC 4226.
 ///
 /// # Errors
 ///
 /// The `hash` method returns an error if the hashing process fails.
+///
+/// # Example
+///
+/// ```
+/// use hmac::{Hmac, Mac};
+/// use rusotp::{AlgorithmTrait, Secret, HOTP};
+/// use sha1::Sha1;
+/// use std::num::NonZeroU8;
+///
+/// struct ExternalSha1;
+///
+/// impl AlgorithmTrait for ExternalSha1 {
+///     fn to_string(&self) -> String {
+///         "SHA1".to_string()
+///     }
+///
+///     fn from_string(name: String) -> Option<Self> {
+///         (name == "SHA1").then_some(ExternalSha1)
+///     }
+///
+///     fn hash(&self, key: &[u8], message: &[u8]) -> Result<Vec<u8>, String> {
+///         let mut mac = Hmac::<Sha1>::new_from_slice(key).map_err(|e| e.to_string())?;
+///         mac.update(message);
+///         Ok(mac.finalize().into_bytes().to_vec())
+///     }
+/// }
+///
+/// let secret = Secret::new_from_str("12345678901234567890").unwrap();
+/// let hotp = HOTP::new(ExternalSha1, secret, NonZeroU8::new(6).unwrap(), Default::default());
+/// assert_eq!(hotp.generate(0).unwrap(), "755224");
+/// ```
 pub trait AlgorithmTrait {
     fn to_string(&self) -> String;
     fn from_string(s: String) -> Option<Self>
     where
         Self: Sized;
-    fn hash(&self, secret: Vec<u8>, data: u64) -> Result<Vec<u8>, String>;
+    fn hash(&self, key: &[u8], message: &[u8]) -> Result<Vec<u8>, String>;
 }
 
 /// Enum representing the supported hashing algorithms.
@@ -33,13 +74,34 @@ pub trait AlgorithmTrait {
 /// # Variants
 ///
 /// * `SHA1` - Represents the SHA-1 hashing algorithm.
+/// * `SHA224` - Represents the SHA-224 hashing algorithm.
 /// * `SHA256` - Represents the SHA-256 hashing algorithm.
+/// * `SHA384` - Represents the SHA-384 hashing algorithm.
 /// * `SHA512` - Represents the SHA-512 hashing algorithm.
+/// * `SHA3_224`, `SHA3_256`, `SHA3_384`, `SHA3_512` - Represent the SHA-3 hashing algorithms (feature `sha3`).
+/// * `BLAKE2B512`, `BLAKE2S256` - Represent the BLAKE2b-512 and BLAKE2s-256 hashing algorithms (feature `blake2`).
+///
+/// The variants depend on the enabled features and more may be added, so matches outside the crate need a wildcard arm.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum Algorithm {
     SHA1,
+    SHA224,
     SHA256,
+    SHA384,
     SHA512,
+    #[cfg(feature = "sha3")]
+    SHA3_224,
+    #[cfg(feature = "sha3")]
+    SHA3_256,
+    #[cfg(feature = "sha3")]
+    SHA3_384,
+    #[cfg(feature = "sha3")]
+    SHA3_512,
+    #[cfg(feature = "blake2")]
+    BLAKE2B512,
+    #[cfg(feature = "blake2")]
+    BLAKE2S256,
 }
 
 impl AlgorithmTrait for Algorithm {
@@ -59,8 +121,22 @@ impl AlgorithmTrait for Algorithm {
     fn to_string(&self) -> String {
         match self {
             Algorithm::SHA1 => "SHA1".into(),
+            Algorithm::SHA224 => "SHA224".into(),
             Algorithm::SHA256 => "SHA256".into(),
+            Algorithm::SHA384 => "SHA384".into(),
             Algorithm::SHA512 => "SHA512".into(),
+            #[cfg(feature = "sha3")]
+            Algorithm::SHA3_224 => "SHA3-224".into(),
+            #[cfg(feature = "sha3")]
+            Algorithm::SHA3_256 => "SHA3-256".into(),
+            #[cfg(feature = "sha3")]
+            Algorithm::SHA3_384 => "SHA3-384".into(),
+            #[cfg(feature = "sha3")]
+            Algorithm::SHA3_512 => "SHA3-512".into(),
+            #[cfg(feature = "blake2")]
+            Algorithm::BLAKE2B512 => "BLAKE2B-512".into(),
+            #[cfg(feature = "blake2")]
+            Algorithm::BLAKE2S256 => "BLAKE2S-256".into(),
         }
     }
 
@@ -68,7 +144,9 @@ impl AlgorithmTrait for Algorithm {
     ///
     /// # Arguments
     ///
-    /// * `name` - A `String` representing the algorithm name (e\.g\., `"SHA1"`, `"SHA256"`, or `"SHA512"`)\.
+    /// * `name` - A `String` representing the algorithm name (e\.g\., `"SHA1"`, `"SHA256"`, or `"SHA512"`)\. Letter
+    ///   case, dashes, underscores and an `HMAC` prefix are ignored, so `"sha-256"`, `"HmacSHA256"` and
+    ///   `"HMAC-SHA3-256"` are accepted too\.
     ///
     /// # Returns
     ///
@@ -81,22 +159,42 @@ impl AlgorithmTrait for Algorithm {
     ///
     /// let algo = Algorithm::from_string("SHA256".to_string());
     /// assert_eq!(algo, Some(Algorithm::SHA256));
+    /// assert_eq!(Algorithm::from_string("HmacSHA384".to_string()), Some(Algorithm::SHA384));
     /// ```
     fn from_string(name: String) -> Option<Self> {
-        match name.as_str() {
+        let name: String = name
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        match name.strip_prefix("HMAC").unwrap_or(&name) {
             "SHA1" => Some(Algorithm::SHA1),
+            "SHA224" => Some(Algorithm::SHA224),
             "SHA256" => Some(Algorithm::SHA256),
+            "SHA384" => Some(Algorithm::SHA384),
             "SHA512" => Some(Algorithm::SHA512),
+            #[cfg(feature = "sha3")]
+            "SHA3224" => Some(Algorithm::SHA3_224),
+            #[cfg(feature = "sha3")]
+            "SHA3256" => Some(Algorithm::SHA3_256),
+            #[cfg(feature = "sha3")]
+            "SHA3384" => Some(Algorithm::SHA3_384),
+            #[cfg(feature = "sha3")]
+            "SHA3512" => Some(Algorithm::SHA3_512),
+            #[cfg(feature = "blake2")]
+            "BLAKE2B" | "BLAKE2B512" => Some(Algorithm::BLAKE2B512),
+            #[cfg(feature = "blake2")]
+            "BLAKE2S" | "BLAKE2S256" => Some(Algorithm::BLAKE2S256),
             _ => None,
         }
     }
 
-    /// Hashes the given secret and data using the selected algorithm.
+    /// Computes the HMAC of a message using the selected algorithm.
     ///
     /// # Arguments
     ///
-    /// * `secret` - A `Vec<u8>` representing the shared secret key.
-    /// * `data` - A `u64` value representing the data to be hashed.
+    /// * `key` - The shared secret key.
+    /// * `message` - The message to authenticate, the big-endian counter for HOTP and TOTP.
     ///
     /// # Returns
     ///
@@ -111,36 +209,42 @@ impl AlgorithmTrait for Algorithm {
     /// ```
     /// use rusotp::{Algorithm, AlgorithmTrait};
     ///
-    /// let secret = vec![1, 2, 3, 4, 5, 6, 7, 8];
+    /// let secret = [1, 2, 3, 4, 5, 6, 7, 8];
     /// let data = 123456u64;
     /// let algo = Algorithm::SHA256;
-    /// let result = algo.hash(secret, data);
+    /// let result = algo.hash(&secret, &data.to_be_bytes());
     /// assert!(result.is_ok());
     /// ```
-    fn hash(&self, secret: Vec<u8>, data: u64) -> Result<Vec<u8>, String> {
+    fn hash(&self, key: &[u8], message: &[u8]) -> Result<Vec<u8>, String> {
         match self {
-            Algorithm::SHA1 => match hmac::Hmac::<Sha1>::new_from_slice(secret.as_ref()) {
-                Ok(mut mac) => {
-                    mac.update(&data.to_be_bytes());
-                    Ok(mac.finalize().into_bytes().to_vec())
-                }
-                Err(e) => Err(e.to_string()),
-            },
-            Algorithm::SHA256 => match hmac::Hmac::<Sha256>::new_from_slice(secret.as_ref()) {
-                Ok(mut mac) => {
-                    mac.update(&data.to_be_bytes());
-                    Ok(mac.finalize().into_bytes().to_vec())
-                }
-                Err(e) => Err(e.to_string()),
-            },
-            Algorithm::SHA512 => match hmac::Hmac::<Sha512>::new_from_slice(secret.as_ref()) {
-                Ok(mut mac) => {
-                    mac.update(&data.to_be_bytes());
-                    Ok(mac.finalize().into_bytes().to_vec())
-                }
-                Err(e) => Err(e.to_string()),
-            },
+            Algorithm::SHA1 => mac::<hmac::Hmac<Sha1>>(key, message),
+            Algorithm::SHA224 => mac::<hmac::Hmac<Sha224>>(key, message),
+            Algorithm::SHA256 => mac::<hmac::Hmac<Sha256>>(key, message),
+            Algorithm::SHA384 => mac::<hmac::Hmac<Sha384>>(key, message),
+            Algorithm::SHA512 => mac::<hmac::Hmac<Sha512>>(key, message),
+            #[cfg(feature = "sha3")]
+            Algorithm::SHA3_224 => mac::<hmac::Hmac<Sha3_224>>(key, message),
+            #[cfg(feature = "sha3")]
+            Algorithm::SHA3_256 => mac::<hmac::Hmac<Sha3_256>>(key, message),
+            #[cfg(feature = "sha3")]
+            Algorithm::SHA3_384 => mac::<hmac::Hmac<Sha3_384>>(key, message),
+            #[cfg(feature = "sha3")]
+            Algorithm::SHA3_512 => mac::<hmac::Hmac<Sha3_512>>(key, message),
+            #[cfg(feature = "blake2")]
+            Algorithm::BLAKE2B512 => mac::<hmac::SimpleHmac<Blake2b512>>(key, message),
+            #[cfg(feature = "blake2")]
+            Algorithm::BLAKE2S256 => mac::<hmac::SimpleHmac<Blake2s256>>(key, message),
+        }
+    }
+}
+
+fn mac<M: Mac + KeyInit>(key: &[u8], message: &[u8]) -> Result<Vec<u8>, String> {
+    match <M as Mac>::new_from_slice(key) {
+        Ok(mut mac) => {
+            mac.update(message);
+            Ok(mac.finalize().into_bytes().to_vec())
         }
+        Err(e) => Err(e.to_string()),
     }
 }
 
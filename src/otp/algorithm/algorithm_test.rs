@@ -37,7 +37,7 @@ fn from_string_should_return_none_with_invalid_algorithm_name() {
 fn hash_should_be_generated_using_sha1() {
     let secret = b"mysecret".to_vec();
     let data = 12345u64;
-    let result = Algorithm::SHA1.hash(secret.clone(), data).unwrap();
+    let result = Algorithm::SHA1.hash(&secret, &data.to_be_bytes()).unwrap();
     let mut mac = hmac::Hmac::<Sha1>::new_from_slice(&secret).unwrap();
     mac.update(&data.to_be_bytes());
     assert_eq!(result, mac.finalize().into_bytes().to_vec());
@@ -47,7 +47,7 @@ fn hash_should_be_generated_using_sha1() {
 fn hash_should_be_generated_using_sha256() {
     let secret = b"mysecret".to_vec();
     let data = 12345u64;
-    let result = Algorithm::SHA256.hash(secret.clone(), data).unwrap();
+    let result = Algorithm::SHA256.hash(&secret, &data.to_be_bytes()).unwrap();
     let mut mac = hmac::Hmac::<Sha256>::new_from_slice(&secret).unwrap();
     mac.update(&data.to_be_bytes());
     assert_eq!(result, mac.finalize().into_bytes().to_vec());
@@ -57,8 +57,122 @@ fn hash_should_be_generated_using_sha256() {
 fn hash_should_be_generated_using_sha512() {
     let secret = b"mysecret".to_vec();
     let data = 12345u64;
-    let result = Algorithm::SHA512.hash(secret.clone(), data).unwrap();
+    let result = Algorithm::SHA512.hash(&secret, &data.to_be_bytes()).unwrap();
     let mut mac = hmac::Hmac::<Sha512>::new_from_slice(&secret).unwrap();
     mac.update(&data.to_be_bytes());
     assert_eq!(result, mac.finalize().into_bytes().to_vec());
 }
+
+#[test]
+fn from_string_should_accept_common_spellings() {
+    for name in [
+        "SHA256",
+        "sha256",
+        "sha-256",
+        "SHA_256",
+        "HmacSHA256",
+        "HMAC-SHA256",
+        "hmac-sha-256",
+    ] {
+        assert_eq!(Algorithm::from_string(name.to_string()), Some(Algorithm::SHA256), "{}", name);
+    }
+    assert_eq!(Algorithm::from_string("sha-1".to_string()), Some(Algorithm::SHA1));
+    assert_eq!(Algorithm::from_string("HmacSHA224".to_string()), Some(Algorithm::SHA224));
+    assert_eq!(Algorithm::from_string("SHA-384".to_string()), Some(Algorithm::SHA384));
+    assert_eq!(Algorithm::from_string("HMAC".to_string()), None);
+}
+
+#[test]
+fn to_string_should_round_trip_through_from_string() {
+    for algorithm in [
+        Algorithm::SHA1,
+        Algorithm::SHA224,
+        Algorithm::SHA256,
+        Algorithm::SHA384,
+        Algorithm::SHA512,
+    ] {
+        assert_eq!(Algorithm::from_string(algorithm.to_string()), Some(algorithm));
+    }
+}
+
+fn known_answer(algorithm: Algorithm) -> String {
+    let mac = algorithm.hash(b"mysecret", &12345u64.to_be_bytes()).unwrap();
+    mac.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+fn hash_should_match_known_answer_using_sha224() {
+    assert_eq!(known_answer(Algorithm::SHA224), "8fa409e29e2f36d5589468ecada5c27b3f93138085f8ba76ad4ef0d0");
+}
+
+#[test]
+fn hash_should_match_known_answer_using_sha384() {
+    assert_eq!(
+        known_answer(Algorithm::SHA384),
+        "11ce3e05e506deffaf4e9d96eff42876961a07155fbc488dc1af364698c23c09edf58e2194a76acbb493c9a4da20681f"
+    );
+}
+
+#[cfg(feature = "sha3")]
+#[test]
+fn sha3_should_be_parsed_and_printed() {
+    assert_eq!(Algorithm::from_string("SHA3-256".to_string()), Some(Algorithm::SHA3_256));
+    assert_eq!(Algorithm::from_string("HmacSHA3-512".to_string()), Some(Algorithm::SHA3_512));
+    assert_eq!(Algorithm::from_string("sha3_224".to_string()), Some(Algorithm::SHA3_224));
+    assert_eq!(Algorithm::SHA3_384.to_string(), "SHA3-384");
+    for algorithm in [
+        Algorithm::SHA3_224,
+        Algorithm::SHA3_256,
+        Algorithm::SHA3_384,
+        Algorithm::SHA3_512,
+    ] {
+        assert_eq!(Algorithm::from_string(algorithm.to_string()), Some(algorithm));
+    }
+}
+
+#[cfg(feature = "sha3")]
+#[test]
+fn hash_should_match_known_answers_using_sha3() {
+    assert_eq!(known_answer(Algorithm::SHA3_224), "f31f4c551027b82e3e05aa94f657886c5b44d52d0d7132b487573c58");
+    assert_eq!(known_answer(Algorithm::SHA3_256), "7ac1ff2076f2c1154d15dbc5953e13d287eb95c15c745f9579361a3b95c4e637");
+    assert_eq!(
+        known_answer(Algorithm::SHA3_384),
+        "ea9001f6cbbfde9cf3a2e01d824a9e5e2711c7c468d60c2d3ae815285fd85e8de975e919b056531b75e2c21fb88dcba2"
+    );
+    assert_eq!(
+        known_answer(Algorithm::SHA3_512),
+        "c958a60453058b2057584d5017e47c94eeccd348898df29a611e632b212b6500\
+         231cd31b4e3b3fea410daaa07ff628389c9c424b459e7083df2c6adec6757b14"
+    );
+}
+
+#[cfg(feature = "blake2")]
+#[test]
+fn blake2_should_be_parsed_and_printed() {
+    assert_eq!(Algorithm::from_string("BLAKE2b-512".to_string()), Some(Algorithm::BLAKE2B512));
+    assert_eq!(Algorithm::from_string("blake2s".to_string()), Some(Algorithm::BLAKE2S256));
+    assert_eq!(Algorithm::BLAKE2B512.to_string(), "BLAKE2B-512");
+    for algorithm in [Algorithm::BLAKE2B512, Algorithm::BLAKE2S256] {
+        assert_eq!(Algorithm::from_string(algorithm.to_string()), Some(algorithm));
+    }
+}
+
+#[cfg(feature = "blake2")]
+#[test]
+fn hash_should_match_known_answers_using_blake2() {
+    assert_eq!(
+        known_answer(Algorithm::BLAKE2B512),
+        "d11f397cbc7ef4e0023933f9f7e1874bf741085e7e4124065c71e8ec2d4f2664\
+         02f3a0fbf71bb97f26432930d758fe7d285da8aa852246dba4187732016a731f"
+    );
+    assert_eq!(known_answer(Algorithm::BLAKE2S256), "b66c9f23d3234976cde29a216a492b9b6abe7e63475ac145f8c7509df7b5cd17");
+}
+
+#[test]
+fn hash_should_authenticate_arbitrary_message() {
+    let message = b"arbitrary message bytes";
+    let result = Algorithm::SHA256.hash(b"mysecret", message).unwrap();
+    let mut mac = hmac::Hmac::<Sha256>::new_from_slice(b"mysecret").unwrap();
+    mac.update(message);
+    assert_eq!(result, mac.finalize().into_bytes().to_vec());
+}
@@ -5,13 +5,23 @@
 //
 // See the file LICENSE for details.
 
-use crate::otp::algorithm::{Algorithm, AlgorithmTrait};
+use crate::otp::algorithm::AlgorithmTrait;
+use crate::KeyProvider;
 use crate::{OtpGenericError, OtpResult};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::ops::Rem;
 use num_bigint::BigUint;
-use std::ops::Rem;
 
-pub(crate) fn otp(algorithm: &Algorithm, secret: Vec<u8>, length: u8, radix: u8, counter: u64) -> OtpResult<String> {
-    match otp_bin_code(algorithm, secret, counter) {
+pub(crate) fn otp<A: AlgorithmTrait, K: KeyProvider>(
+    algorithm: &A,
+    key: &K,
+    length: u8,
+    radix: u8,
+    counter: u64,
+) -> OtpResult<String> {
+    match otp_bin_code(algorithm, key, counter) {
         Ok(otp_bin_code) => {
             let value = BigUint::to_str_radix(
                 &BigUint::from(otp_bin_code).rem(BigUint::from(radix as u64).pow(length as u32)),
@@ -23,8 +33,11 @@ pub(crate) fn otp(algorithm: &Algorithm, secret: Vec<u8>, length: u8, radix: u8,
     }
 }
 
-fn otp_bin_code(algorithm: &Algorithm, secret: Vec<u8>, counter: u64) -> Result<u64, String> {
-    match algorithm.hash(secret, counter) {
+fn otp_bin_code<A: AlgorithmTrait, K: KeyProvider>(algorithm: &A, key: &K, counter: u64) -> Result<u64, String> {
+    match key.hmac(algorithm, &counter.to_be_bytes()) {
+        Ok(hmac_result) if hmac_result.len() < 20 => {
+            Err(format!("HMAC of {} must be at least 20 bytes long", algorithm.to_string()))
+        }
         Ok(hmac_result) => {
             let offset = (hmac_result[hmac_result.len() - 1] & 0x0f) as usize;
 
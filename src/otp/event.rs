@@ -0,0 +1,154 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use core::fmt::{Display, Formatter};
+
+/// The kind of generator an [`OtpEvent`] was raised by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtpEventSource {
+    HOTP,
+    TOTP,
+    MOTP,
+    YAOTP,
+}
+
+/// Something that happened while generating or verifying an OTP, reported to an [`OtpEventSink`].
+///
+/// `counter` is the HOTP counter, or the TOTP, mOTP or Yandex Key time step (the timestamp divided by the interval).
+/// `drift` is the distance of the matched counter or time step from the expected one. Events never carry the secret
+/// nor the code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtpEvent {
+    /// An OTP was generated for `counter`.
+    Generated { source: OtpEventSource, counter: u64 },
+    /// An OTP was accepted as the one of `counter`.
+    Verified {
+        source: OtpEventSource,
+        counter: u64,
+        drift: i64,
+    },
+    /// An OTP matched none of the accepted counters; `counter` is the expected one.
+    Rejected { source: OtpEventSource, counter: u64 },
+    /// An OTP was rejected because it is the one of `counter`, which was already used.
+    Replayed { source: OtpEventSource, counter: u64 },
+    /// Two consecutive OTPs were found, the second one being the one of `counter`.
+    Resynced {
+        source: OtpEventSource,
+        counter: u64,
+        drift: i64,
+    },
+}
+
+impl OtpEvent {
+    /// Returns the kind of generator which raised the event.
+    pub fn source(&self) -> OtpEventSource {
+        match self {
+            OtpEvent::Generated { source, .. }
+            | OtpEvent::Verified { source, .. }
+            | OtpEvent::Rejected { source, .. }
+            | OtpEvent::Replayed { source, .. }
+            | OtpEvent::Resynced { source, .. } => *source,
+        }
+    }
+
+    /// Returns the counter or time step the event is about.
+    pub fn counter(&self) -> u64 {
+        match self {
+            OtpEvent::Generated { counter, .. }
+            | OtpEvent::Verified { counter, .. }
+            | OtpEvent::Rejected { counter, .. }
+            | OtpEvent::Replayed { counter, .. }
+            | OtpEvent::Resynced { counter, .. } => *counter,
+        }
+    }
+}
+
+impl Display for OtpEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let unit = match self.source() {
+            OtpEventSource::HOTP => "counter",
+            OtpEventSource::TOTP | OtpEventSource::MOTP | OtpEventSource::YAOTP => "time step",
+        };
+        match self {
+            OtpEvent::Generated { source, counter } => write!(f, "{:?} generated for {} {}", source, unit, counter),
+            OtpEvent::Verified { source, counter, drift } => {
+                write!(f, "{:?} verified at {} {} (drift {})", source, unit, counter, drift)
+            }
+            OtpEvent::Rejected { source, counter } => write!(f, "{:?} rejected at {} {}", source, unit, counter),
+            OtpEvent::Replayed { source, counter } => {
+                write!(f, "{:?} replayed for used {} {}", source, unit, counter)
+            }
+            OtpEvent::Resynced { source, counter, drift } => {
+                write!(f, "{:?} resynchronized at {} {} (drift {})", source, unit, counter, drift)
+            }
+        }
+    }
+}
+
+/// Receives the events of an `HOTP` or `TOTP`, e.g. to keep an audit trail of verifications.
+///
+/// A sink is attached with `HOTP::with_event_sink` or `TOTP::with_event_sink`; it is called synchronously, so it
+/// should not block. Closures taking a `&OtpEvent` are sinks.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::{OtpEvent, Secret, HOTP};
+/// use std::sync::{Arc, Mutex};
+///
+/// let events = Arc::new(Mutex::new(Vec::new()));
+/// let recorded = events.clone();
+/// let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap())
+///     .with_event_sink(Arc::new(move |event: &OtpEvent| recorded.lock().unwrap().push(*event)));
+///
+/// hotp.verify("000000", 0, 0).unwrap();
+/// assert!(matches!(events.lock().unwrap()[0], OtpEvent::Rejected { counter: 0, .. }));
+/// ```
+pub trait OtpEventSink: Send + Sync {
+    /// Records `event`.
+    fn record(&self, event: &OtpEvent);
+}
+
+impl<F> OtpEventSink for F
+where
+    F: Fn(&OtpEvent) + Send + Sync,
+{
+    fn record(&self, event: &OtpEvent) {
+        self(event)
+    }
+}
+
+/// Discards all events; the sink of an `HOTP` or `TOTP` unless another one is attached.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopEventSink;
+
+impl OtpEventSink for NoopEventSink {
+    fn record(&self, _event: &OtpEvent) {}
+}
+
+/// Writes events to the [`log`](https://docs.rs/log) facade under the `rusotp` target.
+///
+/// Rejected and replayed codes are logged as warnings, verifications and resynchronizations as information and
+/// generated codes at debug level.
+#[cfg(feature = "log")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogEventSink;
+
+#[cfg(feature = "log")]
+impl OtpEventSink for LogEventSink {
+    fn record(&self, event: &OtpEvent) {
+        let level = match event {
+            OtpEvent::Generated { .. } => log::Level::Debug,
+            OtpEvent::Verified { .. } | OtpEvent::Resynced { .. } => log::Level::Info,
+            OtpEvent::Rejected { .. } | OtpEvent::Replayed { .. } => log::Level::Warn,
+        };
+        log::log!(target: "rusotp", level, "{}", event);
+    }
+}
+
+#[cfg(test)]
+mod event_test;
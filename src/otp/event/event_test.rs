@@ -0,0 +1,80 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{OtpEvent, OtpEventSource};
+
+#[test]
+fn to_string_should_describe_event_without_code() {
+    let verified = OtpEvent::Verified {
+        source: OtpEventSource::HOTP,
+        counter: 3,
+        drift: 2,
+    };
+    let replayed = OtpEvent::Replayed {
+        source: OtpEventSource::TOTP,
+        counter: 41,
+    };
+
+    assert_eq!(verified.to_string(), "HOTP verified at counter 3 (drift 2)");
+    assert_eq!(replayed.to_string(), "TOTP replayed for used time step 41");
+}
+
+#[test]
+fn accessors_should_return_source_and_counter() {
+    let event = OtpEvent::Resynced {
+        source: OtpEventSource::TOTP,
+        counter: 7,
+        drift: -1,
+    };
+
+    assert_eq!(event.source(), OtpEventSource::TOTP);
+    assert_eq!(event.counter(), 7);
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn log_event_sink_should_log_at_event_level() {
+    use crate::{LogEventSink, OtpEventSink};
+    use std::sync::Mutex;
+
+    struct Capture(Mutex<Vec<(log::Level, String, String)>>);
+
+    impl log::Log for Capture {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let entry = (record.level(), record.target().to_string(), record.args().to_string());
+            self.0.lock().unwrap().push(entry);
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+    log::set_logger(&CAPTURE).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    LogEventSink.record(&OtpEvent::Rejected {
+        source: OtpEventSource::HOTP,
+        counter: 5,
+    });
+    LogEventSink.record(&OtpEvent::Generated {
+        source: OtpEventSource::TOTP,
+        counter: 1,
+    });
+
+    let logged = CAPTURE.0.lock().unwrap();
+    assert_eq!(
+        *logged,
+        vec![
+            (log::Level::Warn, "rusotp".to_string(), "HOTP rejected at counter 5".to_string()),
+            (log::Level::Debug, "rusotp".to_string(), "TOTP generated for time step 1".to_string()),
+        ]
+    );
+}
@@ -5,23 +5,32 @@
 //
 // See the file LICENSE for details.
 
-use crate::otp::algorithm::Algorithm;
+use crate::otp::algorithm::{Algorithm, AlgorithmTrait};
 use crate::otp::base::otp;
-use crate::{
-    InvalidSecretError, OtpResult, Radix, Secret, UnsupportedAlgorithmError, UnsupportedLengthError,
-    UnsupportedRadixError,
-};
+use crate::otp::event::{NoopEventSink, OtpEvent, OtpEventSink, OtpEventSource};
+#[cfg(all(feature = "uri", feature = "qr"))]
+use crate::ProvisioningQr;
+#[cfg(feature = "uri")]
+use crate::{InvalidSecretError, UnsupportedAlgorithmError, UnsupportedLengthError, UnsupportedRadixError};
+use crate::{KeyProvider, OtpResult, Radix, Secret, VerifyOutcome};
+use alloc::string::String;
+use alloc::sync::Arc;
+#[cfg(feature = "uri")]
 use base32ct::{Base32, Encoding};
-use std::num::NonZeroU8;
+use core::fmt::{Debug, Formatter};
+use core::num::NonZeroU8;
 
 /// Represents an HOTP (HMAC-based One-Time Password) generator.
 ///
 /// # Fields
 ///
-/// * `algorithm` - The hashing algorithm used for HOTP generation.
-/// * `secret` - The shared secret key used for HOTP generation.
+/// * `algorithm` - The hashing algorithm used for HOTP generation, an `Algorithm` unless another `AlgorithmTrait`
+///   implementation is given to `new`.
+/// * `secret` - The shared secret key used for HOTP generation, a `Secret` unless another `KeyProvider` is given to
+///   `new`.
 /// * `length` - The length of the generated OTP.
 /// * `radix` - The radix (base) used for the OTP representation.
+/// * `events` - The sink notified of generations and verifications, see `with_event_sink`.
 ///
 /// # Example
 ///
@@ -38,21 +47,42 @@ use std::num::NonZeroU8;
 /// let otp = hotp.generate(1).unwrap();
 /// println!("Generated OTP: {}", otp);
 /// ```
-#[derive(Debug, PartialEq)]
-pub struct HOTP {
-    pub(crate) algorithm: Algorithm,
-    pub(crate) secret: Secret,
+#[derive(Clone)]
+pub struct HOTP<A = Algorithm, K = Secret> {
+    pub(crate) algorithm: A,
+    pub(crate) secret: K,
     pub(crate) length: NonZeroU8,
     pub(crate) radix: Radix,
+    pub(crate) events: Arc<dyn OtpEventSink>,
 }
 
-impl HOTP {
+impl<A: Debug, K: Debug> Debug for HOTP<A, K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HOTP")
+            .field("algorithm", &self.algorithm)
+            .field("secret", &self.secret)
+            .field("length", &self.length)
+            .field("radix", &self.radix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: PartialEq, K: PartialEq> PartialEq for HOTP<A, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm == other.algorithm
+            && self.secret == other.secret
+            && self.length == other.length
+            && self.radix == other.radix
+    }
+}
+
+impl<A: AlgorithmTrait, K: KeyProvider> HOTP<A, K> {
     /// Creates a new HOTP instance with the specified algorithm, secret, length, and radix.
     ///
     /// # Arguments
     ///
     /// * `algorithm` - The hashing algorithm to be used.
-    /// * `secret` - The shared secret key as a string.
+    /// * `secret` - The shared secret key, or a `KeyProvider` computing the HMAC with it.
     /// * `length` - The length of the OTP to be generated.
     /// * `radix` - The radix (base) for the OTP representation.
     ///
@@ -73,55 +103,58 @@ impl HOTP {
     ///
     /// let hotp = HOTP::new(Algorithm::SHA1, secret, length, radix);
     /// ```
-    pub fn new(algorithm: Algorithm, secret: Secret, length: NonZeroU8, radix: Radix) -> HOTP {
+    pub fn new(algorithm: A, secret: K, length: NonZeroU8, radix: Radix) -> HOTP<A, K> {
         Self {
             algorithm,
             secret,
             length,
             radix,
+            events: Arc::new(NoopEventSink),
         }
     }
 
-    /// Returns a HOTP configured with RFC 4226 recommended defaults:
-    /// - `algorithm`: SHA1
-    /// - `length`: 6 digits
-    /// - `radix`: 10 (decimal)
+    /// Attaches a sink notified of every generated OTP, verification, replay and resynchronization.
     ///
-    /// The provided `secret` is used as the shared key for HOTP generation.
+    /// # Arguments
+    ///
+    /// * `events` - The sink, e.g. `LogEventSink` or a closure taking an `&OtpEvent`.
     ///
     /// # Example
     ///
     /// ```
-    /// use rusotp::{Secret, HOTP};
-    ///
-    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    /// use rusotp::{OtpEvent, Secret, HOTP};
+    /// use std::sync::Arc;
     ///
-    /// let hotp = HOTP::default(secret);
+    /// let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap())
+    ///     .with_event_sink(Arc::new(|event: &OtpEvent| println!("{}", event)));
+    /// hotp.generate(1).unwrap();
     /// ```
-    pub fn default(secret: Secret) -> HOTP {
-        Self::new(Algorithm::SHA1, secret, NonZeroU8::new(6).unwrap(), Radix::new(10).unwrap())
+    pub fn with_event_sink(mut self, events: Arc<dyn OtpEventSink>) -> HOTP<A, K> {
+        self.events = events;
+        self
     }
 
-    /// Returns a HOTP configured with RFC 4226 recommended defaults:
-    /// - Algorithm: SHA1
-    /// - Length: 6 digits
-    /// - Radix: 10 (decimal)
-    ///
-    /// The provided `secret` is used as the shared key for HOTP generation.
-    ///
-    /// Convenience constructor equivalent to `HOTP::default`.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use rusotp::{Secret, HOTP};
-    ///
-    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
-    ///
-    /// let hotp = HOTP::rfc4226_default(secret);
-    /// ```
-    pub fn rfc4226_default(secret: Secret) -> HOTP {
-        Self::default(secret)
+    /// Returns the hashing algorithm used for OTP generation.
+    pub fn algorithm(&self) -> A
+    where
+        A: Clone,
+    {
+        self.algorithm.clone()
+    }
+
+    /// Returns the shared secret key, or its provider.
+    pub fn secret(&self) -> &K {
+        &self.secret
+    }
+
+    /// Returns the length of the generated OTP.
+    pub fn length(&self) -> NonZeroU8 {
+        self.length
+    }
+
+    /// Returns the radix (base) used for the OTP representation.
+    pub fn radix(&self) -> Radix {
+        self.radix
     }
 
     /// Generates an OTP based on the provided counter value.
@@ -151,7 +184,41 @@ impl HOTP {
     /// println!("Generated OTP: {}", otp);
     /// ```
     pub fn generate(&self, counter: u64) -> OtpResult<String> {
-        otp(&self.algorithm, self.secret.clone().get(), self.length.get(), self.radix.get(), counter)
+        let otp = self.code(counter)?;
+        self.emit(OtpEvent::Generated {
+            source: OtpEventSource::HOTP,
+            counter,
+        });
+        Ok(otp)
+    }
+
+    /// Generates the OTPs of a range of counters, e.g. to print a code sheet.
+    ///
+    /// # Arguments
+    ///
+    /// * `counters` - The counters, e.g. `0..10`.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of `Result`s containing each counter and its OTP.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, HOTP};
+    ///
+    /// let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let codes: Vec<_> = hotp.codes(1..=2).map(Result::unwrap).collect();
+    /// assert_eq!(codes, vec![(1, "287082".to_string()), (2, "359152".to_string())]);
+    /// ```
+    pub fn codes<'a, R>(&'a self, counters: R) -> impl Iterator<Item = OtpResult<(u64, String)>> + 'a
+    where
+        R: IntoIterator<Item = u64>,
+        R::IntoIter: 'a,
+    {
+        counters
+            .into_iter()
+            .map(move |counter| Ok((counter, self.generate(counter)?)))
     }
 
     /// Verifies an OTP based on the provided counter value and retries.
@@ -165,6 +232,7 @@ impl HOTP {
     /// # Returns
     ///
     /// A `Result` containing an `Option<u64>` with the counter value if the OTP is verified, or `None` if the OTP is not verified. Returns a `String` with the error message if the verification fails.
+    /// See `verify_window` for the drift and the counter to expect next.
     ///
     /// # Errors
     ///
@@ -187,21 +255,161 @@ impl HOTP {
     /// assert_eq!(verified, Some(1));
     /// ```
     pub fn verify(&self, otp: &str, counter: u64, retries: u64) -> OtpResult<Option<u64>> {
-        if self.length.get() != otp.len() as u8 {
-            Ok(None)
-        } else {
-            for i in counter..=(counter + retries) {
-                match self.generate(i) {
-                    Ok(generated_otp) => {
-                        if otp == generated_otp {
-                            return Ok(Some(i));
-                        }
-                    }
-                    Err(e) => return Err(e),
+        Ok(self
+            .verify_window(otp, counter, retries)?
+            .map(|outcome| outcome.counter))
+    }
+
+    /// Verifies an OTP against the expected counter and the `ahead` following ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP to be verified as a string.
+    /// * `counter` - The expected counter value, usually the `next` of the last outcome.
+    /// * `ahead` - The number of counters after `counter` to accept, for authenticators which generated OTPs that were
+    ///   never verified.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `VerifyOutcome` with the matched counter, its drift from `counter` and the counter to
+    /// expect next if the OTP is verified, or `None` if it is not, including when it has the wrong length or only
+    /// matches `u64::MAX`, after which no counter is left to expect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, HOTP};
+    ///
+    /// let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let outcome = hotp.verify_window("359152", 0, 5).unwrap().unwrap();
+    /// assert_eq!((outcome.counter, outcome.drift, outcome.next), (2, 2, 3));
+    /// assert_eq!(hotp.verify_window("359152", outcome.next, 5).unwrap(), None);
+    /// ```
+    pub fn verify_window(&self, otp: &str, counter: u64, ahead: u64) -> OtpResult<Option<VerifyOutcome>> {
+        if self.length.get() == otp.len() as u8 {
+            for i in counter..=counter.saturating_add(ahead).min(VerifyOutcome::LAST) {
+                if otp == self.code(i)? {
+                    let outcome = VerifyOutcome::new(i, counter);
+                    self.emit(OtpEvent::Verified {
+                        source: OtpEventSource::HOTP,
+                        counter: i,
+                        drift: outcome.drift,
+                    });
+                    return Ok(Some(outcome));
+                }
+            }
+            if counter > 0 && otp == self.code(counter - 1)? {
+                self.emit(OtpEvent::Replayed {
+                    source: OtpEventSource::HOTP,
+                    counter: counter - 1,
+                });
+                return Ok(None);
+            }
+        }
+        self.emit(OtpEvent::Rejected {
+            source: OtpEventSource::HOTP,
+            counter,
+        });
+        Ok(None)
+    }
+
+    /// Resynchronizes with an authenticator whose counter ran ahead, by searching two consecutive OTPs.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The OTP displayed first by the authenticator.
+    /// * `second` - The OTP displayed right after `first`.
+    /// * `counter` - The expected counter value.
+    /// * `window` - The number of counters after `counter` to search.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an `Option<u64>` with the counter of `second` if both OTPs were found, or `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, HOTP};
+    ///
+    /// let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let resynced = hotp.resync("399871", "520489", 0, 100).unwrap();
+    /// assert_eq!(resynced, Some(9));
+    /// ```
+    pub fn resync(&self, first: &str, second: &str, counter: u64, window: u64) -> OtpResult<Option<u64>> {
+        let lengths_match = self.length.get() == first.len() as u8 && self.length.get() == second.len() as u8;
+        // `second` is searched up to u64::MAX, the last valid counter.
+        if let Some(start) = counter.checked_add(1).filter(|_| lengths_match) {
+            let mut previous = self.code(counter)?;
+            for i in start..=counter.saturating_add(window).saturating_add(1) {
+                let current = self.code(i)?;
+                if first == previous && second == current {
+                    self.emit(OtpEvent::Resynced {
+                        source: OtpEventSource::HOTP,
+                        counter: i,
+                        drift: (i - counter) as i64,
+                    });
+                    return Ok(Some(i));
                 }
+                previous = current;
             }
-            Ok(None)
         }
+        self.emit(OtpEvent::Rejected {
+            source: OtpEventSource::HOTP,
+            counter,
+        });
+        Ok(None)
+    }
+
+    fn code(&self, counter: u64) -> OtpResult<String> {
+        otp(&self.algorithm, &self.secret, self.length.get(), self.radix.get(), counter)
+    }
+
+    fn emit(&self, event: OtpEvent) {
+        self.events.record(&event);
+    }
+}
+
+impl HOTP {
+    /// Returns a HOTP configured with RFC 4226 recommended defaults:
+    /// - `algorithm`: SHA1
+    /// - `length`: 6 digits
+    /// - `radix`: 10 (decimal)
+    ///
+    /// The provided `secret` is used as the shared key for HOTP generation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, HOTP};
+    ///
+    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    ///
+    /// let hotp = HOTP::default(secret);
+    /// ```
+    pub fn default(secret: Secret) -> HOTP {
+        Self::new(Algorithm::SHA1, secret, NonZeroU8::new(6).unwrap(), Radix::new(10).unwrap())
+    }
+
+    /// Returns a HOTP configured with RFC 4226 recommended defaults:
+    /// - Algorithm: SHA1
+    /// - Length: 6 digits
+    /// - Radix: 10 (decimal)
+    ///
+    /// The provided `secret` is used as the shared key for HOTP generation.
+    ///
+    /// Convenience constructor equivalent to `HOTP::default`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, HOTP};
+    ///
+    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    ///
+    /// let hotp = HOTP::rfc4226_default(secret);
+    /// ```
+    pub fn rfc4226_default(secret: Secret) -> HOTP {
+        Self::default(secret)
     }
 
     /// Generates a provisioning URI for HOTP based on the provided name and initial counter value.
@@ -235,6 +443,7 @@ impl HOTP {
     /// let uri = hotp.provisioning_uri("rusotp", "rusotp", 1).unwrap();
     /// println!("Provisioning URI: {}", uri);
     /// ```
+    #[cfg(feature = "uri")]
     pub fn provisioning_uri(&self, issuer: &str, user: &str, counter: u64) -> OtpResult<String> {
         if self.length.get() != 6 {
             Err(Box::new(UnsupportedLengthError(self.length.get())))
@@ -253,6 +462,36 @@ impl HOTP {
         }
     }
 
+    /// Generates a provisioning QR code for HOTP, encoding the URI returned by `provisioning_uri`.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The issuer of the HOTP as a string.
+    /// * `user` - The name of the user or account as a string.
+    /// * `counter` - The initial counter value used in the HOTP generation.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `ProvisioningQr` that can be rendered as SVG, PNG or terminal output.
+    ///
+    /// # Errors
+    ///
+    /// Same as `provisioning_uri`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, HOTP};
+    ///
+    /// let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let qr = hotp.provisioning_qr("rusotp", "user@email.mail", 1).unwrap();
+    /// println!("{}", qr.terminal().unwrap());
+    /// ```
+    #[cfg(all(feature = "uri", feature = "qr"))]
+    pub fn provisioning_qr(&self, issuer: &str, user: &str, counter: u64) -> OtpResult<ProvisioningQr> {
+        self.provisioning_uri(issuer, user, counter).map(ProvisioningQr::new)
+    }
+
     /// Parse an `otpauth://hotp/...` provisioning URI and construct an `HOTP` instance.
     ///
     /// The function extracts query parameters from the provided `uri` and uses them to
@@ -269,8 +508,7 @@ impl HOTP {
     ///
     /// Returns `OtpResult<HOTP>`:
     /// - `Ok(HOTP)` on success (the returned `HOTP` uses RFC4226 defaults except for the provided secret).
-    /// - `Err` if the `secret` parameter is missing or if parsing/decoding fails (note: this implementation
-    ///   uses `unwrap()` on some decoding/parsing operations, which will panic on invalid input).
+    /// - `Err` if the `secret` parameter is missing or is not valid Base32 (letter case and padding are ignored).
     ///
     /// # Example
     ///
@@ -283,6 +521,7 @@ impl HOTP {
     /// let otp = hotp.generate(1).unwrap();
     /// assert_eq!(otp.len(), 6);
     /// ```
+    #[cfg(feature = "uri")]
     pub fn from_uri(uri: &str) -> OtpResult<HOTP> {
         let params = uri.split('?').next_back().unwrap().split('&');
 
@@ -291,9 +530,8 @@ impl HOTP {
         for param in params {
             if let Some((key, value)) = param.split_once('=') {
                 if key == "secret" && !value.is_empty() {
-                    secret = Some(Secret::new_from_vec(
-                        Base32::decode_vec(urlencoding::decode(value).unwrap().trim()).unwrap(),
-                    ))
+                    let value = urlencoding::decode(value).map_err(|_| Box::new(InvalidSecretError()) as _)?;
+                    secret = Some(Secret::new_from_base32(&value)?)
                 }
             }
         }
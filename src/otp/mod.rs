@@ -7,6 +7,16 @@
 
 pub mod algorithm;
 
+#[cfg(feature = "uri")]
+pub(crate) mod account;
+
 pub(crate) mod base;
+pub(crate) mod event;
 pub(crate) mod hotp;
+#[cfg(feature = "motp")]
+pub(crate) mod motp;
 pub(crate) mod totp;
+#[cfg(feature = "std")]
+pub(crate) mod watch;
+#[cfg(feature = "yandex")]
+pub(crate) mod yandex;
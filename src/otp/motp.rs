@@ -0,0 +1,245 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::otp::event::{NoopEventSink, OtpEvent, OtpEventSink, OtpEventSource};
+#[cfg(feature = "std")]
+use crate::util::time::now;
+use crate::{AfterError, DriftBehindError, OtpResult, Secret};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::fmt::{Debug, Formatter, Write};
+use md5::{Digest, Md5};
+
+/// The time step, in seconds, of Mobile-OTP codes.
+const INTERVAL: u64 = 10;
+
+/// The number of hexadecimal digits of Mobile-OTP codes.
+const LENGTH: usize = 6;
+
+/// Represents a Mobile-OTP (mOTP) generator, the legacy time-based scheme of the Mobile-OTP apps.
+///
+/// A code is the first 6 hexadecimal digits of `MD5(timestamp / 10 || secret || PIN)`, where the time step is
+/// written in decimal and the secret is the init-secret as entered in the app (usually 16 hexadecimal characters).
+///
+/// # Fields
+///
+/// * `secret` - The init-secret shared with the app.
+/// * `pin` - The PIN the user enters in the app.
+/// * `events` - The sink notified of generations and verifications, see `with_event_sink`.
+#[derive(Clone)]
+pub struct MOtp {
+    pub(crate) secret: Secret,
+    pub(crate) pin: String,
+    pub(crate) events: Arc<dyn OtpEventSink>,
+}
+
+impl Debug for MOtp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MOtp")
+            .field("secret", &self.secret)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for MOtp {
+    fn eq(&self, other: &Self) -> bool {
+        self.secret == other.secret && self.pin == other.pin
+    }
+}
+
+impl MOtp {
+    /// Creates a new mOTP instance with the specified init-secret and PIN.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The init-secret, e.g. `Secret::new_from_str("1234567890abcdef")`.
+    /// * `pin` - The PIN entered in the app, usually 4 digits.
+    ///
+    /// # Returns
+    ///
+    /// An `MOtp` instance
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{MOtp, Secret};
+    ///
+    /// let motp = MOtp::new(Secret::new_from_str("1234567890abcdef").unwrap(), "1234");
+    /// assert_eq!(motp.generate_at(1111111109).unwrap(), "934350");
+    /// ```
+    pub fn new(secret: Secret, pin: &str) -> MOtp {
+        Self {
+            secret,
+            pin: String::from(pin),
+            events: Arc::new(NoopEventSink),
+        }
+    }
+
+    /// Attaches a sink notified of every generated OTP, verification and replay.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The sink, e.g. `LogEventSink` or a closure taking an `&OtpEvent`.
+    pub fn with_event_sink(mut self, events: Arc<dyn OtpEventSink>) -> MOtp {
+        self.events = events;
+        self
+    }
+
+    /// Returns the init-secret.
+    pub fn secret(&self) -> &Secret {
+        &self.secret
+    }
+
+    /// Generates an OTP based on the current time.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the generated OTP as a `String` of 6 lowercase hexadecimal digits.
+    #[cfg(feature = "std")]
+    pub fn generate(&self) -> OtpResult<String> {
+        self.generate_at(now())
+    }
+
+    /// Generates an OTP based on the provided timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - A timestamp value used in the OTP generation.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the generated OTP as a `String` of 6 lowercase hexadecimal digits.
+    pub fn generate_at(&self, timestamp: u64) -> OtpResult<String> {
+        let step = timestamp / INTERVAL;
+        let otp = self.code(step);
+        self.emit(OtpEvent::Generated {
+            source: OtpEventSource::MOTP,
+            counter: step,
+        });
+        Ok(otp)
+    }
+
+    /// Verifies an OTP based on the current time and drift values.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP to be verified, in either letter case.
+    /// * `after` - An optional timestamp value after which the OTP is valid.
+    /// * `drift_ahead` - The allowed drift ahead in seconds.
+    /// * `drift_behind` - The allowed drift behind in seconds; Mobile-OTP servers usually accept 180 seconds both ways.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an `Option<u64>` with the timestamp value if the OTP is verified, or `None` if the OTP is
+    /// not verified.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the drift behind is greater than or equal to the current timestamp, or if
+    /// `after` is later than it.
+    #[cfg(feature = "std")]
+    pub fn verify(&self, otp: &str, after: Option<u64>, drift_ahead: u64, drift_behind: u64) -> OtpResult<Option<u64>> {
+        self.verify_at(otp, now(), after, drift_ahead, drift_behind)
+    }
+
+    /// Verifies an OTP based on the provided timestamp and drift values.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP to be verified, in either letter case.
+    /// * `at` - A timestamp value used in the OTP verification.
+    /// * `after` - An optional timestamp value after which the OTP is valid.
+    /// * `drift_ahead` - The allowed drift ahead in seconds.
+    /// * `drift_behind` - The allowed drift behind in seconds.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an `Option<u64>` with the timestamp value if the OTP is verified, or `None` if the OTP is
+    /// not verified.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the drift behind is greater than or equal to the timestamp, or if `after` is
+    /// later than it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{MOtp, Secret};
+    ///
+    /// let motp = MOtp::new(Secret::new_from_str("1234567890abcdef").unwrap(), "1234");
+    /// let verified = motp.verify_at("934350", 1111111200, None, 180, 180).unwrap();
+    /// assert_eq!(verified, Some(1111111100));
+    /// ```
+    pub fn verify_at(
+        &self,
+        otp: &str,
+        at: u64,
+        after: Option<u64>,
+        drift_ahead: u64,
+        drift_behind: u64,
+    ) -> OtpResult<Option<u64>> {
+        if otp.len() == LENGTH {
+            if drift_behind >= at {
+                return Err(Box::new(DriftBehindError(drift_behind, at)));
+            }
+            if let Some(after_value) = after {
+                if after_value > at {
+                    return Err(Box::new(AfterError(after_value, at)));
+                }
+            }
+            let earliest = at - drift_behind;
+            let start = after.map_or(earliest, |after_value| earliest.max(after_value));
+            let end = at + drift_ahead;
+
+            for step in start / INTERVAL..=end / INTERVAL {
+                if otp.eq_ignore_ascii_case(&self.code(step)) {
+                    self.emit(OtpEvent::Verified {
+                        source: OtpEventSource::MOTP,
+                        counter: step,
+                        drift: step as i64 - (at / INTERVAL) as i64,
+                    });
+                    return Ok(Some(start.max(step * INTERVAL)));
+                }
+            }
+            for step in earliest / INTERVAL..start / INTERVAL {
+                if otp.eq_ignore_ascii_case(&self.code(step)) {
+                    self.emit(OtpEvent::Replayed {
+                        source: OtpEventSource::MOTP,
+                        counter: step,
+                    });
+                    return Ok(None);
+                }
+            }
+        }
+        self.emit(OtpEvent::Rejected {
+            source: OtpEventSource::MOTP,
+            counter: at / INTERVAL,
+        });
+        Ok(None)
+    }
+
+    fn code(&self, step: u64) -> String {
+        let mut hasher = Md5::new();
+        hasher.update(format!("{}", step).as_bytes());
+        hasher.update(&self.secret.0);
+        hasher.update(self.pin.as_bytes());
+        let digest = hasher.finalize();
+
+        let mut otp = String::with_capacity(LENGTH);
+        for byte in &digest[..LENGTH / 2] {
+            let _ = write!(otp, "{:02x}", byte);
+        }
+        otp
+    }
+
+    fn emit(&self, event: OtpEvent) {
+        self.events.record(&event);
+    }
+}
@@ -5,40 +5,86 @@
 //
 // See the file LICENSE for details.
 
-use crate::otp::algorithm::Algorithm;
+use crate::otp::algorithm::{Algorithm, AlgorithmTrait};
 use crate::otp::base::otp;
+use crate::otp::event::{NoopEventSink, OtpEvent, OtpEventSink, OtpEventSource};
+#[cfg(feature = "std")]
+use crate::otp::watch::AsyncTotpWatch;
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", feature = "wasm"))))]
+use crate::otp::watch::TotpWatch;
+#[cfg(feature = "std")]
+use crate::util::time::now;
+#[cfg(all(feature = "uri", feature = "qr"))]
+use crate::ProvisioningQr;
+use crate::{AfterError, DriftBehindError, KeyProvider, OtpResult, Radix, Secret, VerifyOutcome};
+#[cfg(feature = "uri")]
 use crate::{
-    AfterError, DriftBehindError, InvalidSecretError, OtpResult, Radix, Secret, UnsupportedAlgorithmError,
-    UnsupportedIntervalError, UnsupportedLengthError, UnsupportedRadixError,
+    InvalidSecretError, UnsupportedAlgorithmError, UnsupportedIntervalError, UnsupportedLengthError,
+    UnsupportedRadixError,
 };
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+#[cfg(feature = "uri")]
 use base32ct::{Base32, Encoding};
-use std::num::{NonZeroU64, NonZeroU8};
+use core::fmt::{Debug, Formatter};
+#[cfg(feature = "std")]
+use core::future::Future;
+use core::num::{NonZeroU64, NonZeroU8};
+#[cfg(feature = "std")]
+use core::time::Duration;
 
 /// Represents a TOTP (Time-based One-Time Password) generator.
 ///
 /// # Fields
 ///
-/// * `algorithm` - The hashing algorithm used for TOTP generation.
-/// * `secret` - The shared secret key used for TOTP generation.
+/// * `algorithm` - The hashing algorithm used for TOTP generation, an `Algorithm` unless another `AlgorithmTrait`
+///   implementation is given to `new`.
+/// * `secret` - The shared secret key used for TOTP generation, a `Secret` unless another `KeyProvider` is given to
+///   `new`.
 /// * `length` - The length of the generated OTP.
 /// * `radix` - The radix (base) used for the OTP representation.
 /// * `interval` - The time interval in seconds for TOTP generation.
-#[derive(Debug, PartialEq)]
-pub struct TOTP {
-    pub(crate) algorithm: Algorithm,
-    pub(crate) secret: Secret,
+/// * `events` - The sink notified of generations and verifications, see `with_event_sink`.
+#[derive(Clone)]
+pub struct TOTP<A = Algorithm, K = Secret> {
+    pub(crate) algorithm: A,
+    pub(crate) secret: K,
     pub(crate) length: NonZeroU8,
     pub(crate) radix: Radix,
     pub(crate) interval: NonZeroU64,
+    pub(crate) events: Arc<dyn OtpEventSink>,
 }
 
-impl TOTP {
+impl<A: Debug, K: Debug> Debug for TOTP<A, K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TOTP")
+            .field("algorithm", &self.algorithm)
+            .field("secret", &self.secret)
+            .field("length", &self.length)
+            .field("radix", &self.radix)
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: PartialEq, K: PartialEq> PartialEq for TOTP<A, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm == other.algorithm
+            && self.secret == other.secret
+            && self.length == other.length
+            && self.radix == other.radix
+            && self.interval == other.interval
+    }
+}
+
+impl<A: AlgorithmTrait, K: KeyProvider> TOTP<A, K> {
     /// Creates a new TOTP instance with the specified algorithm, secret, length, radix, and interval.
     ///
     /// # Arguments
     ///
     /// * `algorithm` - The hashing algorithm to be used.
-    /// * `secret` - The shared secret key as a string.
+    /// * `secret` - The shared secret key, or a `KeyProvider` computing the HMAC with it.
     /// * `length` - The length of the OTP to be generated.
     /// * `radix` - The radix (base) for the OTP representation.
     /// * `interval` - The time interval in seconds for TOTP generation.
@@ -63,62 +109,64 @@ impl TOTP {
     /// let otp = totp.generate().unwrap();
     /// println!("Generated OTP: {}", otp);
     /// ```
-    pub fn new(algorithm: Algorithm, secret: Secret, length: NonZeroU8, radix: Radix, interval: NonZeroU64) -> TOTP {
+    pub fn new(algorithm: A, secret: K, length: NonZeroU8, radix: Radix, interval: NonZeroU64) -> TOTP<A, K> {
         Self {
             algorithm,
             secret,
             length,
             radix,
             interval,
+            events: Arc::new(NoopEventSink),
         }
     }
 
-    /// Returns a TOTP configured with RFC 4226 recommended defaults:
-    /// - `algorithm`: SHA1
-    /// - `length`: 6 digits
-    /// - `radix`: 10 (decimal)
+    /// Attaches a sink notified of every generated OTP, verification, replay and resynchronization.
     ///
-    /// The provided `secret` is used as the shared key for TOTP generation.
+    /// # Arguments
+    ///
+    /// * `events` - The sink, e.g. `LogEventSink` or a closure taking an `&OtpEvent`.
     ///
     /// # Example
     ///
     /// ```
-    /// use rusotp::{Secret, TOTP};
-    ///
-    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    /// use rusotp::{OtpEvent, Secret, TOTP};
+    /// use std::sync::Arc;
     ///
-    /// let totp = TOTP::default(secret);
+    /// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap())
+    ///     .with_event_sink(Arc::new(|event: &OtpEvent| println!("{}", event)));
+    /// totp.generate_at(59).unwrap();
     /// ```
-    pub fn default(secret: Secret) -> TOTP {
-        Self::new(
-            Algorithm::SHA1,
-            secret,
-            NonZeroU8::new(6).unwrap(),
-            Radix::new(10).unwrap(),
-            NonZeroU64::new(30).unwrap(),
-        )
+    pub fn with_event_sink(mut self, events: Arc<dyn OtpEventSink>) -> TOTP<A, K> {
+        self.events = events;
+        self
     }
 
-    /// Returns a TOTP configured with RFC 4226 recommended defaults:
-    /// - Algorithm: SHA1
-    /// - Length: 6 digits
-    /// - Radix: 10 (decimal)
-    ///
-    /// The provided `secret` is used as the shared key for TOTP generation.
-    ///
-    /// Convenience constructor equivalent to `TOTP::default`.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use rusotp::{Secret, TOTP};
-    ///
-    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
-    ///
-    /// let totp = TOTP::rfc6238_default(secret);
-    /// ```
-    pub fn rfc6238_default(secret: Secret) -> TOTP {
-        Self::default(secret)
+    /// Returns the hashing algorithm used for OTP generation.
+    pub fn algorithm(&self) -> A
+    where
+        A: Clone,
+    {
+        self.algorithm.clone()
+    }
+
+    /// Returns the shared secret key, or its provider.
+    pub fn secret(&self) -> &K {
+        &self.secret
+    }
+
+    /// Returns the length of the generated OTP.
+    pub fn length(&self) -> NonZeroU8 {
+        self.length
+    }
+
+    /// Returns the radix (base) used for the OTP representation.
+    pub fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    /// Returns the time step, in seconds, of a single OTP.
+    pub fn interval(&self) -> NonZeroU64 {
+        self.interval
     }
 
     /// Generates an OTP based on the current time.
@@ -143,14 +191,9 @@ impl TOTP {
     /// let otp = totp.generate().unwrap();
     /// println!("Generated OTP: {}", otp);
     /// ```
+    #[cfg(feature = "std")]
     pub fn generate(&self) -> OtpResult<String> {
-        otp(
-            &self.algorithm,
-            self.secret.clone().get(),
-            self.length.get(),
-            self.radix.get(),
-            self.time_code(std::time::UNIX_EPOCH.elapsed().unwrap().as_secs()),
-        )
+        self.generate_at(now())
     }
 
     /// Generates an OTP based on the provided timestamp.
@@ -180,7 +223,101 @@ impl TOTP {
     /// println!("Generated OTP: {}", otp);
     /// ```
     pub fn generate_at(&self, timestamp: u64) -> OtpResult<String> {
-        otp(&self.algorithm, self.secret.clone().get(), self.length.get(), self.radix.get(), self.time_code(timestamp))
+        self.generate_step(self.time_code(timestamp))
+    }
+
+    /// Returns the time step of the current time, i.e. the counter of the OTP returned by `generate`.
+    #[cfg(feature = "std")]
+    pub fn current_step(&self) -> u64 {
+        self.time_code(now())
+    }
+
+    /// Generates the OTP of the time step following the current one, e.g. to have it ready at the step boundary.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the time step and its OTP, valid from `step * interval` seconds.
+    #[cfg(feature = "std")]
+    pub fn next_code(&self) -> OtpResult<(u64, String)> {
+        let step = self.current_step().saturating_add(1);
+        Ok((step, self.generate_step(step)?))
+    }
+
+    /// Generates the OTPs of the time steps between two timestamps, e.g. to print a code sheet.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The timestamp of the first time step.
+    /// * `end` - The timestamp of the last time step, included.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of `Result`s containing each time step and its OTP, empty if `end` is before `start`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, TOTP};
+    ///
+    /// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let codes: Vec<_> = totp.codes_between(0, 89).map(Result::unwrap).collect();
+    /// assert_eq!(codes, vec![(0, "755224".to_string()), (1, "287082".to_string()), (2, "359152".to_string())]);
+    /// ```
+    pub fn codes_between(&self, start: u64, end: u64) -> impl Iterator<Item = OtpResult<(u64, String)>> + '_ {
+        (self.time_code(start)..=self.time_code(end)).map(move |step| Ok((step, self.generate_step(step)?)))
+    }
+
+    /// Returns a blocking iterator of the OTP of the current time step, then of each following time step as soon as
+    /// it starts.
+    ///
+    /// `next` sleeps the thread until the next step boundary, so the iterator never ends. It is not available with
+    /// the `wasm` feature on `wasm32`, which cannot block; use [`TOTP::watch_async`] there.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rusotp::{Secret, TOTP};
+    ///
+    /// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// for code in totp.watch().take(3) {
+    ///     let (step, otp) = code.unwrap();
+    ///     println!("{}: {}", step, otp);
+    /// }
+    /// ```
+    #[cfg(all(feature = "std", not(all(target_arch = "wasm32", feature = "wasm"))))]
+    pub fn watch(&self) -> TotpWatch<'_, A, K> {
+        TotpWatch::new(self)
+    }
+
+    /// Returns an asynchronous watch of the OTP of the current time step, then of each following time step as soon as
+    /// it starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `sleep` - Returns a future completing after the given duration, from the async runtime in use, e.g.
+    ///   `tokio::time::sleep`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rusotp::{Secret, TOTP};
+    ///
+    /// async fn print_codes(sleep: impl Fn(std::time::Duration) -> std::future::Ready<()>) {
+    ///     let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    ///     let mut watch = totp.watch_async(sleep);
+    ///     loop {
+    ///         let (step, otp) = watch.next().await.unwrap();
+    ///         println!("{}: {}", step, otp);
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn watch_async<S, F>(&self, sleep: S) -> AsyncTotpWatch<'_, A, K, S>
+    where
+        S: FnMut(Duration) -> F,
+        F: Future<Output = ()>,
+    {
+        AsyncTotpWatch::new(self, sleep)
     }
 
     /// Verifies an OTP based on the current time and drift values.
@@ -217,8 +354,9 @@ impl TOTP {
     /// let verified = totp.verify(&otp, None, 30, 30).unwrap();
     /// assert!(verified.is_some());
     /// ```
+    #[cfg(feature = "std")]
     pub fn verify(&self, otp: &str, after: Option<u64>, drift_ahead: u64, drift_behind: u64) -> OtpResult<Option<u64>> {
-        self.verify_at(otp, std::time::UNIX_EPOCH.elapsed().unwrap().as_secs(), after, drift_ahead, drift_behind)
+        self.verify_at(otp, now(), after, drift_ahead, drift_behind)
     }
 
     /// Verifies an OTP based on the provided timestamp and drift values.
@@ -234,6 +372,7 @@ impl TOTP {
     /// # Returns
     ///
     /// A `Result` containing an `Option<u64>` with the timestamp value if the OTP is verified, or `None` if the OTP is not verified. Returns a `String` with the error message if the verification fails.
+    /// The timestamp is the first second of the window producing the OTP, see `verify_window_at` for its time step.
     ///
     /// # Errors
     ///
@@ -264,36 +403,296 @@ impl TOTP {
         drift_ahead: u64,
         drift_behind: u64,
     ) -> OtpResult<Option<u64>> {
-        if self.length.get() != otp.len() as u8 {
-            Ok(None)
-        } else if drift_behind >= at {
-            Err(Box::new(DriftBehindError(drift_behind, at)))
-        } else {
-            let mut start = at - drift_behind;
-
+        if self.length.get() == otp.len() as u8 {
+            if drift_behind >= at {
+                return Err(Box::new(DriftBehindError(drift_behind, at)));
+            }
             if let Some(after_value) = after {
                 if after_value > at {
-                    return Err(Box::new(AfterError(after.unwrap(), at)));
-                }
-                if start < after_value {
-                    start = after_value;
+                    return Err(Box::new(AfterError(after_value, at)));
                 }
             }
-
+            let earliest = at - drift_behind;
+            let start = after.map_or(earliest, |after_value| earliest.max(after_value));
             let end = at + drift_ahead;
 
+            let mut last_step = None;
             for i in start..=end {
-                match self.generate_at(i) {
-                    Ok(generated_otp) => {
-                        if otp == generated_otp {
-                            return Ok(Some(i));
-                        }
-                    }
-                    Err(e) => return Err(e),
+                let step = self.time_code(i);
+                if last_step != Some(step) && otp == self.code(step)? {
+                    self.emit(OtpEvent::Verified {
+                        source: OtpEventSource::TOTP,
+                        counter: step,
+                        drift: step as i64 - self.time_code(at) as i64,
+                    });
+                    return Ok(Some(i));
+                }
+                last_step = Some(step);
+            }
+            for step in self.time_code(earliest)..self.time_code(start) {
+                if otp == self.code(step)? {
+                    self.emit(OtpEvent::Replayed {
+                        source: OtpEventSource::TOTP,
+                        counter: step,
+                    });
+                    return Ok(None);
+                }
+            }
+        }
+        self.emit(OtpEvent::Rejected {
+            source: OtpEventSource::TOTP,
+            counter: self.time_code(at),
+        });
+        Ok(None)
+    }
+
+    /// Verifies an OTP against the current time step and the `behind` previous and `ahead` following ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP to be verified as a string.
+    /// * `next` - An optional time step before which OTPs are rejected, usually the `next` of the last outcome.
+    /// * `behind` - The number of time steps before the current one to accept.
+    /// * `ahead` - The number of time steps after the current one to accept.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `VerifyOutcome` with the matched time step, its drift from the current one and the time
+    /// step to expect next if the OTP is verified, or `None` if it is not.
+    #[cfg(feature = "std")]
+    pub fn verify_window(
+        &self,
+        otp: &str,
+        next: Option<u64>,
+        behind: u64,
+        ahead: u64,
+    ) -> OtpResult<Option<VerifyOutcome>> {
+        self.verify_window_at(otp, now(), next, behind, ahead)
+    }
+
+    /// Verifies an OTP against the time step of the provided timestamp and the `behind` previous and `ahead`
+    /// following ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP to be verified as a string.
+    /// * `at` - A timestamp value used as the current time.
+    /// * `next` - An optional time step before which OTPs are rejected, usually the `next` of the last outcome.
+    /// * `behind` - The number of time steps before the one of `at` to accept.
+    /// * `ahead` - The number of time steps after the one of `at` to accept.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `VerifyOutcome` with the matched time step, its drift from the one of `at` and the time
+    /// step to expect next if the OTP is verified, or `None` if it is not, including when it has the wrong length, its
+    /// time step is before `next` or it only matches time step `u64::MAX`, after which no step is left to expect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, TOTP};
+    ///
+    /// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let otp = totp.generate_at(59).unwrap();
+    ///
+    /// let outcome = totp.verify_window_at(&otp, 75, None, 1, 1).unwrap().unwrap();
+    /// assert_eq!((outcome.counter, outcome.drift, outcome.next), (1, -1, 2));
+    /// assert_eq!(totp.verify_window_at(&otp, 75, Some(outcome.next), 1, 1).unwrap(), None);
+    /// ```
+    pub fn verify_window_at(
+        &self,
+        otp: &str,
+        at: u64,
+        next: Option<u64>,
+        behind: u64,
+        ahead: u64,
+    ) -> OtpResult<Option<VerifyOutcome>> {
+        let current = self.time_code(at);
+        if self.length.get() == otp.len() as u8 {
+            let earliest = current.saturating_sub(behind);
+            let latest = current.saturating_add(ahead).min(VerifyOutcome::LAST);
+            let start = next.map_or(earliest, |next_value| earliest.max(next_value));
+
+            for step in start..=latest {
+                if otp == self.code(step)? {
+                    let outcome = VerifyOutcome::new(step, current);
+                    self.emit(OtpEvent::Verified {
+                        source: OtpEventSource::TOTP,
+                        counter: step,
+                        drift: outcome.drift,
+                    });
+                    return Ok(Some(outcome));
+                }
+            }
+            for step in earliest..start.min(latest.saturating_add(1)) {
+                if otp == self.code(step)? {
+                    self.emit(OtpEvent::Replayed {
+                        source: OtpEventSource::TOTP,
+                        counter: step,
+                    });
+                    return Ok(None);
+                }
+            }
+        }
+        self.emit(OtpEvent::Rejected {
+            source: OtpEventSource::TOTP,
+            counter: current,
+        });
+        Ok(None)
+    }
+
+    /// Resynchronizes with an authenticator whose clock drifted, by searching two consecutive OTPs around the
+    /// current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The OTP displayed first by the authenticator.
+    /// * `second` - The OTP displayed right after `first`.
+    /// * `after` - An optional timestamp before which OTPs are rejected.
+    /// * `window` - The number of time steps before and after the current one to search.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an `Option<u64>` with the time step of `second` if both OTPs were found, or `None`.
+    /// The drift of the authenticator is the difference between this time step and the current one.
+    #[cfg(feature = "std")]
+    pub fn resync(&self, first: &str, second: &str, after: Option<u64>, window: u64) -> OtpResult<Option<u64>> {
+        self.resync_at(first, second, now(), after, window)
+    }
+
+    /// Resynchronizes with an authenticator whose clock drifted, by searching two consecutive OTPs around the
+    /// provided timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The OTP displayed first by the authenticator.
+    /// * `second` - The OTP displayed right after `first`.
+    /// * `at` - A timestamp value used as the current time.
+    /// * `after` - An optional timestamp before which OTPs are rejected.
+    /// * `window` - The number of time steps before and after the one of `at` to search.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an `Option<u64>` with the time step of `second` if both OTPs were found, or `None`.
+    /// The drift of the authenticator is the difference between this time step and the one of `at`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, TOTP};
+    ///
+    /// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let first = totp.generate_at(3000).unwrap();
+    /// let second = totp.generate_at(3030).unwrap();
+    ///
+    /// let resynced = totp.resync_at(&first, &second, 0, None, 200).unwrap();
+    /// assert_eq!(resynced, Some(101));
+    /// ```
+    pub fn resync_at(
+        &self,
+        first: &str,
+        second: &str,
+        at: u64,
+        after: Option<u64>,
+        window: u64,
+    ) -> OtpResult<Option<u64>> {
+        let current = self.time_code(at);
+        let lengths_match = self.length.get() == first.len() as u8 && self.length.get() == second.len() as u8;
+        let start = current
+            .saturating_sub(window)
+            .max(after.map_or(0, |after_value| self.time_code(after_value)));
+        // `second` is searched up to u64::MAX, the last valid time step.
+        if let Some(second_start) = start.checked_add(1).filter(|_| lengths_match) {
+            let mut previous = self.code(start)?;
+            for step in second_start..=current.saturating_add(window).saturating_add(1) {
+                let next = self.code(step)?;
+                if first == previous && second == next {
+                    self.emit(OtpEvent::Resynced {
+                        source: OtpEventSource::TOTP,
+                        counter: step,
+                        drift: step.wrapping_sub(current) as i64,
+                    });
+                    return Ok(Some(step));
                 }
+                previous = next;
             }
-            Ok(None)
         }
+        self.emit(OtpEvent::Rejected {
+            source: OtpEventSource::TOTP,
+            counter: current,
+        });
+        Ok(None)
+    }
+
+    pub(crate) fn generate_step(&self, step: u64) -> OtpResult<String> {
+        let otp = self.code(step)?;
+        self.emit(OtpEvent::Generated {
+            source: OtpEventSource::TOTP,
+            counter: step,
+        });
+        Ok(otp)
+    }
+
+    fn time_code(&self, timestamp: u64) -> u64 {
+        timestamp / self.interval.get()
+    }
+
+    fn code(&self, step: u64) -> OtpResult<String> {
+        otp(&self.algorithm, &self.secret, self.length.get(), self.radix.get(), step)
+    }
+
+    fn emit(&self, event: OtpEvent) {
+        self.events.record(&event);
+    }
+}
+
+impl TOTP {
+    /// Returns a TOTP configured with RFC 4226 recommended defaults:
+    /// - `algorithm`: SHA1
+    /// - `length`: 6 digits
+    /// - `radix`: 10 (decimal)
+    ///
+    /// The provided `secret` is used as the shared key for TOTP generation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, TOTP};
+    ///
+    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    ///
+    /// let totp = TOTP::default(secret);
+    /// ```
+    pub fn default(secret: Secret) -> TOTP {
+        Self::new(
+            Algorithm::SHA1,
+            secret,
+            NonZeroU8::new(6).unwrap(),
+            Radix::new(10).unwrap(),
+            NonZeroU64::new(30).unwrap(),
+        )
+    }
+
+    /// Returns a TOTP configured with RFC 4226 recommended defaults:
+    /// - Algorithm: SHA1
+    /// - Length: 6 digits
+    /// - Radix: 10 (decimal)
+    ///
+    /// The provided `secret` is used as the shared key for TOTP generation.
+    ///
+    /// Convenience constructor equivalent to `TOTP::default`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, TOTP};
+    ///
+    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    ///
+    /// let totp = TOTP::rfc6238_default(secret);
+    /// ```
+    pub fn rfc6238_default(secret: Secret) -> TOTP {
+        Self::default(secret)
     }
 
     /// Generates a provisioning URI for TOTP based on the provided issuer and name.
@@ -327,6 +726,7 @@ impl TOTP {
     /// let uri = totp.provisioning_uri("ExampleIssuer", "example@example.com").unwrap();
     /// println!("Provisioning URI: {}", uri);
     /// ```
+    #[cfg(feature = "uri")]
     pub fn provisioning_uri(&self, issuer: &str, user: &str) -> OtpResult<String> {
         if self.interval.get() < 30 {
             Err(Box::new(UnsupportedIntervalError(self.interval.get())))
@@ -346,6 +746,35 @@ impl TOTP {
         }
     }
 
+    /// Generates a provisioning QR code for TOTP, encoding the URI returned by `provisioning_uri`.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The issuer of the TOTP as a string.
+    /// * `user` - The name of the user or account as a string.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `ProvisioningQr` that can be rendered as SVG, PNG or terminal output.
+    ///
+    /// # Errors
+    ///
+    /// Same as `provisioning_uri`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, TOTP};
+    ///
+    /// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    /// let qr = totp.provisioning_qr("rusotp", "user@email.mail").unwrap();
+    /// println!("{}", qr.terminal().unwrap());
+    /// ```
+    #[cfg(all(feature = "uri", feature = "qr"))]
+    pub fn provisioning_qr(&self, issuer: &str, user: &str) -> OtpResult<ProvisioningQr> {
+        self.provisioning_uri(issuer, user).map(ProvisioningQr::new)
+    }
+
     /// Parse an `otpauth://totp/...` provisioning URI and construct a `TOTP` instance.
     ///
     /// This function extracts query parameters from the provided `uri` and builds a `TOTP`
@@ -360,8 +789,7 @@ impl TOTP {
     ///
     /// # Returns:
     /// * `Ok(TOTP)` on success (uses RFC6238 defaults except for the provided secret).
-    /// * `Err` if the `secret` parameter is missing or if decoding fails (note: current
-    ///   implementation may panic on invalid encoding instead of returning an `Err`).
+    /// * `Err` if the `secret` parameter is missing or is not valid Base32 (letter case and padding are ignored).
     ///
     /// # Example:
     /// ```rust
@@ -373,6 +801,7 @@ impl TOTP {
     /// let otp = hotp.generate().unwrap();
     /// assert_eq!(otp.len(), 6);
     /// ```
+    #[cfg(feature = "uri")]
     pub fn from_uri(uri: &str) -> OtpResult<TOTP> {
         let params = uri.split('?').next_back().unwrap().split('&');
 
@@ -381,9 +810,8 @@ impl TOTP {
         for param in params {
             if let Some((key, value)) = param.split_once('=') {
                 if key == "secret" && !value.is_empty() {
-                    secret = Some(Secret::new_from_vec(
-                        Base32::decode_vec(urlencoding::decode(value).unwrap().trim()).unwrap(),
-                    ))
+                    let value = urlencoding::decode(value).map_err(|_| Box::new(InvalidSecretError()) as _)?;
+                    secret = Some(Secret::new_from_base32(&value)?)
                 }
             }
         }
@@ -394,8 +822,4 @@ impl TOTP {
 
         Ok(TOTP::default(secret.ok_or(InvalidSecretError()).unwrap()))
     }
-
-    fn time_code(&self, timestamp: u64) -> u64 {
-        timestamp / self.interval.get()
-    }
 }
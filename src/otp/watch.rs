@@ -0,0 +1,113 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::otp::totp::TOTP;
+use crate::util::time::now_millis;
+use crate::{AlgorithmTrait, KeyProvider, OtpResult};
+use core::fmt::{Debug, Formatter};
+use core::future::Future;
+use core::time::Duration;
+
+/// A blocking iterator of the OTP of each time step of a `TOTP`, returned by `TOTP::watch`.
+///
+/// The first item is the OTP of the current time step; each following one waits for the next step boundary.
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+#[derive(Debug)]
+pub struct TotpWatch<'a, A, K> {
+    totp: &'a TOTP<A, K>,
+    last: Option<u64>,
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+impl<'a, A: AlgorithmTrait, K: KeyProvider> TotpWatch<'a, A, K> {
+    pub(crate) fn new(totp: &'a TOTP<A, K>) -> Self {
+        Self { totp, last: None }
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+impl<A: AlgorithmTrait, K: KeyProvider> Iterator for TotpWatch<'_, A, K> {
+    type Item = OtpResult<(u64, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match poll(self.totp, &mut self.last) {
+                Ok(code) => return Some(code),
+                Err(delay) => std::thread::sleep(delay),
+            }
+        }
+    }
+}
+
+/// An asynchronous watch of the OTP of each time step of a `TOTP`, returned by `TOTP::watch_async`.
+///
+/// The first call to `next` returns the OTP of the current time step; each following one awaits the sleep future
+/// until the next step boundary.
+pub struct AsyncTotpWatch<'a, A, K, S> {
+    totp: &'a TOTP<A, K>,
+    last: Option<u64>,
+    sleep: S,
+}
+
+impl<A: Debug, K: Debug, S> Debug for AsyncTotpWatch<'_, A, K, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AsyncTotpWatch")
+            .field("totp", &self.totp)
+            .field("last", &self.last)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, A, K, S, F> AsyncTotpWatch<'a, A, K, S>
+where
+    A: AlgorithmTrait,
+    K: KeyProvider,
+    S: FnMut(Duration) -> F,
+    F: Future<Output = ()>,
+{
+    pub(crate) fn new(totp: &'a TOTP<A, K>, sleep: S) -> Self {
+        Self {
+            totp,
+            last: None,
+            sleep,
+        }
+    }
+
+    /// Waits for the next time step and generates its OTP.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the time step and its OTP, or the error of the generation.
+    pub async fn next(&mut self) -> OtpResult<(u64, String)> {
+        loop {
+            match poll(self.totp, &mut self.last) {
+                Ok(code) => return code,
+                Err(delay) => (self.sleep)(delay).await,
+            }
+        }
+    }
+}
+
+/// Generates the OTP of the current time step if it was not returned yet, or returns the delay until the next one.
+fn poll<A: AlgorithmTrait, K: KeyProvider>(
+    totp: &TOTP<A, K>,
+    last: &mut Option<u64>,
+) -> Result<OtpResult<(u64, String)>, Duration> {
+    let now = now_millis();
+    let interval = u128::from(totp.interval.get()) * 1000;
+    let step = (now / interval) as u64;
+    match *last {
+        Some(last_step) if last_step >= step => {
+            let delay = (u128::from(last_step) + 1) * interval - now;
+            Err(Duration::from_millis(delay.min(u128::from(u64::MAX)) as u64))
+        }
+        _ => {
+            *last = Some(step);
+            Ok(totp.generate_step(step).map(|otp| (step, otp)))
+        }
+    }
+}
@@ -0,0 +1,360 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::otp::algorithm::{Algorithm, AlgorithmTrait};
+use crate::otp::event::{NoopEventSink, OtpEvent, OtpEventSink, OtpEventSource};
+#[cfg(feature = "std")]
+use crate::util::time::now;
+#[cfg(feature = "uri")]
+use crate::InvalidUriError;
+use crate::{AfterError, DriftBehindError, InvalidPinError, InvalidSecretError, OtpGenericError, OtpResult, Secret};
+use alloc::boxed::Box;
+#[cfg(feature = "uri")]
+use alloc::format;
+use alloc::string::String;
+#[cfg(feature = "uri")]
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use sha2::{Digest, Sha256};
+
+/// The time step, in seconds, of Yandex Key codes.
+const INTERVAL: u64 = 30;
+
+/// The number of letters of Yandex Key codes.
+const LENGTH: usize = 8;
+
+/// The length of the key, the first bytes of a full secret.
+const KEY_LENGTH: usize = 16;
+
+/// The length of a full secret: the key followed by a 12 bits checksum.
+const FULL_LENGTH: usize = 26;
+
+/// Represents a Yandex Key OTP generator.
+///
+/// The HMAC-SHA256 key is `SHA256(PIN || secret)` (without its first byte when it is zero), the counter is the
+/// 30-second time step and the truncated MAC is rendered as 8 lowercase latin letters.
+///
+/// # Fields
+///
+/// * `secret` - The 16-byte secret shared with the app.
+/// * `key` - The HMAC key derived from the PIN and the secret.
+/// * `events` - The sink notified of generations and verifications, see `with_event_sink`.
+#[derive(Clone)]
+pub struct YandexOtp {
+    pub(crate) secret: Secret,
+    pub(crate) key: Vec<u8>,
+    pub(crate) events: Arc<dyn OtpEventSink>,
+}
+
+impl Debug for YandexOtp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("YandexOtp")
+            .field("secret", &self.secret)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for YandexOtp {
+    fn eq(&self, other: &Self) -> bool {
+        self.secret == other.secret && self.key == other.key
+    }
+}
+
+impl YandexOtp {
+    /// Creates a new Yandex Key OTP instance with the specified secret and PIN.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The 16-byte secret, or the 26-byte secret of a provisioning URI whose checksum is checked.
+    /// * `pin` - The PIN of the user, 4 to 16 digits.
+    ///
+    /// # Returns
+    ///
+    /// A `YandexOtp` instance, or an `InvalidSecretError` if the secret has another length or a wrong checksum, or an
+    /// `InvalidPinError` if the PIN is not 4 to 16 digits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, YandexOtp};
+    ///
+    /// let secret = Secret::new_from_base32("LA2V6KMCGYMWWVEW64RNP3JA3IAAAAAAHTSG4HRZPI").unwrap();
+    /// let yandex = YandexOtp::new(secret, "7586").unwrap();
+    /// assert_eq!(yandex.generate_at(1581064020).unwrap(), "oactmacq");
+    /// ```
+    pub fn new(secret: Secret, pin: &str) -> OtpResult<YandexOtp> {
+        let mut bytes = secret.0;
+        match bytes.len() {
+            KEY_LENGTH => {}
+            FULL_LENGTH if checksum_matches(&bytes) => bytes.truncate(KEY_LENGTH),
+            _ => return Err(Box::new(InvalidSecretError())),
+        }
+        if !(4..=16).contains(&pin.len()) || !pin.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Box::new(InvalidPinError(String::from("must be 4 to 16 digits"))));
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(pin.as_bytes());
+        hasher.update(&bytes);
+        let hash = hasher.finalize();
+        let key = if hash[0] == 0 { &hash[1..] } else { &hash[..] };
+
+        Ok(Self {
+            secret: Secret::new_from_vec(bytes),
+            key: key.to_vec(),
+            events: Arc::new(NoopEventSink),
+        })
+    }
+
+    /// Attaches a sink notified of every generated OTP, verification and replay.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The sink, e.g. `LogEventSink` or a closure taking an `&OtpEvent`.
+    pub fn with_event_sink(mut self, events: Arc<dyn OtpEventSink>) -> YandexOtp {
+        self.events = events;
+        self
+    }
+
+    /// Returns the 16-byte secret, without the checksum.
+    pub fn secret(&self) -> &Secret {
+        &self.secret
+    }
+
+    /// Generates an OTP based on the current time.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the generated OTP as a `String` of 8 lowercase letters.
+    #[cfg(feature = "std")]
+    pub fn generate(&self) -> OtpResult<String> {
+        self.generate_at(now())
+    }
+
+    /// Generates an OTP based on the provided timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - A timestamp value used in the OTP generation.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the generated OTP as a `String` of 8 lowercase letters.
+    pub fn generate_at(&self, timestamp: u64) -> OtpResult<String> {
+        let step = timestamp / INTERVAL;
+        let otp = self.code(step)?;
+        self.emit(OtpEvent::Generated {
+            source: OtpEventSource::YAOTP,
+            counter: step,
+        });
+        Ok(otp)
+    }
+
+    /// Verifies an OTP based on the current time and drift values.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP to be verified, in either letter case.
+    /// * `after` - An optional timestamp value after which the OTP is valid.
+    /// * `drift_ahead` - The allowed drift ahead in seconds.
+    /// * `drift_behind` - The allowed drift behind in seconds.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an `Option<u64>` with the timestamp value if the OTP is verified, or `None` if the OTP is
+    /// not verified.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the drift behind is greater than or equal to the current timestamp, or if
+    /// `after` is later than it.
+    #[cfg(feature = "std")]
+    pub fn verify(&self, otp: &str, after: Option<u64>, drift_ahead: u64, drift_behind: u64) -> OtpResult<Option<u64>> {
+        self.verify_at(otp, now(), after, drift_ahead, drift_behind)
+    }
+
+    /// Verifies an OTP based on the provided timestamp and drift values.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP to be verified, in either letter case.
+    /// * `at` - A timestamp value used in the OTP verification.
+    /// * `after` - An optional timestamp value after which the OTP is valid.
+    /// * `drift_ahead` - The allowed drift ahead in seconds.
+    /// * `drift_behind` - The allowed drift behind in seconds.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an `Option<u64>` with the timestamp value if the OTP is verified, or `None` if the OTP is
+    /// not verified.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the drift behind is greater than or equal to the timestamp, or if `after` is
+    /// later than it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::{Secret, YandexOtp};
+    ///
+    /// let secret = Secret::new_from_base32("LA2V6KMCGYMWWVEW64RNP3JA3IAAAAAAHTSG4HRZPI").unwrap();
+    /// let yandex = YandexOtp::new(secret, "7586").unwrap();
+    /// let verified = yandex.verify_at("oactmacq", 1581064050, None, 0, 30).unwrap();
+    /// assert_eq!(verified, Some(1581064020));
+    /// ```
+    pub fn verify_at(
+        &self,
+        otp: &str,
+        at: u64,
+        after: Option<u64>,
+        drift_ahead: u64,
+        drift_behind: u64,
+    ) -> OtpResult<Option<u64>> {
+        if otp.len() == LENGTH {
+            if drift_behind >= at {
+                return Err(Box::new(DriftBehindError(drift_behind, at)));
+            }
+            if let Some(after_value) = after {
+                if after_value > at {
+                    return Err(Box::new(AfterError(after_value, at)));
+                }
+            }
+            let earliest = at - drift_behind;
+            let start = after.map_or(earliest, |after_value| earliest.max(after_value));
+            let end = at + drift_ahead;
+
+            for step in start / INTERVAL..=end / INTERVAL {
+                if otp.eq_ignore_ascii_case(&self.code(step)?) {
+                    self.emit(OtpEvent::Verified {
+                        source: OtpEventSource::YAOTP,
+                        counter: step,
+                        drift: step as i64 - (at / INTERVAL) as i64,
+                    });
+                    return Ok(Some(start.max(step * INTERVAL)));
+                }
+            }
+            for step in earliest / INTERVAL..start / INTERVAL {
+                if otp.eq_ignore_ascii_case(&self.code(step)?) {
+                    self.emit(OtpEvent::Replayed {
+                        source: OtpEventSource::YAOTP,
+                        counter: step,
+                    });
+                    return Ok(None);
+                }
+            }
+        }
+        self.emit(OtpEvent::Rejected {
+            source: OtpEventSource::YAOTP,
+            counter: at / INTERVAL,
+        });
+        Ok(None)
+    }
+
+    /// Creates a Yandex Key OTP from an `otpauth://yaotp/...` provisioning URI and the PIN of the user.
+    ///
+    /// The URI carries the 26-byte secret in Base32 and, optionally, the PIN length in `pin_length`; the PIN itself
+    /// is never part of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The provisioning URI.
+    /// * `pin` - The PIN of the user.
+    ///
+    /// # Returns
+    ///
+    /// A `YandexOtp` instance, or an error if the URI is not a `yaotp` URI, its secret is invalid or the PIN does not
+    /// have the announced length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::YandexOtp;
+    ///
+    /// let uri = "otpauth://yaotp/user?secret=LA2V6KMCGYMWWVEW64RNP3JA3IAAAAAAHTSG4HRZPI&name=user&pin_length=4";
+    /// let yandex = YandexOtp::from_uri(uri, "7586").unwrap();
+    /// assert_eq!(yandex.generate_at(1581090810).unwrap(), "wemdwrix");
+    /// ```
+    #[cfg(feature = "uri")]
+    pub fn from_uri(uri: &str, pin: &str) -> OtpResult<YandexOtp> {
+        let rest = uri
+            .strip_prefix("otpauth://yaotp/")
+            .ok_or_else(|| Box::new(InvalidUriError("expected an `otpauth://yaotp/` URI".to_string())) as _)?;
+        let query = rest.split_once('?').map_or("", |(_, query)| query);
+
+        let mut secret = None;
+        for (key, value) in query.split('&').filter_map(|param| param.split_once('=')) {
+            match key {
+                "secret" if !value.is_empty() => {
+                    let value = urlencoding::decode(value).map_err(|_| Box::new(InvalidSecretError()) as _)?;
+                    secret = Some(Secret::new_from_base32(&value)?);
+                }
+                "pin_length" if value.parse() != Ok(pin.len()) => {
+                    return Err(Box::new(InvalidPinError(format!("must be {} digits", value))));
+                }
+                _ => {}
+            }
+        }
+
+        YandexOtp::new(secret.ok_or_else(|| Box::new(InvalidSecretError()) as _)?, pin)
+    }
+
+    fn code(&self, step: u64) -> OtpResult<String> {
+        let mac = Algorithm::SHA256
+            .hash(&self.key, &step.to_be_bytes())
+            .map_err(|e| Box::new(OtpGenericError(e)) as _)?;
+        let offset = (mac[mac.len() - 1] & 0xf) as usize;
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&mac[offset..offset + 8]);
+        let mut value = (u64::from_be_bytes(value) & 0x7fff_ffff_ffff_ffff) % 26u64.pow(LENGTH as u32);
+
+        let mut otp = [b'a'; LENGTH];
+        for letter in otp.iter_mut().rev() {
+            *letter = b'a' + (value % 26) as u8;
+            value /= 26;
+        }
+        Ok(otp.iter().map(|&letter| letter as char).collect())
+    }
+
+    fn emit(&self, event: OtpEvent) {
+        self.events.record(&event);
+    }
+}
+
+/// Checks the 12 bits checksum ending a 26-byte secret, a CRC with the 13 bits polynomial `0x18F3` over the bits
+/// before it.
+fn checksum_matches(secret: &[u8]) -> bool {
+    let expected = (u16::from(secret[secret.len() - 2] & 0x0f) << 8) | u16::from(secret[secret.len() - 1]);
+
+    let mut accumulator: u16 = 0;
+    let mut accumulated = 0;
+    let mut remaining = secret.len() as u32 * 8 - 12;
+    let (mut index, mut available) = (0, 8);
+    while remaining > 0 {
+        let mut required = (13 - accumulated).min(remaining);
+        while required > 0 {
+            let bits = required.min(available);
+            let current = (u16::from(secret[index]) & ((1 << available) - 1)) >> (available - bits);
+            accumulator = (accumulator << bits) | current;
+            remaining -= bits;
+            required -= bits;
+            available -= bits;
+            accumulated += bits;
+            if available == 0 {
+                index += 1;
+                available = 8;
+            }
+        }
+        if accumulated == 13 {
+            accumulator ^= 0b1_1000_1111_0011;
+        }
+        accumulated = 16 - accumulator.leading_zeros();
+    }
+    accumulator == expected
+}
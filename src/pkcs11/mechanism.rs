@@ -0,0 +1,33 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::pkcs11::sys::*;
+use crate::{Algorithm, AlgorithmTrait};
+
+/// Returns the PKCS#11 HMAC mechanism of `algorithm`, which is identified by its name.
+pub(crate) fn hmac_mechanism<A: AlgorithmTrait>(algorithm: &A) -> Result<CkUlong, String> {
+    let name = algorithm.to_string();
+    match Algorithm::from_string(name.clone()) {
+        Some(Algorithm::SHA1) => Ok(CKM_SHA_1_HMAC),
+        Some(Algorithm::SHA224) => Ok(CKM_SHA224_HMAC),
+        Some(Algorithm::SHA256) => Ok(CKM_SHA256_HMAC),
+        Some(Algorithm::SHA384) => Ok(CKM_SHA384_HMAC),
+        Some(Algorithm::SHA512) => Ok(CKM_SHA512_HMAC),
+        #[cfg(feature = "sha3")]
+        Some(Algorithm::SHA3_224) => Ok(CKM_SHA3_224_HMAC),
+        #[cfg(feature = "sha3")]
+        Some(Algorithm::SHA3_256) => Ok(CKM_SHA3_256_HMAC),
+        #[cfg(feature = "sha3")]
+        Some(Algorithm::SHA3_384) => Ok(CKM_SHA3_384_HMAC),
+        #[cfg(feature = "sha3")]
+        Some(Algorithm::SHA3_512) => Ok(CKM_SHA3_512_HMAC),
+        _ => Err(format!("{} has no PKCS#11 HMAC mechanism", name)),
+    }
+}
+
+#[cfg(test)]
+mod mechanism_test;
@@ -0,0 +1,32 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::pkcs11::mechanism::hmac_mechanism;
+use crate::pkcs11::sys::*;
+use crate::Algorithm;
+
+#[test]
+fn hmac_mechanism_should_map_sha2_algorithms() {
+    assert_eq!(hmac_mechanism(&Algorithm::SHA1), Ok(CKM_SHA_1_HMAC));
+    assert_eq!(hmac_mechanism(&Algorithm::SHA224), Ok(CKM_SHA224_HMAC));
+    assert_eq!(hmac_mechanism(&Algorithm::SHA256), Ok(CKM_SHA256_HMAC));
+    assert_eq!(hmac_mechanism(&Algorithm::SHA384), Ok(CKM_SHA384_HMAC));
+    assert_eq!(hmac_mechanism(&Algorithm::SHA512), Ok(CKM_SHA512_HMAC));
+}
+
+#[cfg(feature = "sha3")]
+#[test]
+fn hmac_mechanism_should_map_sha3_algorithms() {
+    assert_eq!(hmac_mechanism(&Algorithm::SHA3_256), Ok(CKM_SHA3_256_HMAC));
+    assert_eq!(hmac_mechanism(&Algorithm::SHA3_512), Ok(CKM_SHA3_512_HMAC));
+}
+
+#[cfg(feature = "blake2")]
+#[test]
+fn hmac_mechanism_should_reject_blake2() {
+    assert_eq!(hmac_mechanism(&Algorithm::BLAKE2B512), Err("BLAKE2B-512 has no PKCS#11 HMAC mechanism".to_string()));
+}
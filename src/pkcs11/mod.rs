@@ -0,0 +1,371 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! HMAC computation inside a PKCS#11 token, enabled with the `pkcs11` feature.
+//!
+//! A [`Pkcs11Key`] is a [`KeyProvider`]: an `HOTP` or `TOTP` built with it asks the token to compute the HMAC
+//! (`C_Sign` with `CKM_SHA_1_HMAC`, `CKM_SHA256_HMAC`, ...) with a secret key object, so the seed never enters the
+//! application memory. Seeds are created on the token once with [`Pkcs11Session::import_key`], or by any other
+//! provisioning tool, as sensitive, non-extractable generic secret keys identified by their label.
+//!
+//! The module is loaded at runtime; SoftHSM (`libsofthsm2.so`) can be used for local testing.
+//!
+//! ```no_run
+//! use rusotp::pkcs11::Pkcs11Module;
+//! use rusotp::{Algorithm, Radix, TOTP};
+//! use std::num::{NonZeroU64, NonZeroU8};
+//!
+//! let module = unsafe { Pkcs11Module::load("/usr/lib/softhsm/libsofthsm2.so") }.unwrap();
+//! let session = module.login("rusotp", "1234").unwrap();
+//! let key = session.key("user@email.mail").unwrap();
+//!
+//! let totp = TOTP::new(Algorithm::SHA1, key, NonZeroU8::new(6).unwrap(), Radix::default(), NonZeroU64::new(30).unwrap());
+//! println!("{}", totp.generate().unwrap());
+//! ```
+
+mod mechanism;
+mod sys;
+
+use crate::pkcs11::mechanism::hmac_mechanism;
+use crate::pkcs11::sys::*;
+use crate::{AlgorithmTrait, KeyProvider, OtpError, OtpResult, Pkcs11Error, Secret};
+use libloading::Library;
+use std::ffi::{c_void, OsStr};
+use std::fmt::{Debug, Formatter};
+use std::ptr;
+use std::sync::{Arc, Mutex, PoisonError};
+use zeroize::Zeroize;
+
+/// A loaded and initialized PKCS#11 module, finalized when dropped along with its sessions.
+///
+/// A module is initialized once per process: if it was already initialized, e.g. by another `Pkcs11Module` or by
+/// another library of the process, this instance uses it and leaves its finalization to whoever initialized it.
+pub struct Pkcs11Module {
+    inner: Arc<Module>,
+}
+
+struct Module {
+    functions: Functions,
+    // Whether `C_Initialize` was called by this instance, which must then call `C_Finalize`.
+    initialized: bool,
+    // Keeps the module loaded while `functions` are in use.
+    _library: Library,
+}
+
+impl Drop for Module {
+    fn drop(&mut self) {
+        if self.initialized {
+            unsafe { (self.functions.finalize)(ptr::null_mut()) };
+        }
+    }
+}
+
+impl Debug for Pkcs11Module {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Module").finish_non_exhaustive()
+    }
+}
+
+impl Pkcs11Module {
+    /// Loads the PKCS#11 module at `path` and initializes it for use by multiple threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the shared library, e.g. `/usr/lib/softhsm/libsofthsm2.so`.
+    ///
+    /// # Returns
+    ///
+    /// The module, or a `Pkcs11Error` if it cannot be loaded or initialized.
+    ///
+    /// # Safety
+    ///
+    /// The library is loaded and its initialization routines run; it must be a PKCS#11 module exporting the `C_*`
+    /// functions of the standard.
+    pub unsafe fn load<P: AsRef<OsStr>>(path: P) -> OtpResult<Self> {
+        let library = Library::new(path.as_ref()).map_err(|e| error(format!("cannot load module: {}", e)))?;
+        let functions = Functions::load(&library).map_err(|e| error(format!("not a PKCS#11 module: {}", e)))?;
+        let mut args = InitializeArgs {
+            create_mutex: ptr::null_mut(),
+            destroy_mutex: ptr::null_mut(),
+            lock_mutex: ptr::null_mut(),
+            unlock_mutex: ptr::null_mut(),
+            flags: CKF_OS_LOCKING_OK,
+            reserved: ptr::null_mut(),
+        };
+        let initialized = match (functions.initialize)(&mut args as *mut InitializeArgs as *mut c_void) {
+            CKR_OK => true,
+            CKR_CRYPTOKI_ALREADY_INITIALIZED => false,
+            rv => return Err(failure("C_Initialize", rv)),
+        };
+        Ok(Self {
+            inner: Arc::new(Module {
+                functions,
+                initialized,
+                _library: library,
+            }),
+        })
+    }
+
+    /// Opens a session with the token labelled `token` and logs the user in.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The label of the token, as given to `softhsm2-util --init-token --label`.
+    /// * `pin` - The user PIN of the token.
+    ///
+    /// # Returns
+    ///
+    /// The session, or a `Pkcs11Error` if no token has this label or the PIN is refused.
+    pub fn login(&self, token: &str, pin: &str) -> OtpResult<Pkcs11Session> {
+        let functions = &self.inner.functions;
+        let slot = self.slot(token)?;
+        let mut handle: CkUlong = 0;
+        let flags = CKF_SERIAL_SESSION | CKF_RW_SESSION;
+        let rv = unsafe { (functions.open_session)(slot, flags, ptr::null_mut(), ptr::null_mut(), &mut handle) };
+        check("C_OpenSession", rv)?;
+        let session = Pkcs11Session {
+            inner: Arc::new(Session {
+                module: self.inner.clone(),
+                handle: Mutex::new(handle),
+            }),
+        };
+        match unsafe { (functions.login)(handle, CKU_USER, pin.as_ptr(), pin.len() as CkUlong) } {
+            CKR_OK | CKR_USER_ALREADY_LOGGED_IN => Ok(session),
+            rv => Err(failure("C_Login", rv)),
+        }
+    }
+
+    fn slot(&self, token: &str) -> OtpResult<CkUlong> {
+        let functions = &self.inner.functions;
+        let mut count: CkUlong = 0;
+        check("C_GetSlotList", unsafe { (functions.get_slot_list)(1, ptr::null_mut(), &mut count) })?;
+        let mut slots = vec![0 as CkUlong; count as usize];
+        check("C_GetSlotList", unsafe { (functions.get_slot_list)(1, slots.as_mut_ptr(), &mut count) })?;
+        slots.truncate(count as usize);
+
+        for slot in slots {
+            let mut info = TokenInfo {
+                label: [0; 32],
+                manufacturer_id: [0; 32],
+                model: [0; 16],
+                serial_number: [0; 16],
+                counters: [0; 11],
+                hardware_version: [0; 2],
+                firmware_version: [0; 2],
+                utc_time: [0; 16],
+            };
+            check("C_GetTokenInfo", unsafe { (functions.get_token_info)(slot, &mut info) })?;
+            let label = info.label;
+            if String::from_utf8_lossy(&label).trim_end_matches([' ', '\0']) == token {
+                return Ok(slot);
+            }
+        }
+        Err(error(format!("no token labelled `{}`", token)))
+    }
+}
+
+/// A logged in session with a token, closed when the last of its clones and keys is dropped.
+///
+/// A session serves one request at a time; HMACs computed concurrently with the same session wait for each other.
+#[derive(Clone)]
+pub struct Pkcs11Session {
+    inner: Arc<Session>,
+}
+
+struct Session {
+    module: Arc<Module>,
+    handle: Mutex<CkUlong>,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let handle = *self.handle.get_mut().unwrap_or_else(PoisonError::into_inner);
+        unsafe { (self.module.functions.close_session)(handle) };
+    }
+}
+
+impl Debug for Pkcs11Session {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Session").finish_non_exhaustive()
+    }
+}
+
+impl Pkcs11Session {
+    /// Finds the secret key labelled `label`.
+    ///
+    /// # Returns
+    ///
+    /// The key, or a `Pkcs11Error` if the token holds no secret key with this label.
+    pub fn key(&self, label: &str) -> OtpResult<Pkcs11Key> {
+        let functions = &self.inner.module.functions;
+        let handle = self.lock();
+        let mut class = CKO_SECRET_KEY;
+        let mut label_bytes = label.as_bytes().to_vec();
+        let template = [
+            attribute(CKA_CLASS, &mut class),
+            bytes_attribute(CKA_LABEL, &mut label_bytes),
+        ];
+
+        check("C_FindObjectsInit", unsafe {
+            (functions.find_objects_init)(*handle, template.as_ptr(), template.len() as CkUlong)
+        })?;
+        let mut object: CkUlong = 0;
+        let mut count: CkUlong = 0;
+        let rv = unsafe { (functions.find_objects)(*handle, &mut object, 1, &mut count) };
+        unsafe { (functions.find_objects_final)(*handle) };
+        check("C_FindObjects", rv)?;
+
+        if count == 0 {
+            return Err(error(format!("no secret key labelled `{}`", label)));
+        }
+        Ok(Pkcs11Key {
+            session: self.clone(),
+            handle: object,
+            label: label.to_string(),
+        })
+    }
+
+    /// Stores `secret` on the token as a sensitive, non-extractable secret key labelled `label`, then wipes `secret`.
+    ///
+    /// This is meant for provisioning: the secret passes through the application memory once, then only the token
+    /// computes HMACs with it. `secret` is zeroed and emptied whether or not the token accepts it.
+    ///
+    /// # Returns
+    ///
+    /// The key, or a `Pkcs11Error` if the token refuses to create it.
+    pub fn import_key(&self, label: &str, secret: &mut Secret) -> OtpResult<Pkcs11Key> {
+        let functions = &self.inner.module.functions;
+        let handle = self.lock();
+        let (mut class, mut key_type) = (CKO_SECRET_KEY, CKK_GENERIC_SECRET);
+        let (mut yes, mut no) = (1u8, 0u8);
+        let mut label_bytes = label.as_bytes().to_vec();
+        let template = [
+            attribute(CKA_CLASS, &mut class),
+            attribute(CKA_KEY_TYPE, &mut key_type),
+            attribute(CKA_TOKEN, &mut yes),
+            attribute(CKA_PRIVATE, &mut yes),
+            attribute(CKA_SENSITIVE, &mut yes),
+            attribute(CKA_EXTRACTABLE, &mut no),
+            attribute(CKA_SIGN, &mut yes),
+            attribute(CKA_VERIFY, &mut yes),
+            bytes_attribute(CKA_LABEL, &mut label_bytes),
+            bytes_attribute(CKA_VALUE, &mut secret.0),
+        ];
+
+        let mut object: CkUlong = 0;
+        let rv =
+            unsafe { (functions.create_object)(*handle, template.as_ptr(), template.len() as CkUlong, &mut object) };
+        secret.0.zeroize();
+        check("C_CreateObject", rv)?;
+        Ok(Pkcs11Key {
+            session: self.clone(),
+            handle: object,
+            label: label.to_string(),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CkUlong> {
+        self.inner.handle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A secret key object of a token, computing the HMACs of an `HOTP` or `TOTP`.
+///
+/// # Example
+///
+/// ```no_run
+/// use rusotp::pkcs11::Pkcs11Module;
+/// use rusotp::{Algorithm, Radix, HOTP};
+/// use std::num::NonZeroU8;
+///
+/// let module = unsafe { Pkcs11Module::load("/usr/lib/softhsm/libsofthsm2.so") }.unwrap();
+/// let key = module.login("rusotp", "1234").unwrap().key("user@email.mail").unwrap();
+///
+/// let hotp = HOTP::new(Algorithm::SHA1, key, NonZeroU8::new(6).unwrap(), Radix::default());
+/// assert!(hotp.verify("755224", 0, 10).is_ok());
+/// ```
+#[derive(Clone)]
+pub struct Pkcs11Key {
+    session: Pkcs11Session,
+    handle: CkUlong,
+    label: String,
+}
+
+impl Debug for Pkcs11Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Key")
+            .field("label", &self.label)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Pkcs11Key {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.session.inner, &other.session.inner) && self.handle == other.handle
+    }
+}
+
+impl Pkcs11Key {
+    /// Returns the label of the key object.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+impl KeyProvider for Pkcs11Key {
+    fn hmac<A: AlgorithmTrait>(&self, algorithm: &A, message: &[u8]) -> Result<Vec<u8>, String> {
+        let functions = &self.session.inner.module.functions;
+        let mechanism = Mechanism {
+            mechanism: hmac_mechanism(algorithm)?,
+            parameter: ptr::null_mut(),
+            len: 0,
+        };
+        let handle = self.session.lock();
+
+        check("C_SignInit", unsafe { (functions.sign_init)(*handle, &mechanism, self.handle) })
+            .map_err(|e| e.to_string())?;
+        let mut mac = vec![0u8; 64];
+        let mut len = mac.len() as CkUlong;
+        check("C_Sign", unsafe {
+            (functions.sign)(*handle, message.as_ptr(), message.len() as CkUlong, mac.as_mut_ptr(), &mut len)
+        })
+        .map_err(|e| e.to_string())?;
+        mac.truncate(len as usize);
+        Ok(mac)
+    }
+}
+
+fn attribute<T>(kind: CkUlong, value: &mut T) -> Attribute {
+    Attribute {
+        kind,
+        value: value as *mut T as *mut c_void,
+        len: std::mem::size_of::<T>() as CkUlong,
+    }
+}
+
+fn bytes_attribute(kind: CkUlong, value: &mut [u8]) -> Attribute {
+    Attribute {
+        kind,
+        value: value.as_mut_ptr() as *mut c_void,
+        len: value.len() as CkUlong,
+    }
+}
+
+fn check(function: &str, rv: CkRv) -> OtpResult<()> {
+    match rv {
+        CKR_OK => Ok(()),
+        rv => Err(failure(function, rv)),
+    }
+}
+
+fn failure(function: &str, rv: CkRv) -> Box<dyn OtpError> {
+    error(format!("{} failed with CKR 0x{:08X}", function, rv))
+}
+
+fn error(message: String) -> Box<dyn OtpError> {
+    Box::new(Pkcs11Error(message))
+}
@@ -0,0 +1,150 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! The subset of the PKCS#11 (Cryptoki) C interface used to compute HMACs inside a token.
+
+use std::ffi::{c_ulong, c_void};
+
+pub(crate) type CkUlong = c_ulong;
+pub(crate) type CkRv = CkUlong;
+
+pub(crate) const CKR_OK: CkRv = 0x000;
+pub(crate) const CKR_CRYPTOKI_ALREADY_INITIALIZED: CkRv = 0x191;
+pub(crate) const CKR_USER_ALREADY_LOGGED_IN: CkRv = 0x100;
+
+pub(crate) const CKF_RW_SESSION: CkUlong = 0x2;
+pub(crate) const CKF_SERIAL_SESSION: CkUlong = 0x4;
+pub(crate) const CKF_OS_LOCKING_OK: CkUlong = 0x2;
+pub(crate) const CKU_USER: CkUlong = 1;
+
+pub(crate) const CKA_CLASS: CkUlong = 0x000;
+pub(crate) const CKA_TOKEN: CkUlong = 0x001;
+pub(crate) const CKA_PRIVATE: CkUlong = 0x002;
+pub(crate) const CKA_LABEL: CkUlong = 0x003;
+pub(crate) const CKA_VALUE: CkUlong = 0x011;
+pub(crate) const CKA_KEY_TYPE: CkUlong = 0x100;
+pub(crate) const CKA_SENSITIVE: CkUlong = 0x103;
+pub(crate) const CKA_SIGN: CkUlong = 0x108;
+pub(crate) const CKA_VERIFY: CkUlong = 0x10A;
+pub(crate) const CKA_EXTRACTABLE: CkUlong = 0x162;
+
+pub(crate) const CKO_SECRET_KEY: CkUlong = 0x4;
+pub(crate) const CKK_GENERIC_SECRET: CkUlong = 0x10;
+
+pub(crate) const CKM_SHA_1_HMAC: CkUlong = 0x221;
+pub(crate) const CKM_SHA256_HMAC: CkUlong = 0x251;
+pub(crate) const CKM_SHA224_HMAC: CkUlong = 0x256;
+pub(crate) const CKM_SHA384_HMAC: CkUlong = 0x261;
+pub(crate) const CKM_SHA512_HMAC: CkUlong = 0x271;
+#[cfg(feature = "sha3")]
+pub(crate) const CKM_SHA3_256_HMAC: CkUlong = 0x2B1;
+#[cfg(feature = "sha3")]
+pub(crate) const CKM_SHA3_224_HMAC: CkUlong = 0x2B6;
+#[cfg(feature = "sha3")]
+pub(crate) const CKM_SHA3_384_HMAC: CkUlong = 0x2C1;
+#[cfg(feature = "sha3")]
+pub(crate) const CKM_SHA3_512_HMAC: CkUlong = 0x2D1;
+
+/// `CK_ATTRIBUTE`
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub(crate) struct Attribute {
+    pub(crate) kind: CkUlong,
+    pub(crate) value: *mut c_void,
+    pub(crate) len: CkUlong,
+}
+
+/// `CK_MECHANISM`
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub(crate) struct Mechanism {
+    pub(crate) mechanism: CkUlong,
+    pub(crate) parameter: *mut c_void,
+    pub(crate) len: CkUlong,
+}
+
+/// `CK_C_INITIALIZE_ARGS`
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub(crate) struct InitializeArgs {
+    pub(crate) create_mutex: *mut c_void,
+    pub(crate) destroy_mutex: *mut c_void,
+    pub(crate) lock_mutex: *mut c_void,
+    pub(crate) unlock_mutex: *mut c_void,
+    pub(crate) flags: CkUlong,
+    pub(crate) reserved: *mut c_void,
+}
+
+/// `CK_TOKEN_INFO`
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub(crate) struct TokenInfo {
+    pub(crate) label: [u8; 32],
+    pub(crate) manufacturer_id: [u8; 32],
+    pub(crate) model: [u8; 16],
+    pub(crate) serial_number: [u8; 16],
+    pub(crate) counters: [CkUlong; 11],
+    pub(crate) hardware_version: [u8; 2],
+    pub(crate) firmware_version: [u8; 2],
+    pub(crate) utc_time: [u8; 16],
+}
+
+pub(crate) type Initialize = unsafe extern "C" fn(*mut c_void) -> CkRv;
+pub(crate) type Finalize = unsafe extern "C" fn(*mut c_void) -> CkRv;
+pub(crate) type GetSlotList = unsafe extern "C" fn(u8, *mut CkUlong, *mut CkUlong) -> CkRv;
+pub(crate) type GetTokenInfo = unsafe extern "C" fn(CkUlong, *mut TokenInfo) -> CkRv;
+pub(crate) type OpenSession = unsafe extern "C" fn(CkUlong, CkUlong, *mut c_void, *mut c_void, *mut CkUlong) -> CkRv;
+pub(crate) type CloseSession = unsafe extern "C" fn(CkUlong) -> CkRv;
+pub(crate) type Login = unsafe extern "C" fn(CkUlong, CkUlong, *const u8, CkUlong) -> CkRv;
+pub(crate) type CreateObject = unsafe extern "C" fn(CkUlong, *const Attribute, CkUlong, *mut CkUlong) -> CkRv;
+pub(crate) type FindObjectsInit = unsafe extern "C" fn(CkUlong, *const Attribute, CkUlong) -> CkRv;
+pub(crate) type FindObjects = unsafe extern "C" fn(CkUlong, *mut CkUlong, CkUlong, *mut CkUlong) -> CkRv;
+pub(crate) type FindObjectsFinal = unsafe extern "C" fn(CkUlong) -> CkRv;
+pub(crate) type SignInit = unsafe extern "C" fn(CkUlong, *const Mechanism, CkUlong) -> CkRv;
+pub(crate) type Sign = unsafe extern "C" fn(CkUlong, *const u8, CkUlong, *mut u8, *mut CkUlong) -> CkRv;
+
+/// The entry points of a loaded module; only valid while its library is loaded.
+pub(crate) struct Functions {
+    pub(crate) initialize: Initialize,
+    pub(crate) finalize: Finalize,
+    pub(crate) get_slot_list: GetSlotList,
+    pub(crate) get_token_info: GetTokenInfo,
+    pub(crate) open_session: OpenSession,
+    pub(crate) close_session: CloseSession,
+    pub(crate) login: Login,
+    pub(crate) create_object: CreateObject,
+    pub(crate) find_objects_init: FindObjectsInit,
+    pub(crate) find_objects: FindObjects,
+    pub(crate) find_objects_final: FindObjectsFinal,
+    pub(crate) sign_init: SignInit,
+    pub(crate) sign: Sign,
+}
+
+impl Functions {
+    /// Resolves the `C_*` functions exported by `library`.
+    ///
+    /// # Safety
+    ///
+    /// `library` must be a PKCS#11 module, whose exported functions have the signatures of the standard.
+    pub(crate) unsafe fn load(library: &libloading::Library) -> Result<Self, libloading::Error> {
+        Ok(Self {
+            initialize: *library.get(b"C_Initialize\0")?,
+            finalize: *library.get(b"C_Finalize\0")?,
+            get_slot_list: *library.get(b"C_GetSlotList\0")?,
+            get_token_info: *library.get(b"C_GetTokenInfo\0")?,
+            open_session: *library.get(b"C_OpenSession\0")?,
+            close_session: *library.get(b"C_CloseSession\0")?,
+            login: *library.get(b"C_Login\0")?,
+            create_object: *library.get(b"C_CreateObject\0")?,
+            find_objects_init: *library.get(b"C_FindObjectsInit\0")?,
+            find_objects: *library.get(b"C_FindObjects\0")?,
+            find_objects_final: *library.get(b"C_FindObjectsFinal\0")?,
+            sign_init: *library.get(b"C_SignInit\0")?,
+            sign: *library.get(b"C_Sign\0")?,
+        })
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{OtpResult, PskcError};
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::Sha256;
+
+const AES128_CBC: &str = "http://www.w3.org/2001/04/xmlenc#aes128-cbc";
+const AES192_CBC: &str = "http://www.w3.org/2001/04/xmlenc#aes192-cbc";
+const AES256_CBC: &str = "http://www.w3.org/2001/04/xmlenc#aes256-cbc";
+const HMAC_SHA1: &str = "http://www.w3.org/2000/09/xmldsig#hmac-sha1";
+const HMAC_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#hmac-sha256";
+const IV_LENGTH: usize = 16;
+
+fn error(message: String) -> Box<dyn crate::OtpError> {
+    Box::new(PskcError(message))
+}
+
+/// Decrypts an xmlenc CBC `CipherValue`, i.e. the IV followed by the PKCS#7 padded ciphertext.
+pub(crate) fn decrypt(algorithm: &str, key: &[u8], data: &[u8]) -> OtpResult<Vec<u8>> {
+    if data.len() <= IV_LENGTH {
+        return Err(error("encrypted value is too short".to_string()));
+    }
+    let (iv, ciphertext) = data.split_at(IV_LENGTH);
+    let invalid_key = |_| error(format!("the key does not fit `{}`", algorithm));
+    let plain = match algorithm {
+        AES128_CBC => cbc::Decryptor::<aes::Aes128>::new_from_slices(key, iv)
+            .map_err(invalid_key)?
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext),
+        AES192_CBC => cbc::Decryptor::<aes::Aes192>::new_from_slices(key, iv)
+            .map_err(invalid_key)?
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext),
+        AES256_CBC => cbc::Decryptor::<aes::Aes256>::new_from_slices(key, iv)
+            .map_err(invalid_key)?
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext),
+        _ => return Err(error(format!("unsupported encryption algorithm `{}`", algorithm))),
+    };
+    plain.map_err(|_| error("decryption failed, wrong pre-shared key?".to_string()))
+}
+
+/// Checks that `mac` is the MAC of `data` under `key`.
+pub(crate) fn verify_mac(algorithm: &str, key: &[u8], data: &[u8], mac: &[u8]) -> OtpResult<()> {
+    let valid = match algorithm {
+        HMAC_SHA1 => Hmac::<Sha1>::new_from_slice(key).map(|hmac| hmac.chain_update(data).verify_slice(mac).is_ok()),
+        HMAC_SHA256 => {
+            Hmac::<Sha256>::new_from_slice(key).map(|hmac| hmac.chain_update(data).verify_slice(mac).is_ok())
+        }
+        _ => return Err(error(format!("unsupported MAC algorithm `{}`", algorithm))),
+    };
+    match valid {
+        Ok(true) => Ok(()),
+        _ => Err(error("MAC verification failed".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod crypto_test;
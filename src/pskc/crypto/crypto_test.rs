@@ -0,0 +1,59 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use super::*;
+use crate::Secret;
+
+fn hex(text: &str) -> Vec<u8> {
+    Secret::new_from_hex(text).unwrap().get()
+}
+
+const CIPHER_VALUE: &str =
+    "101112131415161718191a1b1c1d1e1f53ce1be4481667306c9813112ddacec6ef98a3e296e7a2b677dc8d69e9f765c5";
+
+#[test]
+fn decrypt_should_support_aes256_cbc() {
+    let key: Vec<u8> = (0..32).collect();
+    assert_eq!(decrypt(AES256_CBC, &key, &hex(CIPHER_VALUE)).unwrap(), b"12345678901234567890");
+}
+
+#[test]
+fn decrypt_should_reject_key_of_wrong_size() {
+    let key: Vec<u8> = (0..32).collect();
+    assert_eq!(
+        decrypt(AES128_CBC, &key, &hex(CIPHER_VALUE)).unwrap_err().to_string(),
+        format!("PSKC error: the key does not fit `{}`", AES128_CBC)
+    );
+}
+
+#[test]
+fn decrypt_should_reject_unsupported_algorithm() {
+    let result = decrypt("http://www.w3.org/2001/04/xmlenc#tripledes-cbc", &[0; 24], &hex(CIPHER_VALUE));
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("unsupported encryption algorithm"));
+}
+
+#[test]
+fn decrypt_should_reject_value_without_ciphertext() {
+    assert!(decrypt(AES128_CBC, &[0; 16], &[0; 16]).is_err());
+}
+
+#[test]
+fn verify_mac_should_check_hmac_sha256() {
+    // RFC 4231 test case 2
+    let mac = hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    assert!(verify_mac(HMAC_SHA256, b"Jefe", b"what do ya want for nothing?", &mac).is_ok());
+    assert!(verify_mac(HMAC_SHA256, b"Jefe", b"what do ya want for something?", &mac).is_err());
+}
+
+#[test]
+fn verify_mac_should_reject_unsupported_algorithm() {
+    let result = verify_mac("http://www.w3.org/2001/04/xmldsig-more#hmac-md5", b"key", b"data", &[]);
+    assert!(result.unwrap_err().to_string().contains("unsupported MAC algorithm"));
+}
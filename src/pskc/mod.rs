@@ -0,0 +1,362 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! Import of PSKC (RFC 6030) key containers, enabled with the `pskc` feature.
+//!
+//! Vendors of OATH hardware tokens ship the token seeds as `KeyContainer` XML documents. Keys with the algorithm
+//! `urn:ietf:params:xml:ns:keyprov:pskc:hotp` or `urn:ietf:params:xml:ns:keyprov:pskc:totp` are turned into
+//! `HOTP`/`TOTP` generators, using the `ResponseFormat` length and encoding, the `Counter` and the `TimeInterval` of
+//! the key. Secrets encrypted with a pre-shared key (AES-CBC, RFC 6030 section 6.1) are decrypted and their
+//! `ValueMAC` (HMAC-SHA1 or HMAC-SHA256) is verified. Keys derived from a passphrase (PBKDF2) and asymmetric
+//! encryption are not supported.
+//!
+//! ```no_run
+//! use rusotp::pskc::PskcContainer;
+//! use rusotp::Secret;
+//!
+//! let xml = std::fs::read_to_string("tokens.pskc").unwrap();
+//! let pre_shared_key = Secret::new_from_hex("12345678901234567890123456789012").unwrap().get();
+//! let container = PskcContainer::parse(&xml, Some(&pre_shared_key)).unwrap();
+//! for key in container.keys {
+//!     println!("{:?} {:?}", key.serial_no, key.otp);
+//! }
+//! ```
+
+mod crypto;
+
+use crate::otp::algorithm::Algorithm;
+use crate::{OtpAccount, OtpError, OtpKind, OtpResult, PskcError, Radix, Secret, HOTP, TOTP};
+use base64ct::{Base64, Encoding};
+use roxmltree::{Document, Node};
+use std::num::{NonZeroU64, NonZeroU8};
+
+const NAMESPACE: &str = "urn:ietf:params:xml:ns:keyprov:pskc";
+const VERSION: &str = "1.0";
+
+/// A `HOTP`/`TOTP` key read from a PSKC container.
+///
+/// # Fields
+///
+/// * `id` - The `Id` of the key, unique within the container.
+/// * `issuer` - The `Issuer` of the key.
+/// * `user_id` - The `UserId` of the key or, if missing, of the device.
+/// * `friendly_name` - The `FriendlyName` of the key.
+/// * `manufacturer` - The `Manufacturer` of the device.
+/// * `serial_no` - The `SerialNo` of the device, usually printed on the token.
+/// * `otp` - The generator, with the current counter for HOTP keys.
+#[derive(Debug, PartialEq)]
+pub struct PskcKey {
+    pub id: String,
+    pub issuer: Option<String>,
+    pub user_id: Option<String>,
+    pub friendly_name: Option<String>,
+    pub manufacturer: Option<String>,
+    pub serial_no: Option<String>,
+    pub otp: OtpKind,
+}
+
+/// A key of a PSKC container which could not be imported.
+///
+/// # Fields
+///
+/// * `index` - The position of the `KeyPackage` in the container, starting at 0.
+/// * `id` - The `Id` of the key, empty if missing.
+/// * `error` - The reason, e.g. an unsupported algorithm such as `urn:ietf:params:xml:ns:keyprov:pskc:pin`.
+#[derive(Debug)]
+pub struct PskcKeyError {
+    pub index: usize,
+    pub id: String,
+    pub error: Box<dyn OtpError>,
+}
+
+/// A parsed PSKC `KeyContainer`.
+///
+/// # Fields
+///
+/// * `id` - The `Id` of the container.
+/// * `keys` - The `HOTP`/`TOTP` keys, in document order.
+/// * `errors` - The key packages which were skipped.
+#[derive(Debug)]
+pub struct PskcContainer {
+    pub id: Option<String>,
+    pub keys: Vec<PskcKey>,
+    pub errors: Vec<PskcKeyError>,
+}
+
+/// The keys protecting the values of a container.
+struct Protection<'a> {
+    encryption_key: Option<&'a [u8]>,
+    mac: Option<(&'a str, Vec<u8>)>,
+}
+
+/// A `PlainValue`, or the decrypted bytes of an `EncryptedValue`.
+enum DataValue {
+    Plain(String),
+    Decrypted(Vec<u8>),
+}
+
+fn error(message: &str) -> Box<dyn OtpError> {
+    Box::new(PskcError(message.to_string()))
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn children<'a, 'input: 'a>(node: Node<'a, 'input>, name: &'a str) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn text(node: Node, name: &str) -> Option<String> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+}
+
+fn decode_base64(text: &str) -> OtpResult<Vec<u8>> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    Base64::decode_vec(&text).map_err(|_| error("invalid base64 value"))
+}
+
+impl PskcContainer {
+    /// Parses a PSKC `KeyContainer` document.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The XML document.
+    /// * `pre_shared_key` - The AES key the secrets are encrypted with, if the container uses encryption.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the keys and the key packages which were skipped, or an error if the document is
+    /// not a PSKC 1.0 container or its MAC key cannot be decrypted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::pskc::PskcContainer;
+    /// use rusotp::OtpKind;
+    ///
+    /// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <KeyContainer Version="1.0" Id="exampleID1" xmlns="urn:ietf:params:xml:ns:keyprov:pskc">
+    ///   <KeyPackage>
+    ///     <Key Id="12345678" Algorithm="urn:ietf:params:xml:ns:keyprov:pskc:hotp">
+    ///       <Issuer>Issuer-A</Issuer>
+    ///       <Data>
+    ///         <Secret><PlainValue>MTIzNDU2Nzg5MDEyMzQ1Njc4OTA=</PlainValue></Secret>
+    ///       </Data>
+    ///     </Key>
+    ///   </KeyPackage>
+    /// </KeyContainer>"#;
+    ///
+    /// let container = PskcContainer::parse(xml, None).unwrap();
+    /// if let OtpKind::HOTP(hotp, counter) = &container.keys[0].otp {
+    ///     assert_eq!(hotp.generate(*counter).unwrap(), "755224");
+    /// }
+    /// ```
+    pub fn parse(xml: &str, pre_shared_key: Option<&[u8]>) -> OtpResult<PskcContainer> {
+        let document = Document::parse(xml).map_err(|e| Box::new(PskcError(e.to_string())) as Box<dyn OtpError>)?;
+        let root = document.root_element();
+        if root.tag_name().name() != "KeyContainer" || root.tag_name().namespace() != Some(NAMESPACE) {
+            return Err(error("not a PSKC key container"));
+        }
+        if root.attribute("Version") != Some(VERSION) {
+            return Err(error("unsupported PSKC version"));
+        }
+
+        let protection = Self::protection(root, pre_shared_key)?;
+        let mut container = PskcContainer {
+            id: root.attribute("Id").map(str::to_string),
+            keys: Vec::new(),
+            errors: Vec::new(),
+        };
+        for (index, package) in children(root, "KeyPackage").enumerate() {
+            match PskcKey::parse(package, &protection) {
+                Ok(key) => container.keys.push(key),
+                Err(error) => container.errors.push(PskcKeyError {
+                    index,
+                    id: child(package, "Key")
+                        .and_then(|key| key.attribute("Id"))
+                        .unwrap_or_default()
+                        .to_string(),
+                    error,
+                }),
+            }
+        }
+        Ok(container)
+    }
+
+    /// Reads the `EncryptionKey` and `MACMethod` of the container.
+    fn protection<'a>(root: Node<'a, '_>, pre_shared_key: Option<&'a [u8]>) -> OtpResult<Protection<'a>> {
+        if let Some(encryption_key) = child(root, "EncryptionKey") {
+            if child(encryption_key, "DerivedKey").is_some() {
+                return Err(error("passphrase derived keys are not supported"));
+            }
+            if child(encryption_key, "X509Data").is_some() {
+                return Err(error("asymmetric key transport is not supported"));
+            }
+        }
+
+        let mut protection = Protection {
+            encryption_key: pre_shared_key,
+            mac: None,
+        };
+        if let Some(method) = child(root, "MACMethod") {
+            let algorithm = method
+                .attribute("Algorithm")
+                .ok_or_else(|| error("missing MAC algorithm"))?;
+            // Without a MACKey element, the MAC key is the encryption key.
+            let key = match child(method, "MACKey") {
+                Some(mac_key) => decrypt_value(mac_key, pre_shared_key)?,
+                None => pre_shared_key
+                    .ok_or_else(|| error("a pre-shared key is required"))?
+                    .to_vec(),
+            };
+            protection.mac = Some((algorithm, key));
+        }
+        Ok(protection)
+    }
+}
+
+/// Decrypts an element holding `EncryptionMethod` and `CipherData` children.
+fn decrypt_value(node: Node, key: Option<&[u8]>) -> OtpResult<Vec<u8>> {
+    let key = key.ok_or_else(|| error("the container is encrypted, a pre-shared key is required"))?;
+    crypto::decrypt(
+        child(node, "EncryptionMethod")
+            .and_then(|method| method.attribute("Algorithm"))
+            .ok_or_else(|| error("missing encryption algorithm"))?,
+        key,
+        &cipher_value(node)?,
+    )
+}
+
+fn cipher_value(node: Node) -> OtpResult<Vec<u8>> {
+    let value = child(node, "CipherData")
+        .and_then(|data| text(data, "CipherValue"))
+        .ok_or_else(|| error("missing CipherValue"))?;
+    decode_base64(&value)
+}
+
+impl Protection<'_> {
+    /// Reads the value of a `Data` child such as `Secret` or `Counter`, checking its `ValueMAC`.
+    fn value(&self, node: Node) -> OtpResult<DataValue> {
+        if let Some(plain) = text(node, "PlainValue") {
+            return Ok(DataValue::Plain(plain));
+        }
+        let encrypted = child(node, "EncryptedValue").ok_or_else(|| error("missing value"))?;
+
+        match (&self.mac, text(node, "ValueMAC")) {
+            (Some((algorithm, key)), Some(mac)) => {
+                crypto::verify_mac(algorithm, key, &cipher_value(encrypted)?, &decode_base64(&mac)?)?
+            }
+            (Some(_), None) => return Err(error("missing ValueMAC")),
+            (None, Some(_)) => return Err(error("missing MACMethod")),
+            (None, None) => {}
+        }
+        decrypt_value(encrypted, self.encryption_key).map(DataValue::Decrypted)
+    }
+
+    fn bytes(&self, node: Node) -> OtpResult<Vec<u8>> {
+        match self.value(node)? {
+            DataValue::Plain(text) => decode_base64(&text),
+            DataValue::Decrypted(bytes) => Ok(bytes),
+        }
+    }
+
+    fn integer(&self, node: Node) -> OtpResult<u64> {
+        match self.value(node)? {
+            DataValue::Plain(text) => text.parse().map_err(|_| error("invalid integer value")),
+            DataValue::Decrypted(bytes) if bytes.len() <= 8 => {
+                Ok(bytes.iter().fold(0, |value, byte| value << 8 | *byte as u64))
+            }
+            DataValue::Decrypted(_) => Err(error("invalid integer value")),
+        }
+    }
+}
+
+impl PskcKey {
+    fn parse(package: Node, protection: &Protection) -> OtpResult<PskcKey> {
+        let key = child(package, "Key").ok_or_else(|| error("missing Key"))?;
+        let id = key.attribute("Id").ok_or_else(|| error("missing key Id"))?.to_string();
+        let algorithm = key.attribute("Algorithm").unwrap_or_default();
+        let kind = match algorithm.rsplit([':', '#']).next() {
+            Some("hotp") => "hotp",
+            Some("totp") => "totp",
+            _ => return Err(Box::new(PskcError(format!("unsupported algorithm `{}`", algorithm)))),
+        };
+
+        let parameters = child(key, "AlgorithmParameters");
+        let format = parameters.and_then(|parameters| child(parameters, "ResponseFormat"));
+        let length = match format.and_then(|format| format.attribute("Length")) {
+            Some(length) => length.parse().map_err(|_| error("invalid ResponseFormat Length"))?,
+            None => NonZeroU8::new(6).unwrap(),
+        };
+        let radix = match format
+            .and_then(|format| format.attribute("Encoding"))
+            .unwrap_or("DECIMAL")
+        {
+            "DECIMAL" => Radix::new(10).unwrap(),
+            "HEXADECIMAL" => Radix::new(16).unwrap(),
+            encoding => return Err(Box::new(PskcError(format!("unsupported encoding `{}`", encoding)))),
+        };
+        let hash = match parameters.and_then(|parameters| text(parameters, "Suite")) {
+            Some(suite) if suite.to_ascii_uppercase().contains("SHA512") => Algorithm::SHA512,
+            Some(suite) if suite.to_ascii_uppercase().contains("SHA256") => Algorithm::SHA256,
+            _ => Algorithm::SHA1,
+        };
+
+        let data = child(key, "Data").ok_or_else(|| error("missing Data"))?;
+        let secret = protection.bytes(child(data, "Secret").ok_or_else(|| error("missing Secret"))?)?;
+        if secret.is_empty() {
+            return Err(error("empty Secret"));
+        }
+        let secret = Secret::new_from_vec(secret);
+
+        let otp = if kind == "hotp" {
+            let counter = match child(data, "Counter") {
+                Some(counter) => protection.integer(counter)?,
+                None => 0,
+            };
+            OtpKind::HOTP(HOTP::new(hash, secret, length, radix), counter)
+        } else {
+            let interval = match child(data, "TimeInterval") {
+                Some(interval) => protection.integer(interval)?,
+                None => 30,
+            };
+            let interval = NonZeroU64::new(interval).ok_or_else(|| error("invalid TimeInterval"))?;
+            OtpKind::TOTP(TOTP::new(hash, secret, length, radix, interval))
+        };
+
+        let device = child(package, "DeviceInfo");
+        Ok(PskcKey {
+            id,
+            issuer: text(key, "Issuer"),
+            user_id: text(key, "UserId").or_else(|| device.and_then(|device| text(device, "UserId"))),
+            friendly_name: text(key, "FriendlyName"),
+            manufacturer: device.and_then(|device| text(device, "Manufacturer")),
+            serial_no: device.and_then(|device| text(device, "SerialNo")),
+            otp,
+        })
+    }
+
+    /// Converts the key into an account, named after the user, the friendly name, the serial number or the key Id,
+    /// whichever is present first.
+    pub fn into_account(self) -> OtpAccount {
+        let name = self
+            .user_id
+            .or(self.friendly_name)
+            .or(self.serial_no)
+            .unwrap_or(self.id);
+        OtpAccount {
+            issuer: self.issuer.unwrap_or_default(),
+            name,
+            otp: self.otp,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{OtpResult, RecoveryCodeError};
+use rand_core::{OsRng, RngCore};
+
+/// Digits and upper-case letters without the easily confused `0`, `1`, `I` and `O`.
+pub const DEFAULT_ALPHABET: &str = "23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// The shape of generated recovery codes.
+///
+/// # Fields
+///
+/// * `count` - Number of codes generated at once.
+/// * `alphabet` - Characters the codes are made of; each character is picked uniformly.
+/// * `groups` - Number of groups per code.
+/// * `group_length` - Number of characters per group.
+/// * `separator` - Character between two groups; it is ignored when a code is verified.
+///
+/// The default generates 10 codes like `7KQM-X2PD`, each carrying 40 bits of entropy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryCodeConfig {
+    pub count: usize,
+    pub alphabet: String,
+    pub groups: usize,
+    pub group_length: usize,
+    pub separator: char,
+}
+
+impl Default for RecoveryCodeConfig {
+    fn default() -> Self {
+        Self {
+            count: 10,
+            alphabet: DEFAULT_ALPHABET.to_string(),
+            groups: 2,
+            group_length: 4,
+            separator: '-',
+        }
+    }
+}
+
+impl RecoveryCodeConfig {
+    /// Checks that the configuration generates usable codes.
+    ///
+    /// # Returns
+    ///
+    /// `RecoveryCodeError` if `count`, `groups` or `group_length` is zero, or if the alphabet has less than two
+    /// distinct characters, contains duplicates, whitespace or the separator.
+    pub fn validate(&self) -> OtpResult<()> {
+        let error = |message: &str| Err(Box::new(RecoveryCodeError(message.to_string())) as _);
+        let alphabet: Vec<char> = self.alphabet.chars().collect();
+
+        if self.count == 0 || self.groups == 0 || self.group_length == 0 {
+            return error("count, groups and group length must be greater than 0");
+        }
+        if alphabet.len() < 2 {
+            return error("the alphabet must have at least 2 characters");
+        }
+        if alphabet.iter().enumerate().any(|(i, c)| alphabet[..i].contains(c)) {
+            return error("the alphabet must not contain duplicates");
+        }
+        if alphabet.iter().any(|c| c.is_whitespace() || *c == self.separator) {
+            return error("the alphabet must not contain whitespace or the separator");
+        }
+        Ok(())
+    }
+
+    /// Generates a single code, grouped and separated as configured.
+    pub(crate) fn generate_code(&self) -> String {
+        let alphabet: Vec<char> = self.alphabet.chars().collect();
+        let mut code = String::with_capacity(self.groups * (self.group_length + 1));
+        for group in 0..self.groups {
+            if group > 0 {
+                code.push(self.separator);
+            }
+            for _ in 0..self.group_length {
+                code.push(alphabet[uniform(alphabet.len() as u32) as usize]);
+            }
+        }
+        code
+    }
+
+    /// Brings user input into the form the codes are hashed in: without separators and whitespace, and upper-cased
+    /// if the alphabet has no lower-case letters.
+    pub(crate) fn normalize(&self, code: &str) -> String {
+        let case_insensitive = !self.alphabet.chars().any(char::is_lowercase);
+        code.chars()
+            .filter(|c| !c.is_whitespace() && *c != self.separator)
+            .map(|c| if case_insensitive { c.to_ascii_uppercase() } else { c })
+            .collect()
+    }
+}
+
+/// Returns a uniformly distributed random number below `bound`, rejecting values which would bias the modulo.
+fn uniform(bound: u32) -> u32 {
+    let zone = u32::MAX - (u32::MAX % bound);
+    loop {
+        let value = OsRng.next_u32();
+        if value < zone {
+            return value % bound;
+        }
+    }
+}
+
+#[cfg(test)]
+mod config_test;
@@ -0,0 +1,94 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use super::*;
+
+#[test]
+fn default_should_be_valid() {
+    assert!(RecoveryCodeConfig::default().validate().is_ok());
+}
+
+#[test]
+fn validate_should_reject_zero_sizes() {
+    for config in [
+        RecoveryCodeConfig {
+            count: 0,
+            ..Default::default()
+        },
+        RecoveryCodeConfig {
+            groups: 0,
+            ..Default::default()
+        },
+        RecoveryCodeConfig {
+            group_length: 0,
+            ..Default::default()
+        },
+    ] {
+        assert!(config.validate().is_err());
+    }
+}
+
+#[test]
+fn validate_should_reject_invalid_alphabets() {
+    for alphabet in ["A", "ABCA", "AB C", "AB-C"] {
+        let config = RecoveryCodeConfig {
+            alphabet: alphabet.to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err(), "{}", alphabet);
+    }
+}
+
+#[test]
+fn generate_code_should_follow_grouping() {
+    let config = RecoveryCodeConfig {
+        groups: 3,
+        group_length: 5,
+        separator: ' ',
+        ..Default::default()
+    };
+    let code = config.generate_code();
+
+    let groups: Vec<&str> = code.split(' ').collect();
+    assert_eq!(groups.len(), 3);
+    assert!(groups.iter().all(|group| group.len() == 5));
+    assert!(code.chars().filter(|c| *c != ' ').all(|c| DEFAULT_ALPHABET.contains(c)));
+}
+
+#[test]
+fn generate_code_should_use_alphabet() {
+    let config = RecoveryCodeConfig {
+        alphabet: "01".to_string(),
+        groups: 1,
+        group_length: 64,
+        ..Default::default()
+    };
+    let code = config.generate_code();
+
+    assert!(code.chars().all(|c| c == '0' || c == '1'));
+    assert!(code.contains('0') && code.contains('1'));
+}
+
+#[test]
+fn normalize_should_ignore_separator_whitespace_and_case() {
+    let config = RecoveryCodeConfig::default();
+    assert_eq!(config.normalize(" 7kqm-x2pd "), "7KQMX2PD");
+}
+
+#[test]
+fn normalize_should_keep_case_for_mixed_case_alphabet() {
+    let config = RecoveryCodeConfig {
+        alphabet: "abcABC".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(config.normalize("aB-c"), "aBc");
+}
+
+#[test]
+fn uniform_should_stay_below_bound() {
+    assert!((0..1000).all(|_| uniform(3) < 3));
+}
@@ -0,0 +1,78 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{OtpError, OtpResult, RecoveryCodeError};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Argon2, Params};
+use rand_core::{OsRng, RngCore};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const SALT_LENGTH: usize = 16;
+
+/// Upper bounds of the parameters of a parsed hash, so that a tampered store cannot make verification exhaust memory
+/// or time.
+const MAX_MEMORY_KIB: u32 = 256 * 1024;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_PARALLELISM: u32 = 8;
+
+/// The salted Argon2id hash of a recovery code, the only form in which codes are stored.
+///
+/// It is written as a PHC string, e.g. `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`, by `to_string` and read back
+/// with `parse`. Codes are hashed with the Argon2id parameters recommended by OWASP (19 MiB, 2 iterations, 1 lane);
+/// since the parameters are part of the string, codes hashed with other parameters are still verified with their own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashedRecoveryCode(String);
+
+impl HashedRecoveryCode {
+    /// Hashes a normalized code with a fresh random salt.
+    pub(crate) fn new(code: &str) -> OtpResult<Self> {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let salt = SaltString::encode_b64(&salt).map_err(hash_error)?;
+        let hash = Argon2::default()
+            .hash_password(code.as_bytes(), &salt)
+            .map_err(hash_error)?;
+        Ok(Self(hash.to_string()))
+    }
+
+    /// Checks a normalized code against the hash, comparing the digests in constant time.
+    pub(crate) fn matches(&self, code: &str) -> bool {
+        PasswordHash::new(&self.0)
+            .and_then(|hash| Argon2::default().verify_password(code.as_bytes(), &hash))
+            .is_ok()
+    }
+}
+
+fn hash_error(error: argon2::password_hash::Error) -> Box<dyn OtpError> {
+    Box::new(RecoveryCodeError(error.to_string()))
+}
+
+impl Display for HashedRecoveryCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for HashedRecoveryCode {
+    type Err = Box<dyn OtpError>;
+
+    fn from_str(text: &str) -> OtpResult<Self> {
+        let invalid = || Box::new(RecoveryCodeError(format!("invalid hashed code `{}`", text))) as _;
+        let hash = PasswordHash::new(text).map_err(|_| invalid())?;
+        let params = Params::try_from(&hash).map_err(|_| invalid())?;
+        if hash.algorithm != argon2::ARGON2ID_IDENT
+            || hash.hash.is_none()
+            || params.m_cost() > MAX_MEMORY_KIB
+            || params.t_cost() > MAX_ITERATIONS
+            || params.p_cost() > MAX_PARALLELISM
+        {
+            return Err(invalid());
+        }
+        Ok(Self(text.to_string()))
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! One-time recovery codes, enabled with the `recovery` feature.
+//!
+//! Recovery codes let users sign in when their authenticator is lost. They are shown once when generated; only their
+//! salted Argon2id hashes are kept in a [`RecoveryCodeStore`], and each code is accepted a single time.
+//!
+//! ```
+//! use rusotp::recovery::{MemoryRecoveryCodeStore, RecoveryCodeConfig, RecoveryCodes};
+//!
+//! let codes = RecoveryCodes::new(MemoryRecoveryCodeStore::new(), RecoveryCodeConfig::default()).unwrap();
+//! let plain = codes.generate("user@email.mail").unwrap();
+//! assert_eq!(plain.len(), 10);
+//!
+//! assert!(codes.verify("user@email.mail", &plain[0]).unwrap());
+//! assert!(!codes.verify("user@email.mail", &plain[0]).unwrap());
+//! assert_eq!(codes.remaining("user@email.mail").unwrap(), 9);
+//! ```
+
+mod config;
+mod hash;
+mod store;
+
+pub use config::{RecoveryCodeConfig, DEFAULT_ALPHABET};
+pub use hash::HashedRecoveryCode;
+pub use store::{MemoryRecoveryCodeStore, RecoveryCodeStore};
+
+use crate::OtpResult;
+
+/// Generates, verifies and consumes the recovery codes of users, backed by a [`RecoveryCodeStore`].
+#[derive(Debug)]
+pub struct RecoveryCodes<S: RecoveryCodeStore> {
+    store: S,
+    config: RecoveryCodeConfig,
+}
+
+impl<S: RecoveryCodeStore> RecoveryCodes<S> {
+    /// Creates a manager for the codes kept in `store`.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the manager, or `RecoveryCodeError` if the configuration is invalid, see
+    /// [`RecoveryCodeConfig::validate`].
+    pub fn new(store: S, config: RecoveryCodeConfig) -> OtpResult<Self> {
+        config.validate()?;
+        Ok(Self { store, config })
+    }
+
+    /// Returns the store of the codes.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Generates a new set of codes for `user`, replacing any previous set.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The key of the user in the store.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the plain codes, to be shown to the user once, or the error of the store.
+    pub fn generate(&self, user: &str) -> OtpResult<Vec<String>> {
+        let codes: Vec<String> = (0..self.config.count).map(|_| self.config.generate_code()).collect();
+        let hashes = codes
+            .iter()
+            .map(|code| HashedRecoveryCode::new(&self.config.normalize(code)))
+            .collect::<OtpResult<_>>()?;
+        self.store.replace(user, hashes)?;
+        Ok(codes)
+    }
+
+    /// Verifies a code entered by `user` and consumes it.
+    ///
+    /// Separators, whitespace and, for alphabets without lower-case letters, letter case are ignored. The code is
+    /// hashed once per unused code of the user, so a verification costs up to `count` Argon2id hashes.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The key of the user in the store.
+    /// * `code` - The code as entered.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing `true` if the code was valid and is now consumed, `false` if it is unknown or was
+    /// already used, or the error of the store.
+    pub fn verify(&self, user: &str, code: &str) -> OtpResult<bool> {
+        let code = self.config.normalize(code);
+        match self.store.load(user)?.into_iter().find(|hashed| hashed.matches(&code)) {
+            Some(hashed) => self.store.remove(user, &hashed),
+            None => Ok(false),
+        }
+    }
+
+    /// Returns the number of unused codes of `user`.
+    pub fn remaining(&self, user: &str) -> OtpResult<usize> {
+        Ok(self.store.load(user)?.len())
+    }
+}
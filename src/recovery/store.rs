@@ -0,0 +1,70 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::recovery::HashedRecoveryCode;
+use crate::{OtpResult, RecoveryCodeError};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Persistence of the hashed recovery codes of each user.
+///
+/// `remove` must be atomic: when two requests consume the same code concurrently, exactly one of them may see
+/// `true`. With a database this is a `DELETE ... WHERE user = ? AND code = ?` checking the number of affected rows.
+pub trait RecoveryCodeStore {
+    /// Returns the unused codes of `user`, empty if none were generated.
+    fn load(&self, user: &str) -> OtpResult<Vec<HashedRecoveryCode>>;
+
+    /// Replaces all codes of `user`, e.g. after generating a new set.
+    fn replace(&self, user: &str, codes: Vec<HashedRecoveryCode>) -> OtpResult<()>;
+
+    /// Removes `code` of `user`, returning `false` if it was already removed.
+    fn remove(&self, user: &str, code: &HashedRecoveryCode) -> OtpResult<bool>;
+}
+
+/// A [`RecoveryCodeStore`] keeping the codes in memory, for tests and single process services.
+#[derive(Debug, Default)]
+pub struct MemoryRecoveryCodeStore {
+    codes: Mutex<HashMap<String, Vec<HashedRecoveryCode>>>,
+}
+
+impl MemoryRecoveryCodeStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> OtpResult<std::sync::MutexGuard<'_, HashMap<String, Vec<HashedRecoveryCode>>>> {
+        self.codes
+            .lock()
+            .map_err(|_| Box::new(RecoveryCodeError("store lock poisoned".to_string())) as _)
+    }
+}
+
+impl RecoveryCodeStore for MemoryRecoveryCodeStore {
+    fn load(&self, user: &str) -> OtpResult<Vec<HashedRecoveryCode>> {
+        Ok(self.lock()?.get(user).cloned().unwrap_or_default())
+    }
+
+    fn replace(&self, user: &str, codes: Vec<HashedRecoveryCode>) -> OtpResult<()> {
+        self.lock()?.insert(user.to_string(), codes);
+        Ok(())
+    }
+
+    fn remove(&self, user: &str, code: &HashedRecoveryCode) -> OtpResult<bool> {
+        let mut codes = self.lock()?;
+        let Some(user_codes) = codes.get_mut(user) else {
+            return Ok(false);
+        };
+        match user_codes.iter().position(|stored| stored == code) {
+            Some(index) => {
+                user_codes.remove(index);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
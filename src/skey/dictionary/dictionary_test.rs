@@ -0,0 +1,50 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::skey::dictionary::{decode, encode, WORDS};
+
+#[test]
+fn dictionary_should_be_sorted_by_length_then_alphabetically() {
+    let (short, long) = WORDS.split_at(571);
+    assert!(short.iter().all(|word| word.len() <= 3));
+    assert!(long.iter().all(|word| word.len() == 4));
+    assert!(short.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(long.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn encode_should_match_rfc_samples() {
+    assert_eq!(encode(0x9E876134D90499DD), "INCH SEA ANNE LONG AHEM TOUR");
+    assert_eq!(encode(0xBB9E6AE1979D8FF4), "MILT VARY MAST OK SEES WENT");
+    assert_eq!(encode(0x4F296A74FE1567EC), "AURA ALOE HURL WING BERG WAIT");
+}
+
+#[test]
+fn decode_should_ignore_letter_case_and_whitespace() {
+    assert_eq!(decode("inch sea  Anne long\tahem TOUR"), Some(0x9E876134D90499DD));
+}
+
+#[test]
+fn decode_should_reject_wrong_checksum() {
+    assert_eq!(decode("INCH SEA ANNE LONG AHEM TOUT"), None);
+}
+
+#[test]
+fn decode_should_reject_unknown_words_and_counts() {
+    assert_eq!(decode("INCH SEA ANNE LONG AHEM TOURS"), None);
+    assert_eq!(decode("INCH SEA ANNE LONG AHEM"), None);
+    assert_eq!(decode("INCH SEA ANNE LONG AHEM TOUR TOUR"), None);
+}
+
+#[test]
+fn every_word_should_round_trip() {
+    for (index, word) in WORDS.iter().enumerate() {
+        let otp = (index as u64) << 53;
+        assert!(encode(otp).starts_with(word));
+        assert_eq!(decode(&encode(otp)), Some(otp));
+    }
+}
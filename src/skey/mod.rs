@@ -0,0 +1,294 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! S/KEY hash-chain one-time passwords (RFC 2289), enabled with the `skey` feature.
+//!
+//! An [`SKey`] hashes a seed and a pass-phrase, then hashes the result again once per sequence number, folding each
+//! hash to 64 bits. The OTP of sequence `n` is the hash of the OTP of sequence `n - 1`, so a server storing the OTP
+//! of sequence `n` in an [`SKeyState`] verifies the OTP of sequence `n - 1` by hashing it once, then stores it in turn:
+//! an OTP reveals nothing about the next one expected. OTPs are rendered as 16 hexadecimal digits or 6 words of the
+//! RFC 2289 dictionary, e.g. to print a list for hosts without a phone at hand.
+//!
+//! ```
+//! use rusotp::skey::{MemorySKeyStore, SKey, SKeyAlgorithm, SKeyStore, SKeyVerifier};
+//!
+//! let skey = SKey::new(SKeyAlgorithm::MD5, "This is a test.", "TeSt").unwrap();
+//! let verifier = SKeyVerifier::new(MemorySKeyStore::new());
+//! verifier.store().save("root", &skey.state(100)).unwrap();
+//!
+//! // The list handed to the user, from sequence 99 down to 0.
+//! let list = skey.sequence(100);
+//! assert_eq!(verifier.challenge("root").unwrap().unwrap(), "otp-md5 99 test");
+//! assert!(verifier.verify("root", &list[0].1.to_words()).unwrap());
+//! assert_eq!(verifier.challenge("root").unwrap().unwrap(), "otp-md5 98 test");
+//! ```
+
+mod dictionary;
+mod state;
+mod store;
+
+pub use state::SKeyState;
+pub use store::{MemorySKeyStore, SKeyStore};
+
+use crate::{OtpResult, SKeyError};
+use md5::Md5;
+use sha1::{Digest, Sha1};
+use std::fmt::{Debug, Display, Formatter};
+
+/// The shortest pass-phrase accepted by RFC 2289.
+const MIN_PASSPHRASE_LENGTH: usize = 10;
+
+/// The longest seed accepted by RFC 2289.
+const MAX_SEED_LENGTH: usize = 16;
+
+/// The hash function of an S/KEY sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SKeyAlgorithm {
+    MD5,
+    SHA1,
+}
+
+impl SKeyAlgorithm {
+    /// Returns the name of the algorithm in challenges, `md5` or `sha1`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SKeyAlgorithm::MD5 => "md5",
+            SKeyAlgorithm::SHA1 => "sha1",
+        }
+    }
+
+    /// Hashes `data` and folds the hash to 64 bits as specified by RFC 2289 (Appendix A).
+    fn fold(&self, data: &[u8]) -> SKeyOtp {
+        let bytes: [u8; 8] = match self {
+            SKeyAlgorithm::MD5 => {
+                let hash = Md5::digest(data);
+                core::array::from_fn(|i| hash[i] ^ hash[i + 8])
+            }
+            SKeyAlgorithm::SHA1 => {
+                let hash = Sha1::digest(data);
+                let word =
+                    |i: usize| u32::from_be_bytes([hash[4 * i], hash[4 * i + 1], hash[4 * i + 2], hash[4 * i + 3]]);
+                let mut bytes = [0; 8];
+                bytes[..4].copy_from_slice(&(word(0) ^ word(2) ^ word(4)).to_le_bytes());
+                bytes[4..].copy_from_slice(&(word(1) ^ word(3)).to_le_bytes());
+                bytes
+            }
+        };
+        SKeyOtp(u64::from_be_bytes(bytes))
+    }
+
+    /// Returns the OTP following `otp` in a sequence, i.e. the OTP of the previous sequence number.
+    fn next(&self, otp: SKeyOtp) -> SKeyOtp {
+        self.fold(&otp.0.to_be_bytes())
+    }
+}
+
+impl Display for SKeyAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A 64 bits S/KEY one-time password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SKeyOtp(pub u64);
+
+impl SKeyOtp {
+    /// Parses an OTP written as 16 hexadecimal digits or as 6 words of the RFC 2289 dictionary.
+    ///
+    /// Letter case and whitespace are ignored.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the OTP, or an `SKeyError` if it is neither valid hexadecimal nor 6 dictionary words
+    /// with a matching checksum.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::skey::SKeyOtp;
+    ///
+    /// assert_eq!(SKeyOtp::parse("9E87 6134 D904 99DD").unwrap(), SKeyOtp(0x9E876134D90499DD));
+    /// assert_eq!(SKeyOtp::parse("inch sea anne long ahem tour").unwrap(), SKeyOtp(0x9E876134D90499DD));
+    /// ```
+    pub fn parse(otp: &str) -> OtpResult<Self> {
+        let hex: String = otp.split_whitespace().collect();
+        if hex.len() == 16 {
+            if let Ok(otp) = u64::from_str_radix(&hex, 16) {
+                return Ok(SKeyOtp(otp));
+            }
+        }
+        dictionary::decode(otp)
+            .map(SKeyOtp)
+            .ok_or_else(|| error("OTP must be 16 hexadecimal digits or 6 dictionary words"))
+    }
+
+    /// Returns the OTP as 16 uppercase hexadecimal digits.
+    pub fn to_hex(&self) -> String {
+        format!("{:016X}", self.0)
+    }
+
+    /// Returns the OTP as 6 uppercase words of the RFC 2289 dictionary, separated by spaces.
+    pub fn to_words(&self) -> String {
+        dictionary::encode(self.0)
+    }
+}
+
+impl Display for SKeyOtp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// Generates the S/KEY sequence of a pass-phrase and a seed.
+#[derive(Clone, PartialEq)]
+pub struct SKey {
+    algorithm: SKeyAlgorithm,
+    seed: String,
+    initial: SKeyOtp,
+}
+
+impl Debug for SKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SKey")
+            .field("algorithm", &self.algorithm)
+            .field("seed", &self.seed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SKey {
+    /// Creates the generator of a sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The hash function of the sequence.
+    /// * `passphrase` - The secret pass-phrase, at least 10 characters.
+    /// * `seed` - The seed, 1 to 16 ASCII letters or digits, which is not secret and is case-insensitive. A new seed
+    ///   starts a new sequence with the same pass-phrase.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the generator, or an `SKeyError` if the pass-phrase or the seed is invalid.
+    pub fn new(algorithm: SKeyAlgorithm, passphrase: &str, seed: &str) -> OtpResult<Self> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+            return Err(error("pass-phrase must be at least 10 characters"));
+        }
+        let seed = validate_seed(seed)?;
+        let initial = algorithm.fold(format!("{}{}", seed, passphrase).as_bytes());
+
+        Ok(Self {
+            algorithm,
+            seed,
+            initial,
+        })
+    }
+
+    /// Returns the hash function of the sequence.
+    pub fn algorithm(&self) -> SKeyAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns the lowercase seed of the sequence.
+    pub fn seed(&self) -> &str {
+        &self.seed
+    }
+
+    /// Generates the OTP of sequence number `sequence`, hashing `sequence + 1` times.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::skey::{SKey, SKeyAlgorithm};
+    ///
+    /// let skey = SKey::new(SKeyAlgorithm::SHA1, "This is a test.", "TeSt").unwrap();
+    /// assert_eq!(skey.generate(0).to_hex(), "BB9E6AE1979D8FF4");
+    /// assert_eq!(skey.generate(0).to_words(), "MILT VARY MAST OK SEES WENT");
+    /// ```
+    pub fn generate(&self, sequence: u32) -> SKeyOtp {
+        (0..sequence).fold(self.initial, |otp, _| self.algorithm.next(otp))
+    }
+
+    /// Generates the OTPs accepted by [`SKey::state`] of `sequence`, in the order they are used.
+    ///
+    /// # Returns
+    ///
+    /// The sequence numbers and OTPs from `sequence - 1` down to 0, e.g. to print the list of a user.
+    pub fn sequence(&self, sequence: u32) -> Vec<(u32, SKeyOtp)> {
+        let mut otps = Vec::with_capacity(sequence as usize);
+        let mut otp = self.initial;
+        for number in 0..sequence {
+            otps.push((number, otp));
+            otp = self.algorithm.next(otp);
+        }
+        otps.reverse();
+        otps
+    }
+
+    /// Returns the state a server stores to accept the OTPs of the sequence numbers below `sequence`.
+    ///
+    /// Only `SKey::generate(sequence)` is stored, the pass-phrase never leaves the generator.
+    pub fn state(&self, sequence: u32) -> SKeyState {
+        SKeyState {
+            algorithm: self.algorithm,
+            seed: self.seed.clone(),
+            sequence,
+            last: self.generate(sequence),
+        }
+    }
+}
+
+/// Verifies S/KEY OTPs and records the last accepted one of each user, backed by an [`SKeyStore`].
+#[derive(Debug)]
+pub struct SKeyVerifier<S: SKeyStore> {
+    store: S,
+}
+
+impl<S: SKeyStore> SKeyVerifier<S> {
+    /// Creates a verifier reading and updating the states in `store`.
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Returns the store of the states.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the challenge to display to `user`, `None` if the user has no state or its sequence is exhausted.
+    pub fn challenge(&self, user: &str) -> OtpResult<Option<String>> {
+        Ok(self.store.load(user)?.and_then(|state| state.challenge()))
+    }
+
+    /// Verifies the OTP presented by `user` and records it as the last accepted one.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing `true` if the OTP is the one expected next, `false` if it is not, the user has no state
+    /// or another request accepted it first, or an `SKeyError` if it is malformed, or the error of the store.
+    pub fn verify(&self, user: &str, otp: &str) -> OtpResult<bool> {
+        match self.store.load(user)? {
+            Some(state) => match state.verify(otp)? {
+                Some(next) => self.store.advance(user, &next),
+                None => Ok(false),
+            },
+            None => Ok(false),
+        }
+    }
+}
+
+/// Checks a seed and converts it to lowercase.
+fn validate_seed(seed: &str) -> OtpResult<String> {
+    if seed.is_empty() || seed.len() > MAX_SEED_LENGTH || !seed.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(error("seed must be 1 to 16 ASCII letters or digits"));
+    }
+    Ok(seed.to_ascii_lowercase())
+}
+
+fn error(message: &str) -> Box<dyn crate::OtpError> {
+    Box::new(SKeyError(message.to_string()))
+}
@@ -0,0 +1,59 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::skey::{validate_seed, SKeyAlgorithm, SKeyOtp};
+use crate::OtpResult;
+
+/// What a server stores to verify the S/KEY OTPs of a user: the last accepted OTP and its sequence number.
+///
+/// # Fields
+///
+/// * `algorithm` - The hash function of the sequence.
+/// * `seed` - The lowercase seed of the sequence.
+/// * `sequence` - The sequence number of `last`; the next OTP expected has sequence number `sequence - 1`.
+/// * `last` - The last accepted OTP, or the one generated at initialization.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SKeyState {
+    pub algorithm: SKeyAlgorithm,
+    pub seed: String,
+    pub sequence: u32,
+    pub last: SKeyOtp,
+}
+
+impl SKeyState {
+    /// Returns the RFC 2289 challenge asking for the next OTP, e.g. `otp-md5 99 test`, or `None` once the OTP of
+    /// sequence number 0 was accepted and the sequence must be reinitialized.
+    pub fn challenge(&self) -> Option<String> {
+        self.sequence
+            .checked_sub(1)
+            .map(|next| format!("otp-{} {} {}", self.algorithm.name(), next, self.seed))
+    }
+
+    /// Verifies the next OTP against the last accepted one, without changing the state.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP as 16 hexadecimal digits or 6 dictionary words.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the state to store if the OTP hashes to the last accepted one, `None` if it does not
+    /// or the sequence is exhausted, or an `SKeyError` if the OTP or the seed is malformed.
+    pub fn verify(&self, otp: &str) -> OtpResult<Option<SKeyState>> {
+        let otp = SKeyOtp::parse(otp)?;
+        let seed = validate_seed(&self.seed)?;
+        match self.sequence.checked_sub(1) {
+            Some(sequence) if self.algorithm.next(otp) == self.last => Ok(Some(SKeyState {
+                algorithm: self.algorithm,
+                seed,
+                sequence,
+                last: otp,
+            })),
+            _ => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::skey::SKeyState;
+use crate::{OtpResult, SKeyError};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Persistence of the [`SKeyState`] of each user.
+///
+/// `advance` must be atomic: when the same OTP is presented twice concurrently, exactly one of them may see `true`.
+/// With a database this is an `UPDATE ... WHERE user = ? AND seed = ? AND sequence > ?` checking the number of
+/// affected rows.
+pub trait SKeyStore {
+    /// Returns the state of `user`, `None` if the user has none.
+    fn load(&self, user: &str) -> OtpResult<Option<SKeyState>>;
+
+    /// Stores `state` for `user`, replacing any previous one, e.g. to (re)initialize a sequence.
+    fn save(&self, user: &str, state: &SKeyState) -> OtpResult<()>;
+
+    /// Stores `state` for `user` if the stored state has the same seed and a greater sequence number, returning `false`
+    /// otherwise.
+    fn advance(&self, user: &str, state: &SKeyState) -> OtpResult<bool>;
+}
+
+/// An [`SKeyStore`] keeping the states in memory, for tests and single process services.
+#[derive(Debug, Default)]
+pub struct MemorySKeyStore {
+    states: Mutex<HashMap<String, SKeyState>>,
+}
+
+impl MemorySKeyStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> OtpResult<std::sync::MutexGuard<'_, HashMap<String, SKeyState>>> {
+        self.states
+            .lock()
+            .map_err(|_| Box::new(SKeyError("store lock poisoned".to_string())) as _)
+    }
+}
+
+impl SKeyStore for MemorySKeyStore {
+    fn load(&self, user: &str) -> OtpResult<Option<SKeyState>> {
+        Ok(self.lock()?.get(user).cloned())
+    }
+
+    fn save(&self, user: &str, state: &SKeyState) -> OtpResult<()> {
+        self.lock()?.insert(user.to_string(), state.clone());
+        Ok(())
+    }
+
+    fn advance(&self, user: &str, state: &SKeyState) -> OtpResult<bool> {
+        let mut states = self.lock()?;
+        match states.get_mut(user) {
+            Some(stored) if stored.seed == state.seed && stored.sequence > state.sequence => {
+                *stored = state.clone();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
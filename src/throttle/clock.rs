@@ -0,0 +1,48 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+/// Source of the current time of a [`Throttle`](crate::throttle::Throttle), in Unix seconds.
+///
+/// Any `Fn() -> u64` closure is a clock, which lets tests step through back-off and lockout periods without waiting.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::throttle::{Throttle, ThrottleConfig};
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+///
+/// let time = Arc::new(AtomicU64::new(1_000));
+/// let clock = time.clone();
+/// let throttle = Throttle::with_clock(ThrottleConfig::default(), move || clock.load(Ordering::SeqCst));
+///
+/// throttle.record_failure("user@email.mail");
+/// assert!(throttle.check("user@email.mail").is_err());
+///
+/// time.store(1_001, Ordering::SeqCst);
+/// assert!(throttle.check("user@email.mail").is_ok());
+/// ```
+pub trait Clock: Send + Sync {
+    /// Returns the current Unix timestamp in seconds.
+    fn now(&self) -> u64;
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        crate::util::time::now()
+    }
+}
+
+impl<F: Fn() -> u64 + Send + Sync> Clock for F {
+    fn now(&self) -> u64 {
+        self()
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+/// Limits applied by a [`Throttle`](crate::throttle::Throttle) to the failed attempts of each account.
+///
+/// # Fields
+///
+/// * `max_failures` - Number of consecutive failures after which the account is locked, `0` to never lock it.
+/// * `backoff` - Seconds to wait after the first failure; the wait doubles with each further failure, `0` to
+///   disable back-off.
+/// * `max_backoff` - Upper bound of the back-off wait in seconds.
+/// * `lockout` - Seconds the account stays locked once `max_failures` is reached; the failures count from zero
+///   again afterwards.
+///
+/// The default waits 1, 2, 4 and 8 seconds after the first four failures and locks the account for 15 minutes after
+/// the fifth, which leaves an attacker about 20 guesses an hour.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrottleConfig {
+    pub max_failures: u32,
+    pub backoff: u64,
+    pub max_backoff: u64,
+    pub lockout: u64,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: 5,
+            backoff: 1,
+            max_backoff: 60,
+            lockout: 900,
+        }
+    }
+}
+
+impl ThrottleConfig {
+    /// Returns the number of seconds an account is blocked for after its `failures`-th consecutive failure.
+    pub(crate) fn delay(&self, failures: u32) -> u64 {
+        if self.max_failures > 0 && failures >= self.max_failures {
+            return self.lockout;
+        }
+        if self.backoff == 0 || failures == 0 {
+            return 0;
+        }
+        let factor = 1u64.checked_shl(failures - 1).unwrap_or(u64::MAX);
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+#[cfg(test)]
+mod config_test;
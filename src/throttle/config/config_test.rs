@@ -0,0 +1,43 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use super::*;
+
+#[test]
+fn delay_should_double_until_lockout() {
+    let config = ThrottleConfig::default();
+
+    let delays: Vec<u64> = (1..=5).map(|failures| config.delay(failures)).collect();
+
+    assert_eq!(delays, vec![1, 2, 4, 8, 900]);
+}
+
+#[test]
+fn delay_should_be_capped_by_max_backoff() {
+    let config = ThrottleConfig {
+        max_failures: 0,
+        backoff: 5,
+        max_backoff: 30,
+        lockout: 0,
+    };
+
+    assert_eq!(config.delay(3), 20);
+    assert_eq!(config.delay(4), 30);
+    assert_eq!(config.delay(200), 30);
+}
+
+#[test]
+fn delay_should_be_zero_without_backoff() {
+    let config = ThrottleConfig {
+        backoff: 0,
+        ..Default::default()
+    };
+
+    assert_eq!(config.delay(1), 0);
+    assert_eq!(config.delay(4), 0);
+    assert_eq!(config.delay(5), 900);
+}
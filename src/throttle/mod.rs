@@ -0,0 +1,206 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! Throttling of verification attempts.
+//!
+//! `HOTP::verify` and `TOTP::verify_at` evaluate any number of guesses; a 6-digit code accepted within a window of
+//! ±1 time step falls to a few hundred thousand requests. A [`Throttle`] counts the consecutive failures of each
+//! account, makes it wait exponentially longer after each of them and locks it after too many, answering with a
+//! [`RateLimited`](crate::RateLimited) error in the meantime.
+//!
+//! ```
+//! use rusotp::throttle::{Throttle, ThrottleConfig};
+//! use rusotp::{Secret, HOTP};
+//!
+//! let throttle = Throttle::new(ThrottleConfig { backoff: 30, ..Default::default() });
+//! let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+//!
+//! assert_eq!(throttle.verify_hotp("user@email.mail", &hotp, "000000", 0, 0).unwrap(), None);
+//!
+//! let error = throttle.verify_hotp("user@email.mail", &hotp, "755224", 0, 0).unwrap_err();
+//! assert!(error.retry_after().is_some());
+//! ```
+
+mod clock;
+mod config;
+
+pub use clock::{Clock, SystemClock};
+pub use config::ThrottleConfig;
+
+use crate::{OtpResult, RateLimited, HOTP, TOTP};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Counts the failed verification attempts of each account and rejects attempts while an account is throttled.
+///
+/// Accounts are identified by a key chosen by the caller, e.g. the user name. The throttle is shared between
+/// threads by reference; the bookkeeping of an attempt and the verification run under a single lock, so concurrent
+/// guesses cannot slip past the limit.
+///
+/// The failures of an account are forgotten once it has not failed for the longest wait of the configuration
+/// (`lockout` or `max_backoff`) after its last wait ended, so that guesses against arbitrary keys cannot grow the
+/// bookkeeping without bound.
+pub struct Throttle {
+    config: ThrottleConfig,
+    clock: Box<dyn Clock>,
+    attempts: Mutex<HashMap<String, Attempts>>,
+}
+
+#[derive(Debug, Default)]
+struct Attempts {
+    failures: u32,
+    blocked_until: u64,
+}
+
+impl Debug for Throttle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Throttle")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Throttle {
+    /// Creates a throttle using the system clock.
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self::with_clock(config, SystemClock)
+    }
+
+    /// Creates a throttle reading the time from `clock`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The limits applied to each account.
+    /// * `clock` - The source of the current time, see [`Clock`].
+    pub fn with_clock<C: Clock + 'static>(config: ThrottleConfig, clock: C) -> Self {
+        Self {
+            config,
+            clock: Box::new(clock),
+            attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the limits of the throttle.
+    pub fn config(&self) -> &ThrottleConfig {
+        &self.config
+    }
+
+    /// Checks whether `key` may attempt a verification now.
+    ///
+    /// # Returns
+    ///
+    /// `RateLimited` with the remaining wait if the account is throttled.
+    pub fn check(&self, key: &str) -> OtpResult<()> {
+        self.check_locked(&mut self.lock(), key, self.clock.now())
+    }
+
+    /// Counts a failed attempt of `key`.
+    ///
+    /// # Returns
+    ///
+    /// The number of seconds `key` is now throttled for, `0` if it may try again immediately.
+    pub fn record_failure(&self, key: &str) -> u64 {
+        self.record_failure_locked(&mut self.lock(), key, self.clock.now())
+    }
+
+    /// Forgets the failures of `key` after a successful attempt.
+    pub fn record_success(&self, key: &str) {
+        self.lock().remove(key);
+    }
+
+    /// Runs a verification for `key` unless it is throttled, counting its outcome.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The account the attempt is made for.
+    /// * `verify` - The verification, returning `Some` if the OTP was accepted and `None` if it was rejected.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the result of `verify`, `RateLimited` without calling it if the account is
+    /// throttled, or the error of `verify`, which is not counted as a failure.
+    pub fn attempt<T, F>(&self, key: &str, verify: F) -> OtpResult<Option<T>>
+    where
+        F: FnOnce() -> OtpResult<Option<T>>,
+    {
+        let mut attempts = self.lock();
+        let now = self.clock.now();
+        self.check_locked(&mut attempts, key, now)?;
+        let verified = verify()?;
+        match verified {
+            Some(_) => {
+                attempts.remove(key);
+            }
+            None => {
+                self.record_failure_locked(&mut attempts, key, now);
+            }
+        }
+        Ok(verified)
+    }
+
+    /// Verifies an HOTP for `key` through [`HOTP::verify`] unless it is throttled.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the matching counter, `None` if the OTP was rejected, or `RateLimited`.
+    pub fn verify_hotp(&self, key: &str, hotp: &HOTP, otp: &str, counter: u64, retries: u64) -> OtpResult<Option<u64>> {
+        self.attempt(key, || hotp.verify(otp, counter, retries))
+    }
+
+    /// Verifies a TOTP for `key` at the time of the throttle's clock through [`TOTP::verify_at`] unless it is
+    /// throttled.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the matching timestamp, `None` if the OTP was rejected, or `RateLimited`.
+    pub fn verify_totp(
+        &self,
+        key: &str,
+        totp: &TOTP,
+        otp: &str,
+        after: Option<u64>,
+        drift_ahead: u64,
+        drift_behind: u64,
+    ) -> OtpResult<Option<u64>> {
+        let at = self.clock.now();
+        self.attempt(key, || totp.verify_at(otp, at, after, drift_ahead, drift_behind))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Attempts>> {
+        self.attempts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn check_locked(&self, attempts: &mut HashMap<String, Attempts>, key: &str, now: u64) -> OtpResult<()> {
+        match attempts.get(key) {
+            Some(attempt) if attempt.blocked_until > now => Err(Box::new(RateLimited {
+                retry_after: attempt.blocked_until - now,
+            })),
+            Some(attempt) if attempt.failures == 0 => {
+                attempts.remove(key);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn record_failure_locked(&self, attempts: &mut HashMap<String, Attempts>, key: &str, now: u64) -> u64 {
+        // Idle accounts are dropped before the map would grow, which keeps the cost of the scan amortized.
+        if attempts.len() >= attempts.capacity() && !attempts.contains_key(key) {
+            let idle = self.config.lockout.max(self.config.max_backoff);
+            attempts.retain(|_, attempt| attempt.blocked_until.saturating_add(idle) > now);
+        }
+        let attempt = attempts.entry(key.to_string()).or_default();
+        attempt.failures += 1;
+        let delay = self.config.delay(attempt.failures);
+        if self.config.max_failures > 0 && attempt.failures >= self.config.max_failures {
+            attempt.failures = 0;
+        }
+        attempt.blocked_until = now.saturating_add(delay);
+        delay
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{AlgorithmTrait, Secret};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Source of the shared key of an `HOTP` or `TOTP`, computing the HMAC of a message with it.
+///
+/// `Secret` is the default provider and hands its bytes to `AlgorithmTrait::hash`. Other providers keep the key out of
+/// the application memory and compute the HMAC elsewhere, e.g. `Pkcs11Key` inside a PKCS#11 token (feature `pkcs11`).
+///
+/// # Example
+///
+/// ```
+/// use rusotp::{Algorithm, AlgorithmTrait, KeyProvider, Radix, Secret, HOTP};
+/// use std::num::NonZeroU8;
+///
+/// /// A key kept by another service, identified by its name.
+/// struct RemoteKey(&'static str);
+///
+/// impl KeyProvider for RemoteKey {
+///     fn hmac<A: AlgorithmTrait>(&self, algorithm: &A, message: &[u8]) -> Result<Vec<u8>, String> {
+///         // The remote service would compute the HMAC of `message` with the key named `self.0`.
+///         algorithm.hash(b"12345678901234567890", message)
+///     }
+/// }
+///
+/// let hotp = HOTP::new(Algorithm::SHA1, RemoteKey("user@email.mail"), NonZeroU8::new(6).unwrap(), Radix::default());
+/// assert_eq!(hotp.generate(0).unwrap(), "755224");
+/// ```
+pub trait KeyProvider {
+    /// Computes the HMAC of `message` with the key, using `algorithm`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the HMAC cannot be computed, e.g. because the algorithm is not supported.
+    fn hmac<A: AlgorithmTrait>(&self, algorithm: &A, message: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+impl KeyProvider for Secret {
+    fn hmac<A: AlgorithmTrait>(&self, algorithm: &A, message: &[u8]) -> Result<Vec<u8>, String> {
+        algorithm.hash(&self.0, message)
+    }
+}
@@ -5,6 +5,8 @@
 //
 // See the file LICENSE for details.
 
+pub(crate) mod key_provider;
 pub(crate) mod otp_error;
 pub(crate) mod radix;
 pub(crate) mod secret;
+pub(crate) mod verify_outcome;
@@ -8,12 +8,21 @@
 //! Error types and result alias for OTP (One-Time Password) operations.
 
 use crate::{Algorithm, AlgorithmTrait};
-use std::fmt::{Debug, Display};
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt::{Debug, Display};
 
 /// Trait for all OTP-related errors.
 ///
 /// Implementors must also implement `Display` and `Debug`.
-pub trait OtpError: Display + Debug {}
+pub trait OtpError: Display + Debug {
+    /// Returns the number of seconds to wait before trying again if the error is a [`RateLimited`] rejection.
+    ///
+    /// This lets callers holding a `Box<dyn OtpError>` tell throttled attempts apart from other failures.
+    fn retry_after(&self) -> Option<u64> {
+        None
+    }
+}
 
 /// Result type alias for OTP operations.
 pub type OtpResult<T> = Result<T, Box<dyn OtpError>>;
@@ -25,7 +34,7 @@ pub struct OtpGenericError(pub String);
 impl OtpError for OtpGenericError {}
 
 impl Display for OtpGenericError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
@@ -37,7 +46,7 @@ pub struct DriftBehindError(pub u64, pub u64);
 impl OtpError for DriftBehindError {}
 
 impl Display for DriftBehindError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} must be less than `at` ({})", self.0, self.1)
     }
 }
@@ -49,7 +58,7 @@ pub struct AfterError(pub u64, pub u64);
 impl OtpError for AfterError {}
 
 impl Display for AfterError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} must be less than or equal to `at` ({})", self.0, self.1)
     }
 }
@@ -61,7 +70,7 @@ pub struct UnsupportedLengthError(pub u8);
 impl OtpError for UnsupportedLengthError {}
 
 impl Display for UnsupportedLengthError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} must be 6", self.0)
     }
 }
@@ -73,7 +82,7 @@ pub struct UnsupportedIntervalError(pub u64);
 impl OtpError for UnsupportedIntervalError {}
 
 impl Display for UnsupportedIntervalError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} must be greater than or equal to 30", self.0)
     }
 }
@@ -85,7 +94,7 @@ pub struct UnsupportedRadixError(pub u8);
 impl OtpError for UnsupportedRadixError {}
 
 impl Display for UnsupportedRadixError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} must be 10", self.0)
     }
 }
@@ -97,7 +106,7 @@ pub struct UnsupportedAlgorithmError(pub Algorithm);
 impl OtpError for UnsupportedAlgorithmError {}
 
 impl Display for UnsupportedAlgorithmError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} must be {}", self.0.to_string(), Algorithm::SHA1.to_string())
     }
 }
@@ -109,7 +118,178 @@ pub struct InvalidSecretError();
 impl OtpError for InvalidSecretError {}
 
 impl Display for InvalidSecretError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Invalid secret")
     }
 }
+
+/// Error invalid PIN.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidPinError(pub String);
+
+impl OtpError for InvalidPinError {}
+
+impl Display for InvalidPinError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Invalid PIN: {}", self.0)
+    }
+}
+
+/// Error raised while encoding or rendering a QR code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrCodeError(pub String);
+
+impl OtpError for QrCodeError {}
+
+impl Display for QrCodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "QR code error: {}", self.0)
+    }
+}
+
+/// Error for an `otpauth://` or `otpauth-migration://` URI that cannot be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidUriError(pub String);
+
+impl OtpError for InvalidUriError {}
+
+impl Display for InvalidUriError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Invalid URI: {}", self.0)
+    }
+}
+
+/// Error raised while reading, writing or editing an encrypted vault.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultError(pub String);
+
+impl OtpError for VaultError {}
+
+impl Display for VaultError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Vault error: {}", self.0)
+    }
+}
+
+/// Error raised while reading or writing the backup file of an authenticator app.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupError(pub String);
+
+impl OtpError for BackupError {}
+
+impl Display for BackupError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Backup error: {}", self.0)
+    }
+}
+
+/// Error raised by a PKCS#11 module or token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pkcs11Error(pub String);
+
+impl OtpError for Pkcs11Error {}
+
+impl Display for Pkcs11Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "PKCS#11 error: {}", self.0)
+    }
+}
+
+/// Error raised while reading a PSKC (RFC 6030) key container.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PskcError(pub String);
+
+impl OtpError for PskcError {}
+
+impl Display for PskcError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "PSKC error: {}", self.0)
+    }
+}
+
+/// Error raised while decoding or decrypting a Yubico OTP.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YubicoError(pub String);
+
+impl OtpError for YubicoError {}
+
+impl Display for YubicoError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Yubico OTP error: {}", self.0)
+    }
+}
+
+/// Error raised while generating, parsing or verifying S/KEY OTPs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SKeyError(pub String);
+
+impl OtpError for SKeyError {}
+
+impl Display for SKeyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "S/KEY error: {}", self.0)
+    }
+}
+
+/// Error raised by a store of counters, e.g. an `AsyncOtpStore`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreError(pub String);
+
+impl OtpError for StoreError {}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Store error: {}", self.0)
+    }
+}
+
+/// Error returned when a verified OTP could not be recorded because another verification recorded its counter (or a
+/// later one) first, i.e. the OTP was presented twice concurrently.
+///
+/// # Fields
+///
+/// * `0` - The counter or time step of the OTP.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayedOtpError(pub u64);
+
+impl OtpError for ReplayedOtpError {}
+
+impl Display for ReplayedOtpError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "OTP of counter {} was already recorded by another verification", self.0)
+    }
+}
+
+/// Error raised while generating, storing or verifying recovery codes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveryCodeError(pub String);
+
+impl OtpError for RecoveryCodeError {}
+
+impl Display for RecoveryCodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Recovery code error: {}", self.0)
+    }
+}
+
+/// Error returned instead of verifying an OTP while the attempts for an account are throttled.
+///
+/// # Fields
+///
+/// * `retry_after` - Number of seconds until the next attempt is accepted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimited {
+    pub retry_after: u64,
+}
+
+impl OtpError for RateLimited {
+    fn retry_after(&self) -> Option<u64> {
+        Some(self.retry_after)
+    }
+}
+
+impl Display for RateLimited {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Too many failed attempts, retry in {} seconds", self.retry_after)
+    }
+}
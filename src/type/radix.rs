@@ -9,8 +9,8 @@
 #[derive(Debug, Clone, PartialEq)]
 pub struct RadixError(pub u8);
 
-impl std::fmt::Display for RadixError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for RadixError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} must be between 2 and 36", self.0)
     }
 }
@@ -5,12 +5,19 @@
 //
 // See the file LICENSE for details.
 
+use crate::{InvalidSecretError, OtpResult};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "uri")]
+use base32ct::{Base32, Encoding};
+
 /// Error type for secret-related operations.
 #[derive(Debug, Clone, PartialEq)]
 pub struct SecretError;
 
-impl std::fmt::Display for SecretError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SecretError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "must not be empty")
     }
 }
@@ -39,6 +46,94 @@ impl Secret {
         Self(secret)
     }
 
+    /// Creates a new `Secret` from its Base32 (RFC 4648) representation, as shown by authenticator apps.
+    ///
+    /// Letter case, whitespace, dashes and missing padding are tolerated.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSecretError` if the input is empty or not valid Base32.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::Secret;
+    ///
+    /// let secret = Secret::new_from_base32("GEZD GNBV GY3T QOJQ GEZD GNBV GY3T QOJQ").unwrap();
+    /// assert_eq!(secret, Secret::new_from_str("12345678901234567890").unwrap());
+    /// ```
+    #[cfg(feature = "uri")]
+    pub fn new_from_base32(secret: &str) -> OtpResult<Self> {
+        let mut normalized: String = secret
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-' && *c != '=')
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        // Lengths of 1, 3 or 6 characters past a full block encode no whole byte and cannot be padded.
+        if !normalized
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || (b'2'..=b'7').contains(&b))
+            || matches!(normalized.len() % 8, 1 | 3 | 6)
+        {
+            return Err(Box::new(InvalidSecretError()));
+        }
+        while normalized.len() % 8 != 0 {
+            normalized.push('=');
+        }
+        match Base32::decode_vec(&normalized) {
+            Ok(bytes) if !bytes.is_empty() => Ok(Self(bytes)),
+            _ => Err(Box::new(InvalidSecretError())),
+        }
+    }
+
+    /// Creates a new `Secret` from its hexadecimal representation.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSecretError` if the input is empty, has an odd length or contains non hex digits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::Secret;
+    ///
+    /// let secret = Secret::new_from_hex("3132333435363738393031323334353637383930").unwrap();
+    /// assert_eq!(secret, Secret::new_from_str("12345678901234567890").unwrap());
+    /// ```
+    pub fn new_from_hex(secret: &str) -> OtpResult<Self> {
+        let digits: Vec<u8> = secret.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+        if digits.is_empty() || digits.len() % 2 != 0 {
+            return Err(Box::new(InvalidSecretError()));
+        }
+        digits
+            .chunks(2)
+            .map(|pair| {
+                let high = (pair[0] as char).to_digit(16)?;
+                let low = (pair[1] as char).to_digit(16)?;
+                Some((high * 16 + low) as u8)
+            })
+            .collect::<Option<Vec<u8>>>()
+            .map(Self)
+            .ok_or_else(|| Box::new(InvalidSecretError()) as _)
+    }
+
+    /// Returns the secret as an upper-case, unpadded Base32 string, as expected by authenticator apps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusotp::Secret;
+    ///
+    /// let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    /// assert_eq!(secret.to_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    /// ```
+    #[cfg(feature = "uri")]
+    pub fn to_base32(&self) -> String {
+        Base32::encode_string(&self.0)
+            .trim_end_matches('=')
+            .to_ascii_uppercase()
+    }
+
     /// Consumes the `Secret` and returns the underlying byte vector.
     pub fn get(self) -> Vec<u8> {
         self.0
@@ -54,3 +149,6 @@ impl Secret {
         String::from_utf8(self.0).unwrap()
     }
 }
+
+#[cfg(test)]
+mod secret_test;
@@ -0,0 +1,31 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "uri")]
+
+use crate::Secret;
+
+#[test]
+fn new_from_base32_should_reject_lengths_without_whole_bytes() {
+    for secret in ["A", "ABC", "ABCDEF", "GEZDGNBVG"] {
+        assert!(Secret::new_from_base32(secret).is_err(), "{}", secret);
+    }
+}
+
+#[test]
+fn new_from_base32_should_reject_characters_outside_the_alphabet() {
+    for secret in ["!!!", "GEZDGNB1", "GEZDGNB8", "GEZDGNBÉ"] {
+        assert!(Secret::new_from_base32(secret).is_err(), "{}", secret);
+    }
+}
+
+#[test]
+fn new_from_base32_should_accept_every_valid_length() {
+    for (secret, length) in [("GE", 1), ("GEZA", 2), ("GEZDG", 3), ("GEZDGNA", 4), ("GEZDGNBV", 5)] {
+        assert_eq!(Secret::new_from_base32(secret).unwrap().0.len(), length, "{}", secret);
+    }
+}
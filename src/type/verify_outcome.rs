@@ -0,0 +1,46 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+/// The result of a successful `HOTP::verify_window` or `TOTP::verify_window_at`.
+///
+/// # Fields
+///
+/// * `counter` - The counter (HOTP) or time step (TOTP) which produced the OTP.
+/// * `drift` - The number of counters or time steps between `counter` and the expected one, negative when the
+///   authenticator is behind.
+/// * `next` - The counter or time step to expect at the earliest in the next verification, so that the OTP cannot be
+///   used again. It is always greater than `counter`: an OTP of counter `u64::MAX` is never accepted.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::{Secret, VerifyOutcome, HOTP};
+///
+/// let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+/// let outcome = hotp.verify_window("359152", 0, 5).unwrap();
+/// assert_eq!(outcome, Some(VerifyOutcome { counter: 2, drift: 2, next: 3 }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VerifyOutcome {
+    pub counter: u64,
+    pub drift: i64,
+    pub next: u64,
+}
+
+impl VerifyOutcome {
+    /// The last counter or time step which can be accepted, as it leaves a `next` one.
+    pub(crate) const LAST: u64 = u64::MAX - 1;
+
+    /// Creates the outcome of `counter`, at most [`VerifyOutcome::LAST`], matching when `expected` was expected.
+    pub(crate) fn new(counter: u64, expected: u64) -> Self {
+        VerifyOutcome {
+            counter,
+            drift: counter.wrapping_sub(expected) as i64,
+            next: counter + 1,
+        }
+    }
+}
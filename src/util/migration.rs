@@ -0,0 +1,161 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! Decoder for the Google Authenticator `otpauth-migration://offline?data=...` export payload.
+//!
+//! The `data` parameter is a base64 encoded protobuf `MigrationPayload` message:
+//!
+//! ```text
+//! message MigrationPayload {
+//!   repeated OtpParameters otp_parameters = 1;
+//!   ...
+//! }
+//!
+//! message OtpParameters {
+//!   bytes secret = 1;
+//!   string name = 2;
+//!   string issuer = 3;
+//!   Algorithm algorithm = 4; // 0 unspecified, 1 SHA1, 2 SHA256, 3 SHA512, 4 MD5
+//!   DigitCount digits = 5;   // 0 unspecified, 1 six, 2 eight
+//!   OtpType type = 6;        // 0 unspecified, 1 HOTP, 2 TOTP
+//!   int64 counter = 7;
+//! }
+//! ```
+//!
+//! Only the fields above are read, everything else is skipped.
+
+use crate::{InvalidUriError, OtpError, OtpResult};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use base64ct::{Base64, Encoding};
+
+/// A single account entry of a migration payload, with the raw protobuf enum values.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct MigrationEntry {
+    pub(crate) secret: Vec<u8>,
+    pub(crate) name: String,
+    pub(crate) issuer: String,
+    pub(crate) algorithm: u64,
+    pub(crate) digits: u64,
+    pub(crate) kind: u64,
+    pub(crate) counter: u64,
+}
+
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.buf.get(self.pos)?;
+            self.pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len)?;
+        let bytes = self.buf.get(self.pos..end)?;
+        self.pos = end;
+        Some(bytes)
+    }
+
+    /// Reads the next field, returning `None` at the end of the buffer and `Some(Err)` on malformed input.
+    fn field(&mut self) -> Option<Result<(u64, Value<'a>), ()>> {
+        if self.pos >= self.buf.len() {
+            return None;
+        }
+        Some(self.read_field().ok_or(()))
+    }
+
+    fn read_field(&mut self) -> Option<(u64, Value<'a>)> {
+        let key = self.varint()?;
+        let value = match key & 0x07 {
+            0 => Value::Varint(self.varint()?),
+            1 => Value::Bytes(self.take(8)?),
+            2 => {
+                let len = usize::try_from(self.varint()?).ok()?;
+                Value::Bytes(self.take(len)?)
+            }
+            5 => Value::Bytes(self.take(4)?),
+            _ => return None,
+        };
+        Some((key >> 3, value))
+    }
+}
+
+fn malformed() -> Box<dyn OtpError> {
+    Box::new(InvalidUriError("malformed migration payload".to_string()))
+}
+
+fn decode_entry(buf: &[u8]) -> OtpResult<MigrationEntry> {
+    let mut entry = MigrationEntry::default();
+    let mut reader = Reader::new(buf);
+
+    while let Some(field) = reader.field() {
+        match field.map_err(|_| malformed())? {
+            (1, Value::Bytes(bytes)) => entry.secret = bytes.to_vec(),
+            (2, Value::Bytes(bytes)) => entry.name = String::from_utf8_lossy(bytes).into_owned(),
+            (3, Value::Bytes(bytes)) => entry.issuer = String::from_utf8_lossy(bytes).into_owned(),
+            (4, Value::Varint(value)) => entry.algorithm = value,
+            (5, Value::Varint(value)) => entry.digits = value,
+            (6, Value::Varint(value)) => entry.kind = value,
+            (7, Value::Varint(value)) => entry.counter = value,
+            _ => {}
+        }
+    }
+
+    Ok(entry)
+}
+
+/// Decodes the entries of an `otpauth-migration://offline?data=...` URI.
+pub(crate) fn decode_migration_uri(uri: &str) -> OtpResult<Vec<MigrationEntry>> {
+    let data = uri
+        .split_once('?')
+        .map(|(_, query)| query)
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| *key == "data")
+        .map(|(_, value)| value)
+        .ok_or_else(|| Box::new(InvalidUriError("missing `data` parameter".to_string())) as Box<dyn OtpError>)?;
+
+    let data = urlencoding::decode(data).map_err(|e| Box::new(InvalidUriError(e.to_string())) as Box<dyn OtpError>)?;
+    let payload =
+        Base64::decode_vec(data.trim()).map_err(|e| Box::new(InvalidUriError(e.to_string())) as Box<dyn OtpError>)?;
+
+    let mut entries = Vec::new();
+    let mut reader = Reader::new(&payload);
+
+    while let Some(field) = reader.field() {
+        if let (1, Value::Bytes(bytes)) = field.map_err(|_| malformed())? {
+            entries.push(decode_entry(bytes)?);
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod migration_test;
@@ -0,0 +1,101 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use super::*;
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn bytes_field(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    varint(out, field << 3 | 2);
+    varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn varint_field(out: &mut Vec<u8>, field: u64, value: u64) {
+    varint(out, field << 3);
+    varint(out, value);
+}
+
+fn entry(secret: &[u8], name: &str, issuer: &str, algorithm: u64, digits: u64, kind: u64, counter: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    bytes_field(&mut out, 1, secret);
+    bytes_field(&mut out, 2, name.as_bytes());
+    bytes_field(&mut out, 3, issuer.as_bytes());
+    varint_field(&mut out, 4, algorithm);
+    varint_field(&mut out, 5, digits);
+    varint_field(&mut out, 6, kind);
+    varint_field(&mut out, 7, counter);
+    out
+}
+
+fn uri(entries: &[Vec<u8>]) -> String {
+    let mut payload = Vec::new();
+    for entry in entries {
+        bytes_field(&mut payload, 1, entry);
+    }
+    varint_field(&mut payload, 2, 1);
+    varint_field(&mut payload, 3, 1);
+    format!("otpauth-migration://offline?data={}", urlencoding::encode(&Base64::encode_string(&payload)))
+}
+
+#[test]
+fn decode_migration_uri_should_read_all_entries() {
+    let uri = uri(&[
+        entry(b"12345678901234567890", "user@email.mail", "rusotp", 1, 1, 2, 0),
+        entry(b"1234", "rusotp:counter", "rusotp", 2, 2, 1, 300),
+    ]);
+
+    let entries = decode_migration_uri(&uri).unwrap();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(
+        entries[0],
+        MigrationEntry {
+            secret: b"12345678901234567890".to_vec(),
+            name: "user@email.mail".to_string(),
+            issuer: "rusotp".to_string(),
+            algorithm: 1,
+            digits: 1,
+            kind: 2,
+            counter: 0,
+        }
+    );
+    assert_eq!(entries[1].secret, b"1234".to_vec());
+    assert_eq!(entries[1].name, "rusotp:counter");
+    assert_eq!(entries[1].algorithm, 2);
+    assert_eq!(entries[1].digits, 2);
+    assert_eq!(entries[1].kind, 1);
+    assert_eq!(entries[1].counter, 300);
+}
+
+#[test]
+fn decode_migration_uri_should_fail_without_data() {
+    let result = decode_migration_uri("otpauth-migration://offline?version=1");
+    assert_eq!(result.err().unwrap().to_string(), "Invalid URI: missing `data` parameter");
+}
+
+#[test]
+fn decode_migration_uri_should_fail_with_truncated_payload() {
+    let mut payload = Vec::new();
+    bytes_field(&mut payload, 1, &entry(b"1234", "name", "issuer", 1, 1, 2, 0));
+    payload.truncate(payload.len() - 3);
+    let uri = format!("otpauth-migration://offline?data={}", Base64::encode_string(&payload));
+
+    let result = decode_migration_uri(&uri);
+    assert_eq!(result.err().unwrap().to_string(), "Invalid URI: malformed migration payload");
+}
+
+#[test]
+fn decode_migration_uri_should_fail_with_invalid_base64() {
+    assert!(decode_migration_uri("otpauth-migration://offline?data=%%%").is_err());
+}
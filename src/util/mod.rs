@@ -1 +1,8 @@
-pub(crate) mod qr_code;
\ No newline at end of file
+#[cfg(feature = "uri")]
+pub(crate) mod migration;
+#[cfg(feature = "qr")]
+pub(crate) mod qr_code;
+#[cfg(feature = "qr-decode")]
+pub(crate) mod qr_decode;
+#[cfg(feature = "std")]
+pub(crate) mod time;
@@ -1,13 +1,284 @@
-use qrcode::QrCode;
-use image::Luma;
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
 
+use crate::{OtpResult, QrCodeError};
+#[cfg(feature = "qr-image")]
+use image::{ImageFormat, Rgb};
+use qrcode::render::{svg, unicode};
+use qrcode::{EcLevel, QrCode};
+#[cfg(feature = "qr-image")]
+use std::io::Cursor;
+use std::num::NonZeroU32;
+#[cfg(feature = "qr-image")]
+use std::path::Path;
+
+/// QR code error correction level, higher levels survive more damage at the cost of a denser code.
+///
+/// # Variants
+///
+/// * `Low` - Recovers up to 7% of the code.
+/// * `Medium` - Recovers up to 15% of the code.
+/// * `Quartile` - Recovers up to 25% of the code.
+/// * `High` - Recovers up to 30% of the code.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum QrErrorCorrection {
+    Low,
+    #[default]
+    Medium,
+    Quartile,
+    High,
+}
+
+impl QrErrorCorrection {
+    fn ec_level(self) -> EcLevel {
+        match self {
+            QrErrorCorrection::Low => EcLevel::L,
+            QrErrorCorrection::Medium => EcLevel::M,
+            QrErrorCorrection::Quartile => EcLevel::Q,
+            QrErrorCorrection::High => EcLevel::H,
+        }
+    }
+}
+
+/// RGB colour used for the dark and light modules of a rendered QR code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QrColor(pub u8, pub u8, pub u8);
+
+impl QrColor {
+    pub const BLACK: QrColor = QrColor(0, 0, 0);
+    pub const WHITE: QrColor = QrColor(255, 255, 255);
+
+    /// Returns the colour in `#rrggbb` notation.
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Renders data (usually a provisioning URI) as a QR code.
+///
+/// Every renderer returns an `OtpResult`, failing with `QrCodeError` when the data does not fit in a QR code
+/// or the output can not be written.
+///
+/// # Example
+///
+/// ```
+/// use std::num::NonZeroU32;
+/// use rusotp::{QrColor, QrErrorCorrection, QrRenderer};
+///
+/// let renderer = QrRenderer::new()
+///     .with_error_correction(QrErrorCorrection::High)
+///     .with_module_size(NonZeroU32::new(4).unwrap())
+///     .with_colors(QrColor(0, 0, 128), QrColor::WHITE);
+///
+/// let svg = renderer.svg("otpauth://totp/rusotp?secret=gezdgna").unwrap();
+/// assert!(svg.contains("#000080"));
+///
+/// let terminal = renderer.terminal("otpauth://totp/rusotp?secret=gezdgna").unwrap();
+/// println!("{}", terminal);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct QrRenderer {
+    error_correction: QrErrorCorrection,
+    module_size: NonZeroU32,
+    quiet_zone: bool,
+    dark: QrColor,
+    light: QrColor,
+}
+
+impl Default for QrRenderer {
+    fn default() -> Self {
+        Self {
+            error_correction: QrErrorCorrection::default(),
+            module_size: NonZeroU32::new(8).unwrap(),
+            quiet_zone: true,
+            dark: QrColor::BLACK,
+            light: QrColor::WHITE,
+        }
+    }
+}
+
+impl QrRenderer {
+    /// Creates a renderer with medium error correction, 8 pixel modules, a quiet zone and black on white colours.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the error correction level.
+    pub fn with_error_correction(mut self, error_correction: QrErrorCorrection) -> Self {
+        self.error_correction = error_correction;
+        self
+    }
+
+    /// Sets the size of a single module in pixels, used by the SVG and PNG renderers.
+    pub fn with_module_size(mut self, module_size: NonZeroU32) -> Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Enables or disables the 4 module wide quiet zone around the code.
+    pub fn with_quiet_zone(mut self, quiet_zone: bool) -> Self {
+        self.quiet_zone = quiet_zone;
+        self
+    }
+
+    /// Sets the dark and light module colours, used by the SVG and PNG renderers.
+    pub fn with_colors(mut self, dark: QrColor, light: QrColor) -> Self {
+        self.dark = dark;
+        self.light = light;
+        self
+    }
+
+    /// Renders the data as an SVG document.
+    pub fn svg(&self, data: &str) -> OtpResult<String> {
+        let dark = self.dark.to_hex();
+        let light = self.light.to_hex();
+
+        Ok(self
+            .encode(data)?
+            .render::<svg::Color>()
+            .quiet_zone(self.quiet_zone)
+            .module_dimensions(self.module_size.get(), self.module_size.get())
+            .dark_color(svg::Color(dark.as_str()))
+            .light_color(svg::Color(light.as_str()))
+            .build())
+    }
+
+    /// Renders the data using Unicode half-block characters, two modules per character cell.
+    ///
+    /// Dark modules are drawn as filled blocks, so the output is best read on a light terminal background.
+    pub fn terminal(&self, data: &str) -> OtpResult<String> {
+        Ok(self
+            .encode(data)?
+            .render::<unicode::Dense1x2>()
+            .quiet_zone(self.quiet_zone)
+            .dark_color(unicode::Dense1x2::Dark)
+            .light_color(unicode::Dense1x2::Light)
+            .build())
+    }
+
+    /// Renders the data as an in-memory PNG image, e.g. for an HTTP response body.
+    #[cfg(feature = "qr-image")]
+    pub fn png(&self, data: &str) -> OtpResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.image(data)?
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .map_err(|e| Box::new(QrCodeError(e.to_string())) as _)
+            .map(|_| bytes)
+    }
+
+    /// Renders the data as an image and saves it to `path`, the format is derived from the file extension.
+    #[cfg(feature = "qr-image")]
+    pub fn save<P: AsRef<Path>>(&self, data: &str, path: P) -> OtpResult<()> {
+        self.image(data)?
+            .save(path)
+            .map_err(|e| Box::new(QrCodeError(e.to_string())) as _)
+    }
+
+    #[cfg(feature = "qr-image")]
+    fn image(&self, data: &str) -> OtpResult<image::RgbImage> {
+        Ok(self
+            .encode(data)?
+            .render::<Rgb<u8>>()
+            .quiet_zone(self.quiet_zone)
+            .module_dimensions(self.module_size.get(), self.module_size.get())
+            .dark_color(Rgb([self.dark.0, self.dark.1, self.dark.2]))
+            .light_color(Rgb([self.light.0, self.light.1, self.light.2]))
+            .build())
+    }
+
+    fn encode(&self, data: &str) -> OtpResult<QrCode> {
+        QrCode::with_error_correction_level(data.as_bytes(), self.error_correction.ec_level())
+            .map_err(|e| Box::new(QrCodeError(e.to_string())) as _)
+    }
+}
+
+/// Provisioning URI ready to be rendered as a QR code, returned by `HOTP::provisioning_qr` and
+/// `TOTP::provisioning_qr`.
+///
+/// Rendering uses `QrRenderer::new()` unless another renderer is set with `with_renderer`.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::{QrErrorCorrection, QrRenderer, Secret, TOTP};
+///
+/// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+/// let qr = totp
+///     .provisioning_qr("rusotp", "user@email.mail")
+///     .unwrap()
+///     .with_renderer(QrRenderer::new().with_error_correction(QrErrorCorrection::High));
+///
+/// assert!(qr.uri().starts_with("otpauth://totp/"));
+/// assert!(qr.svg().unwrap().contains("<svg"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProvisioningQr {
+    uri: String,
+    renderer: QrRenderer,
+}
+
+impl ProvisioningQr {
+    /// Creates a QR code for the given provisioning URI.
+    pub fn new(uri: String) -> Self {
+        Self {
+            uri,
+            renderer: QrRenderer::new(),
+        }
+    }
+
+    /// Replaces the renderer used for every output format.
+    pub fn with_renderer(mut self, renderer: QrRenderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Returns the encoded provisioning URI.
+    pub fn uri(&self) -> &str {
+        self.uri.as_str()
+    }
+
+    /// Renders the code as an SVG document, see `QrRenderer::svg`.
+    pub fn svg(&self) -> OtpResult<String> {
+        self.renderer.svg(self.uri())
+    }
+
+    /// Renders the code with Unicode half blocks, see `QrRenderer::terminal`.
+    pub fn terminal(&self) -> OtpResult<String> {
+        self.renderer.terminal(self.uri())
+    }
+
+    /// Renders the code as PNG bytes, see `QrRenderer::png`.
+    #[cfg(feature = "qr-image")]
+    pub fn png(&self) -> OtpResult<Vec<u8>> {
+        self.renderer.png(self.uri())
+    }
+
+    /// Saves the code as an image file, see `QrRenderer::save`.
+    #[cfg(feature = "qr-image")]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> OtpResult<()> {
+        self.renderer.save(self.uri(), path)
+    }
+}
+
+/// Renders `data` as a QR code made of `█` and space characters.
+///
+/// Panics if the data can not be encoded, use `QrRenderer::terminal` for a fallible alternative.
+#[deprecated(note = "use `QrRenderer::terminal` instead")]
 pub fn generate_qr_code_string(data: String) -> String {
     let code = QrCode::new(data.as_bytes());
     code.unwrap().to_debug_str('█', ' ')
 }
 
+/// Renders `data` as a QR code image and saves it to `path`.
+///
+/// Panics if the data can not be encoded or the image can not be saved, use `QrRenderer::save` for a fallible
+/// alternative.
+#[cfg(feature = "qr-image")]
+#[deprecated(note = "use `QrRenderer::save` or `QrRenderer::png` instead")]
 pub fn generate_qr_code_image(data: String, path: String) {
-    let code = QrCode::new(data.as_bytes()).unwrap();
-    let image = code.render::<Luma<u8>>().build();
-    image.save(path).unwrap();
+    QrRenderer::new().save(data.as_str(), path).unwrap();
 }
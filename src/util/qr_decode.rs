@@ -0,0 +1,91 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{OtpAccount, OtpError, OtpResult, QrCodeError};
+use image::DynamicImage;
+use std::path::Path;
+
+/// Reads every `otpauth://` and `otpauth-migration://` QR code in an image file and parses it into accounts.
+///
+/// Any format enabled in the `image` crate is accepted, PNG and JPEG included. QR codes whose payload is not an
+/// `otpauth` URI are ignored.
+///
+/// # Arguments
+///
+/// * `path` - The path of the image file.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the accounts of all QR codes found, or an error if the image cannot be read, no
+/// `otpauth` QR code is found or a payload cannot be parsed.
+///
+/// # Example
+///
+/// ```no_run
+/// use rusotp::{decode_qr_file, OtpKind};
+///
+/// for account in decode_qr_file("screenshot.png").unwrap() {
+///     if let OtpKind::TOTP(totp) = account.otp {
+///         println!("{}:{} {}", account.issuer, account.name, totp.generate().unwrap());
+///     }
+/// }
+/// ```
+pub fn decode_qr_file<P: AsRef<Path>>(path: P) -> OtpResult<Vec<OtpAccount>> {
+    decode(image::open(path).map_err(|e| Box::new(QrCodeError(e.to_string())) as Box<dyn OtpError>)?)
+}
+
+/// Reads every `otpauth://` and `otpauth-migration://` QR code in an encoded image and parses it into accounts.
+///
+/// # Arguments
+///
+/// * `bytes` - The encoded image, e.g. the content of an uploaded PNG or JPEG screenshot.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the accounts of all QR codes found, or an error if the image cannot be decoded, no
+/// `otpauth` QR code is found or a payload cannot be parsed.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::{decode_qr_bytes, Secret, TOTP};
+///
+/// let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+/// let png = totp.provisioning_qr("rusotp", "user@email.mail").unwrap().png().unwrap();
+///
+/// let accounts = decode_qr_bytes(&png).unwrap();
+/// assert_eq!(accounts[0].name, "user@email.mail");
+/// ```
+pub fn decode_qr_bytes(bytes: &[u8]) -> OtpResult<Vec<OtpAccount>> {
+    decode(image::load_from_memory(bytes).map_err(|e| Box::new(QrCodeError(e.to_string())) as Box<dyn OtpError>)?)
+}
+
+fn decode(image: DynamicImage) -> OtpResult<Vec<OtpAccount>> {
+    let luma = image.to_luma8();
+    let mut prepared =
+        rqrr::PreparedImage::prepare_from_greyscale(luma.width() as usize, luma.height() as usize, |x, y| {
+            luma.get_pixel(x as u32, y as u32).0[0]
+        });
+
+    let payloads: Vec<String> = prepared
+        .detect_grids()
+        .iter()
+        .filter_map(|grid| grid.decode().ok())
+        .map(|(_, content)| content)
+        .filter(|content| content.starts_with("otpauth://") || content.starts_with("otpauth-migration://"))
+        .collect();
+
+    if payloads.is_empty() {
+        return Err(Box::new(QrCodeError("no otpauth QR code found".to_string())));
+    }
+
+    let mut accounts = Vec::new();
+    for payload in payloads {
+        accounts.extend(OtpAccount::parse(&payload)?);
+    }
+    Ok(accounts)
+}
@@ -0,0 +1,32 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+/// Returns the current Unix timestamp in seconds.
+///
+/// `std::time` is unavailable on `wasm32-unknown-unknown`, so the JavaScript clock is used there instead.
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub(crate) fn now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+/// Returns the current Unix timestamp in seconds.
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+pub(crate) fn now() -> u64 {
+    std::time::UNIX_EPOCH.elapsed().unwrap().as_secs()
+}
+
+/// Returns the current Unix timestamp in milliseconds.
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub(crate) fn now_millis() -> u128 {
+    js_sys::Date::now() as u128
+}
+
+/// Returns the current Unix timestamp in milliseconds.
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+pub(crate) fn now_millis() -> u128 {
+    std::time::UNIX_EPOCH.elapsed().unwrap().as_millis()
+}
@@ -0,0 +1,87 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{OtpResult, VaultError};
+use argon2::{Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+pub(crate) const SALT_LENGTH: usize = 16;
+pub(crate) const KEY_LENGTH: usize = 32;
+pub(crate) const NONCE_LENGTH: usize = 24;
+
+/// Upper bounds of the Argon2id parameters, so that a crafted vault file cannot make opening it exhaust memory or time.
+pub(crate) const MAX_MEMORY_KIB: u32 = 4 * 1024 * 1024;
+pub(crate) const MAX_ITERATIONS: u32 = 64;
+pub(crate) const MAX_PARALLELISM: u32 = 16;
+
+/// Argon2id parameters used to derive the vault key from the password.
+///
+/// # Fields
+///
+/// * `memory_kib` - Memory cost in KiB.
+/// * `iterations` - Number of passes over the memory.
+/// * `parallelism` - Number of lanes.
+///
+/// The defaults (19 MiB, 2 iterations, 1 lane) follow the OWASP recommendation for Argon2id. Parameters above 4 GiB,
+/// 64 iterations or 16 lanes are rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VaultKdf {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for VaultKdf {
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl VaultKdf {
+    pub(crate) fn derive(&self, password: &str, salt: &[u8]) -> OtpResult<[u8; KEY_LENGTH]> {
+        if self.memory_kib > MAX_MEMORY_KIB || self.iterations > MAX_ITERATIONS || self.parallelism > MAX_PARALLELISM {
+            return Err(Box::new(VaultError("key derivation parameters too large".to_string())));
+        }
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(KEY_LENGTH))
+            .map_err(|e| Box::new(VaultError(e.to_string())) as _)?;
+        let mut key = [0u8; KEY_LENGTH];
+        Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), salt, &mut key)
+            .map_err(|e| Box::new(VaultError(e.to_string())) as _)?;
+        Ok(key)
+    }
+}
+
+pub(crate) fn random_salt() -> [u8; SALT_LENGTH] {
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Encrypts `data`, authenticating `aad` along with it, and returns the random nonce and the ciphertext.
+pub(crate) fn encrypt(key: &[u8; KEY_LENGTH], data: &[u8], aad: &[u8]) -> OtpResult<(Vec<u8>, Vec<u8>)> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = XChaCha20Poly1305::new(key.into())
+        .encrypt(&nonce, Payload { msg: data, aad })
+        .map_err(|_| Box::new(VaultError("encryption failed".to_string())) as _)?;
+    Ok((nonce.to_vec(), ciphertext))
+}
+
+pub(crate) fn decrypt(key: &[u8; KEY_LENGTH], nonce: &[u8], ciphertext: &[u8], aad: &[u8]) -> OtpResult<Vec<u8>> {
+    if nonce.len() != NONCE_LENGTH {
+        return Err(Box::new(VaultError("invalid nonce".to_string())));
+    }
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| Box::new(VaultError("wrong password or corrupted vault".to_string())) as _)
+}
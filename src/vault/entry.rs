@@ -0,0 +1,50 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::OtpAccount;
+
+/// An account stored in a [`Vault`](crate::vault::Vault).
+///
+/// # Fields
+///
+/// * `account` - The issuer, account name and generator; the counter of HOTP accounts is kept in `account.otp`.
+/// * `icon` - An optional icon, e.g. a path, an URL or a `data:` URI.
+/// * `tags` - Free form tags, e.g. to group accounts.
+#[derive(Debug, PartialEq)]
+pub struct VaultEntry {
+    pub account: OtpAccount,
+    pub icon: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl VaultEntry {
+    /// Creates an entry without icon and tags.
+    pub fn new(account: OtpAccount) -> Self {
+        Self {
+            account,
+            icon: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Sets the icon of the entry.
+    pub fn with_icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// Sets the tags of the entry.
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Returns the label identifying the entry in its vault, see [`OtpAccount::label`].
+    pub fn label(&self) -> String {
+        self.account.label()
+    }
+}
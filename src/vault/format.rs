@@ -0,0 +1,228 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! On-disk format of a vault.
+//!
+//! ```text
+//! {
+//!   "format": "rusotp-vault",
+//!   "version": 1,
+//!   "kdf": { "algorithm": "argon2id", "salt": "<base64>", "memory_kib": 19456, "iterations": 2, "parallelism": 1 },
+//!   "cipher": { "algorithm": "xchacha20poly1305", "nonce": "<base64>" },
+//!   "data": "<base64 ciphertext of the payload>"
+//! }
+//! ```
+//!
+//! The payload is a JSON document `{ "entries": [...] }`. The `format` and `version` fields are authenticated as
+//! associated data. Older payload versions are brought up to date by [`migrate`] when a vault is opened and are
+//! written back in the current version on the next save.
+
+use crate::otp::algorithm::{Algorithm, AlgorithmTrait};
+use crate::vault::{VaultEntry, VaultKdf};
+use crate::{OtpAccount, OtpError, OtpKind, OtpResult, Radix, Secret, VaultError, HOTP, TOTP};
+use base64ct::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::num::{NonZeroU64, NonZeroU8};
+
+pub(crate) const FORMAT: &str = "rusotp-vault";
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER_ALGORITHM: &str = "xchacha20poly1305";
+
+/// Upgrades the payload of version `index + 1` to version `index + 2`.
+type Migration = fn(Value) -> OtpResult<Value>;
+
+const MIGRATIONS: &[Migration] = &[];
+
+/// The format version written by this release of the crate.
+pub const VAULT_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Envelope {
+    pub(crate) format: String,
+    pub(crate) version: u32,
+    pub(crate) kdf: KdfSection,
+    pub(crate) cipher: CipherSection,
+    pub(crate) data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct KdfSection {
+    pub(crate) algorithm: String,
+    pub(crate) salt: String,
+    pub(crate) memory_kib: u32,
+    pub(crate) iterations: u32,
+    pub(crate) parallelism: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CipherSection {
+    pub(crate) algorithm: String,
+    pub(crate) nonce: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PayloadDocument {
+    entries: Vec<StoredEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    issuer: String,
+    name: String,
+    secret: String,
+    algorithm: String,
+    digits: u8,
+    radix: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    period: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    counter: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+pub(crate) fn error(message: &str) -> Box<dyn OtpError> {
+    Box::new(VaultError(message.to_string()))
+}
+
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    Base64::encode_string(bytes)
+}
+
+pub(crate) fn decode(text: &str) -> OtpResult<Vec<u8>> {
+    Base64::decode_vec(text).map_err(|_| error("invalid base64 field"))
+}
+
+/// The associated data authenticated with the payload.
+pub(crate) fn aad(version: u32) -> Vec<u8> {
+    format!("{}:{}", FORMAT, version).into_bytes()
+}
+
+impl Envelope {
+    pub(crate) fn new(kdf: &VaultKdf, salt: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Self {
+        Self {
+            format: FORMAT.to_string(),
+            version: VAULT_VERSION,
+            kdf: KdfSection {
+                algorithm: KDF_ALGORITHM.to_string(),
+                salt: encode(salt),
+                memory_kib: kdf.memory_kib,
+                iterations: kdf.iterations,
+                parallelism: kdf.parallelism,
+            },
+            cipher: CipherSection {
+                algorithm: CIPHER_ALGORITHM.to_string(),
+                nonce: encode(nonce),
+            },
+            data: encode(ciphertext),
+        }
+    }
+
+    /// Checks the header of a parsed envelope.
+    pub(crate) fn validate(&self) -> OtpResult<()> {
+        if self.format != FORMAT {
+            Err(error("not a rusotp vault"))
+        } else if self.version == 0 || self.version > VAULT_VERSION {
+            Err(Box::new(VaultError(format!("unsupported format version {}", self.version))))
+        } else if self.kdf.algorithm != KDF_ALGORITHM || self.cipher.algorithm != CIPHER_ALGORITHM {
+            Err(error("unsupported key derivation or cipher"))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn kdf(&self) -> VaultKdf {
+        VaultKdf {
+            memory_kib: self.kdf.memory_kib,
+            iterations: self.kdf.iterations,
+            parallelism: self.kdf.parallelism,
+        }
+    }
+}
+
+/// Upgrades a payload of format `version` to [`VAULT_VERSION`].
+pub(crate) fn migrate(payload: Value, version: u32) -> OtpResult<Value> {
+    apply_migrations(payload, version, MIGRATIONS)
+}
+
+/// Runs the migrations of `migrations` from format `version` onwards.
+fn apply_migrations(mut payload: Value, version: u32, migrations: &[Migration]) -> OtpResult<Value> {
+    for migration in &migrations[(version as usize - 1)..] {
+        payload = migration(payload)?;
+    }
+    Ok(payload)
+}
+
+pub(crate) fn serialize_entries(entries: &[VaultEntry]) -> OtpResult<Vec<u8>> {
+    let document = PayloadDocument {
+        entries: entries.iter().map(StoredEntry::from).collect(),
+    };
+    serde_json::to_vec(&document).map_err(|e| Box::new(VaultError(e.to_string())) as _)
+}
+
+pub(crate) fn deserialize_entries(payload: &[u8], version: u32) -> OtpResult<Vec<VaultEntry>> {
+    let payload =
+        serde_json::from_slice(payload).map_err(|e| Box::new(VaultError(e.to_string())) as Box<dyn OtpError>)?;
+    let document: PayloadDocument = serde_json::from_value(migrate(payload, version)?)
+        .map_err(|e| Box::new(VaultError(e.to_string())) as Box<dyn OtpError>)?;
+    document.entries.into_iter().map(StoredEntry::into_entry).collect()
+}
+
+impl From<&VaultEntry> for StoredEntry {
+    fn from(entry: &VaultEntry) -> Self {
+        let otp = entry.account.otp.parameters();
+        Self {
+            kind: otp.kind.to_string(),
+            issuer: entry.account.issuer.clone(),
+            name: entry.account.name.clone(),
+            secret: otp.secret.to_base32(),
+            algorithm: otp.algorithm.to_string(),
+            digits: otp.length.get(),
+            radix: otp.radix.get(),
+            period: otp.period,
+            counter: otp.counter,
+            icon: entry.icon.clone(),
+            tags: entry.tags.clone(),
+        }
+    }
+}
+
+impl StoredEntry {
+    fn into_entry(self) -> OtpResult<VaultEntry> {
+        let algorithm = Algorithm::from_string(self.algorithm).ok_or_else(|| error("invalid algorithm"))?;
+        let secret = Secret::new_from_base32(&self.secret)?;
+        let length = NonZeroU8::new(self.digits).ok_or_else(|| error("invalid digits"))?;
+        let radix = Radix::new(self.radix).map_err(|_| error("invalid radix"))?;
+
+        let otp = match self.kind.as_str() {
+            "hotp" => OtpKind::HOTP(HOTP::new(algorithm, secret, length, radix), self.counter.unwrap_or_default()),
+            "totp" => {
+                let interval = NonZeroU64::new(self.period.unwrap_or(30)).ok_or_else(|| error("invalid period"))?;
+                OtpKind::TOTP(TOTP::new(algorithm, secret, length, radix, interval))
+            }
+            _ => return Err(error("invalid entry type")),
+        };
+
+        Ok(VaultEntry {
+            account: OtpAccount {
+                issuer: self.issuer,
+                name: self.name,
+                otp,
+            },
+            icon: self.icon,
+            tags: self.tags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod format_test;
@@ -0,0 +1,139 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use super::*;
+
+fn envelope() -> Envelope {
+    Envelope::new(&VaultKdf::default(), &[0u8; 16], &[0u8; 24], b"ciphertext")
+}
+
+fn entries() -> Vec<VaultEntry> {
+    let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    vec![
+        VaultEntry::new(OtpAccount {
+            issuer: "rusotp".to_string(),
+            name: "counter".to_string(),
+            otp: OtpKind::HOTP(HOTP::default(secret.clone()), 42),
+        })
+        .with_icon("rusotp.png"),
+        VaultEntry::new(OtpAccount {
+            issuer: String::new(),
+            name: "user@email.mail".to_string(),
+            otp: OtpKind::TOTP(TOTP::new(
+                Algorithm::SHA256,
+                secret,
+                NonZeroU8::new(8).unwrap(),
+                Radix::new(10).unwrap(),
+                NonZeroU64::new(60).unwrap(),
+            )),
+        })
+        .with_tags(vec!["work".to_string(), "mail".to_string()]),
+    ]
+}
+
+#[test]
+fn validate_should_accept_current_version() {
+    assert!(envelope().validate().is_ok());
+}
+
+#[test]
+fn validate_should_reject_future_version() {
+    let mut envelope = envelope();
+    envelope.version = VAULT_VERSION + 1;
+    assert_eq!(
+        envelope.validate().unwrap_err().to_string(),
+        format!("Vault error: unsupported format version {}", VAULT_VERSION + 1)
+    );
+}
+
+#[test]
+fn validate_should_reject_version_zero() {
+    let mut envelope = envelope();
+    envelope.version = 0;
+    assert!(envelope.validate().is_err());
+}
+
+#[test]
+fn validate_should_reject_other_format() {
+    let mut envelope = envelope();
+    envelope.format = "aegis".to_string();
+    assert_eq!(envelope.validate().unwrap_err().to_string(), "Vault error: not a rusotp vault");
+}
+
+#[test]
+fn kdf_should_return_stored_parameters() {
+    assert_eq!(envelope().kdf(), VaultKdf::default());
+}
+
+#[test]
+fn migrate_should_keep_current_version_unchanged() {
+    let payload = serde_json::json!({ "entries": [] });
+    assert_eq!(migrate(payload.clone(), VAULT_VERSION).unwrap(), payload);
+}
+
+// Version 1 stores a `label` instead of `issuer` and `name`; version 2 lacks the `tags` of version 3.
+fn split_label(mut payload: Value) -> OtpResult<Value> {
+    for entry in payload["entries"]
+        .as_array_mut()
+        .ok_or_else(|| error("invalid payload"))?
+    {
+        let label = entry["label"].take();
+        let (issuer, name) = label
+            .as_str()
+            .and_then(|label| label.split_once(':'))
+            .unwrap_or_default();
+        entry["issuer"] = Value::from(issuer);
+        entry["name"] = Value::from(name);
+        entry.as_object_mut().unwrap().remove("label");
+    }
+    Ok(payload)
+}
+
+fn add_tags(mut payload: Value) -> OtpResult<Value> {
+    for entry in payload["entries"]
+        .as_array_mut()
+        .ok_or_else(|| error("invalid payload"))?
+    {
+        entry["tags"] = Value::Array(Vec::new());
+    }
+    Ok(payload)
+}
+
+#[test]
+fn migrations_should_upgrade_older_versions_in_order() {
+    let migrations: &[Migration] = &[split_label, add_tags];
+    let v1 = serde_json::json!({ "entries": [{ "label": "rusotp:user", "secret": "GEZDGNBV" }] });
+    let v3 =
+        serde_json::json!({ "entries": [{ "issuer": "rusotp", "name": "user", "secret": "GEZDGNBV", "tags": [] }] });
+
+    assert_eq!(apply_migrations(v1, 1, migrations).unwrap(), v3);
+    assert_eq!(apply_migrations(v3.clone(), 3, migrations).unwrap(), v3);
+}
+
+#[test]
+fn migrations_should_fail_on_invalid_payload() {
+    let migrations: &[Migration] = &[split_label, add_tags];
+    let result = apply_migrations(serde_json::json!({ "entries": {} }), 1, migrations);
+    assert_eq!(result.unwrap_err().to_string(), "Vault error: invalid payload");
+}
+
+#[test]
+fn aad_should_include_format_and_version() {
+    assert_eq!(aad(1), b"rusotp-vault:1".to_vec());
+}
+
+#[test]
+fn entries_should_round_trip() {
+    let payload = serialize_entries(&entries()).unwrap();
+    assert_eq!(deserialize_entries(&payload, VAULT_VERSION).unwrap(), entries());
+}
+
+#[test]
+fn deserialize_should_reject_unknown_type() {
+    let payload = br#"{"entries":[{"type":"motp","issuer":"","name":"a","secret":"GEZDGNBV","algorithm":"SHA1","digits":6,"radix":10,"tags":[]}]}"#;
+    assert_eq!(deserialize_entries(payload, VAULT_VERSION).unwrap_err().to_string(), "Vault error: invalid entry type");
+}
@@ -0,0 +1,38 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! Encrypted storage of OTP accounts, enabled with the `vault` feature.
+//!
+//! A vault is a single JSON file holding a collection of labeled `HOTP`/`TOTP` entries. The entries are encrypted
+//! with XChaCha20-Poly1305 under a key derived from a password with Argon2id; the key derivation parameters, salt
+//! and nonce are stored in clear next to the ciphertext, together with the format version of the file.
+//!
+//! ```no_run
+//! use rusotp::vault::{Vault, VaultEntry};
+//! use rusotp::{OtpAccount, OtpKind, Secret, TOTP};
+//!
+//! let mut vault = Vault::create("accounts.vault", "correct horse battery staple").unwrap();
+//! let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+//! let account = OtpAccount { issuer: "rusotp".to_string(), name: "user@email.mail".to_string(), otp: OtpKind::TOTP(totp) };
+//! vault.add(VaultEntry::new(account).with_tags(vec!["work".to_string()])).unwrap();
+//! vault.save().unwrap();
+//!
+//! let vault = Vault::open("accounts.vault", "correct horse battery staple").unwrap();
+//! for entry in vault.entries() {
+//!     println!("{}", entry.label());
+//! }
+//! ```
+
+mod crypto;
+mod entry;
+mod format;
+mod store;
+
+pub use crypto::VaultKdf;
+pub use entry::VaultEntry;
+pub use format::VAULT_VERSION;
+pub use store::Vault;
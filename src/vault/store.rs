@@ -0,0 +1,248 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::vault::crypto::{decrypt, encrypt, random_salt, KEY_LENGTH, SALT_LENGTH};
+use crate::vault::format::{aad, decode, deserialize_entries, error, serialize_entries, Envelope, VAULT_VERSION};
+use crate::vault::{VaultEntry, VaultKdf};
+use crate::{OtpError, OtpResult, VaultError};
+use std::fmt::{Debug, Formatter};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroize;
+
+/// A password protected collection of OTP accounts stored in a single file.
+///
+/// Changes are kept in memory until [`Vault::save`] is called, which atomically replaces the file. The derived key is
+/// wiped from memory when the vault is dropped.
+pub struct Vault {
+    path: PathBuf,
+    kdf: VaultKdf,
+    salt: [u8; SALT_LENGTH],
+    key: [u8; KEY_LENGTH],
+    entries: Vec<VaultEntry>,
+}
+
+impl Debug for Vault {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Vault")
+            .field("path", &self.path)
+            .field("kdf", &self.kdf)
+            .field("entries", &self.entries.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Vault {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> Box<dyn OtpError> {
+    Box::new(VaultError(format!("{}: {}", path.display(), error)))
+}
+
+impl Vault {
+    /// Creates a new, empty vault file protected by `password`, using the default key derivation parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the vault file; it must not exist yet.
+    /// * `password` - The password protecting the vault.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the opened `Vault`, or an error if the file exists or cannot be written.
+    pub fn create<P: AsRef<Path>>(path: P, password: &str) -> OtpResult<Vault> {
+        Self::create_with_kdf(path, password, VaultKdf::default())
+    }
+
+    /// Creates a new, empty vault file protected by `password`, using the given key derivation parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the vault file; it must not exist yet.
+    /// * `password` - The password protecting the vault.
+    /// * `kdf` - The Argon2id parameters, stored in the file and used for every later save.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the opened `Vault`, or an error if the file exists, cannot be written or the
+    /// parameters are rejected by Argon2.
+    pub fn create_with_kdf<P: AsRef<Path>>(path: P, password: &str, kdf: VaultKdf) -> OtpResult<Vault> {
+        let path = path.as_ref();
+        if path.exists() {
+            return Err(Box::new(VaultError(format!("{} already exists", path.display()))));
+        }
+
+        let salt = random_salt();
+        let vault = Vault {
+            path: path.to_path_buf(),
+            kdf,
+            salt,
+            key: kdf.derive(password, &salt)?,
+            entries: Vec::new(),
+        };
+        vault.save()?;
+        Ok(vault)
+    }
+
+    /// Opens and decrypts an existing vault file, migrating older format versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the vault file.
+    /// * `password` - The password protecting the vault.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the opened `Vault`, or an error if the file cannot be read, the password is wrong,
+    /// the file was tampered with or its format version is newer than [`VAULT_VERSION`].
+    pub fn open<P: AsRef<Path>>(path: P, password: &str) -> OtpResult<Vault> {
+        let path = path.as_ref();
+        let content = fs::read(path).map_err(|e| io_error(path, e))?;
+        let envelope: Envelope = serde_json::from_slice(&content).map_err(|_| error("not a rusotp vault"))?;
+        envelope.validate()?;
+
+        let kdf = envelope.kdf();
+        let salt: [u8; SALT_LENGTH] = decode(&envelope.kdf.salt)?
+            .try_into()
+            .map_err(|_| error("invalid salt"))?;
+        let key = kdf.derive(password, &salt)?;
+        let payload =
+            decrypt(&key, &decode(&envelope.cipher.nonce)?, &decode(&envelope.data)?, &aad(envelope.version))?;
+
+        Ok(Vault {
+            path: path.to_path_buf(),
+            kdf,
+            salt,
+            key,
+            entries: deserialize_entries(&payload, envelope.version)?,
+        })
+    }
+
+    /// Returns the path of the vault file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns all entries, in insertion order.
+    pub fn entries(&self) -> &[VaultEntry] {
+        &self.entries
+    }
+
+    /// Returns the entry with the given label.
+    pub fn get(&self, label: &str) -> Option<&VaultEntry> {
+        self.entries.iter().find(|entry| entry.label() == label)
+    }
+
+    /// Returns the entry with the given label for modification, e.g. to advance an HOTP counter.
+    pub fn get_mut(&mut self, label: &str) -> Option<&mut VaultEntry> {
+        self.entries.iter_mut().find(|entry| entry.label() == label)
+    }
+
+    /// Adds an entry.
+    ///
+    /// # Returns
+    ///
+    /// An error if an entry with the same label already exists.
+    pub fn add(&mut self, entry: VaultEntry) -> OtpResult<()> {
+        if self.get(&entry.label()).is_some() {
+            return Err(Box::new(VaultError(format!("`{}` already exists", entry.label()))));
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Removes the entry with the given label.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the removed entry, or an error if there is no such entry.
+    pub fn remove(&mut self, label: &str) -> OtpResult<VaultEntry> {
+        let index = self.index(label)?;
+        Ok(self.entries.remove(index))
+    }
+
+    /// Changes the issuer and account name of the entry with the given label.
+    ///
+    /// # Returns
+    ///
+    /// An error if there is no such entry or the new label is already taken by another entry.
+    pub fn rename(&mut self, label: &str, issuer: &str, name: &str) -> OtpResult<()> {
+        let index = self.index(label)?;
+        let (old_issuer, old_name) = {
+            let account = &mut self.entries[index].account;
+            let old = (account.issuer.clone(), account.name.clone());
+            account.issuer = issuer.to_string();
+            account.name = name.to_string();
+            old
+        };
+
+        let new_label = self.entries[index].label();
+        if self.entries.iter().filter(|entry| entry.label() == new_label).count() > 1 {
+            let account = &mut self.entries[index].account;
+            account.issuer = old_issuer;
+            account.name = old_name;
+            return Err(Box::new(VaultError(format!("`{}` already exists", new_label))));
+        }
+        Ok(())
+    }
+
+    /// Replaces the password, with a fresh salt. The file is updated on the next [`Vault::save`].
+    pub fn change_password(&mut self, password: &str) -> OtpResult<()> {
+        let salt = random_salt();
+        let mut key = self.kdf.derive(password, &salt)?;
+        self.key.copy_from_slice(&key);
+        key.zeroize();
+        self.salt = salt;
+        Ok(())
+    }
+
+    /// Encrypts the entries with a fresh nonce and atomically replaces the vault file.
+    ///
+    /// The new content is written and synced to a temporary file next to the vault, which is then renamed over it,
+    /// so the vault is never left half written.
+    pub fn save(&self) -> OtpResult<()> {
+        let (nonce, ciphertext) = encrypt(&self.key, &serialize_entries(&self.entries)?, &aad(VAULT_VERSION))?;
+        let envelope = Envelope::new(&self.kdf, &self.salt, &nonce, &ciphertext);
+        let content = serde_json::to_vec_pretty(&envelope).map_err(|e| Box::new(VaultError(e.to_string())) as _)?;
+
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+
+        let mut file = create_private(&temporary).map_err(|e| io_error(&temporary, e))?;
+        file.write_all(&content)
+            .and_then(|_| file.sync_all())
+            .and_then(|_| fs::rename(&temporary, &self.path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temporary);
+                io_error(&self.path, e)
+            })
+    }
+
+    fn index(&self, label: &str) -> OtpResult<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.label() == label)
+            .ok_or_else(|| Box::new(VaultError(format!("`{}` not found", label))) as _)
+    }
+}
+
+/// Creates (or truncates) a file readable and writable by the owner only.
+fn create_private(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
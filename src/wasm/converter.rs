@@ -0,0 +1,35 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{Algorithm, AlgorithmTrait, OtpError, Radix, Secret};
+use std::num::{NonZeroU64, NonZeroU8};
+use wasm_bindgen::JsError;
+
+pub(crate) fn to_js_error(error: Box<dyn OtpError>) -> JsError {
+    JsError::new(error.to_string().as_str())
+}
+
+pub(crate) fn to_algorithm(algorithm: &str) -> Result<Algorithm, JsError> {
+    Algorithm::from_string(algorithm.to_string())
+        .ok_or_else(|| JsError::new(format!("Unsupported algorithm: {}", algorithm).as_str()))
+}
+
+pub(crate) fn to_secret(secret: &str) -> Result<Secret, JsError> {
+    Secret::new_from_str(secret).map_err(|e| JsError::new(format!("Secret {}", e).as_str()))
+}
+
+pub(crate) fn to_length(length: u8) -> Result<NonZeroU8, JsError> {
+    NonZeroU8::new(length).ok_or_else(|| JsError::new("Length must not be zero"))
+}
+
+pub(crate) fn to_radix(radix: u8) -> Result<Radix, JsError> {
+    Radix::new(radix).map_err(|e| JsError::new(format!("Radix {}", e).as_str()))
+}
+
+pub(crate) fn to_interval(interval: u64) -> Result<NonZeroU64, JsError> {
+    NonZeroU64::new(interval).ok_or_else(|| JsError::new("Interval must not be zero"))
+}
@@ -0,0 +1,68 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::wasm::converter::{to_algorithm, to_js_error, to_length, to_radix, to_secret};
+use crate::HOTP;
+use wasm_bindgen::prelude::*;
+
+/// HOTP generator exported to JavaScript as `HOTP`.
+///
+/// # Example
+///
+/// ```js
+/// import { HOTP } from "rusotp";
+///
+/// const hotp = new HOTP("SHA1", "12345678901234567890", 6, 10);
+/// const otp = hotp.generate(1n);
+/// const verified = hotp.verify(otp, 1n, 0n); // 1n
+/// ```
+#[wasm_bindgen(js_name = HOTP)]
+pub struct HotpJs {
+    inner: HOTP,
+}
+
+#[wasm_bindgen(js_class = HOTP)]
+impl HotpJs {
+    /// Creates a new HOTP instance, see [`HOTP::new`].
+    #[wasm_bindgen(constructor)]
+    pub fn new(algorithm: &str, secret: &str, length: u8, radix: u8) -> Result<HotpJs, JsError> {
+        Ok(Self {
+            inner: HOTP::new(to_algorithm(algorithm)?, to_secret(secret)?, to_length(length)?, to_radix(radix)?),
+        })
+    }
+
+    /// Parses an `otpauth://hotp/...` provisioning URI, see [`HOTP::from_uri`].
+    #[wasm_bindgen(js_name = fromUri)]
+    pub fn from_uri(uri: &str) -> Result<HotpJs, JsError> {
+        HOTP::from_uri(uri).map(|inner| Self { inner }).map_err(to_js_error)
+    }
+
+    /// Generates an OTP for the given counter, see [`HOTP::generate`].
+    pub fn generate(&self, counter: u64) -> Result<String, JsError> {
+        self.inner.generate(counter).map_err(to_js_error)
+    }
+
+    /// Verifies an OTP, returning the matched counter or `undefined`, see [`HOTP::verify`].
+    pub fn verify(&self, otp: &str, counter: u64, retries: u64) -> Result<Option<u64>, JsError> {
+        self.inner.verify(otp, counter, retries).map_err(to_js_error)
+    }
+
+    /// Generates a provisioning URI, see [`HOTP::provisioning_uri`].
+    #[wasm_bindgen(js_name = provisioningUri)]
+    pub fn provisioning_uri(&self, issuer: &str, user: &str, counter: u64) -> Result<String, JsError> {
+        self.inner.provisioning_uri(issuer, user, counter).map_err(to_js_error)
+    }
+
+    /// Renders the provisioning URI as an SVG QR code.
+    #[wasm_bindgen(js_name = provisioningQrSvg)]
+    pub fn provisioning_qr_svg(&self, issuer: &str, user: &str, counter: u64) -> Result<String, JsError> {
+        self.inner
+            .provisioning_qr(issuer, user, counter)
+            .and_then(|qr| qr.svg())
+            .map_err(to_js_error)
+    }
+}
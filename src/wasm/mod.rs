@@ -0,0 +1,18 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! JavaScript bindings, enabled with the `wasm` feature.
+//!
+//! Build with `wasm-pack build --target web -- --no-default-features --features wasm` (or `--target nodejs`)
+//! and use the exported `HOTP` and `TOTP` classes from JavaScript.
+
+mod converter;
+mod hotp_js_binds;
+mod totp_js_binds;
+
+pub use hotp_js_binds::*;
+pub use totp_js_binds::*;
@@ -0,0 +1,103 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::wasm::converter::{to_algorithm, to_interval, to_js_error, to_length, to_radix, to_secret};
+use crate::TOTP;
+use wasm_bindgen::prelude::*;
+
+/// TOTP generator exported to JavaScript as `TOTP`.
+///
+/// # Example
+///
+/// ```js
+/// import { TOTP } from "rusotp";
+///
+/// const totp = new TOTP("SHA1", "12345678901234567890", 6, 10, 30n);
+/// const otp = totp.generate();
+/// const verified = totp.verify(otp, undefined, 0n, 0n); // matched timestamp
+/// ```
+#[wasm_bindgen(js_name = TOTP)]
+pub struct TotpJs {
+    inner: TOTP,
+}
+
+#[wasm_bindgen(js_class = TOTP)]
+impl TotpJs {
+    /// Creates a new TOTP instance, see [`TOTP::new`].
+    #[wasm_bindgen(constructor)]
+    pub fn new(algorithm: &str, secret: &str, length: u8, radix: u8, interval: u64) -> Result<TotpJs, JsError> {
+        Ok(Self {
+            inner: TOTP::new(
+                to_algorithm(algorithm)?,
+                to_secret(secret)?,
+                to_length(length)?,
+                to_radix(radix)?,
+                to_interval(interval)?,
+            ),
+        })
+    }
+
+    /// Parses an `otpauth://totp/...` provisioning URI, see [`TOTP::from_uri`].
+    #[wasm_bindgen(js_name = fromUri)]
+    pub fn from_uri(uri: &str) -> Result<TotpJs, JsError> {
+        TOTP::from_uri(uri).map(|inner| Self { inner }).map_err(to_js_error)
+    }
+
+    /// Generates an OTP for the current time, see [`TOTP::generate`].
+    pub fn generate(&self) -> Result<String, JsError> {
+        self.inner.generate().map_err(to_js_error)
+    }
+
+    /// Generates an OTP for the given timestamp, see [`TOTP::generate_at`].
+    #[wasm_bindgen(js_name = generateAt)]
+    pub fn generate_at(&self, timestamp: u64) -> Result<String, JsError> {
+        self.inner.generate_at(timestamp).map_err(to_js_error)
+    }
+
+    /// Verifies an OTP against the current time, see [`TOTP::verify`].
+    pub fn verify(
+        &self,
+        otp: &str,
+        after: Option<u64>,
+        drift_ahead: u64,
+        drift_behind: u64,
+    ) -> Result<Option<u64>, JsError> {
+        self.inner
+            .verify(otp, after, drift_ahead, drift_behind)
+            .map_err(to_js_error)
+    }
+
+    /// Verifies an OTP against the given timestamp, see [`TOTP::verify_at`].
+    #[wasm_bindgen(js_name = verifyAt)]
+    pub fn verify_at(
+        &self,
+        otp: &str,
+        at: u64,
+        after: Option<u64>,
+        drift_ahead: u64,
+        drift_behind: u64,
+    ) -> Result<Option<u64>, JsError> {
+        self.inner
+            .verify_at(otp, at, after, drift_ahead, drift_behind)
+            .map_err(to_js_error)
+    }
+
+    /// Generates a provisioning URI, see [`TOTP::provisioning_uri`].
+    #[wasm_bindgen(js_name = provisioningUri)]
+    pub fn provisioning_uri(&self, issuer: &str, user: &str) -> Result<String, JsError> {
+        self.inner.provisioning_uri(issuer, user).map_err(to_js_error)
+    }
+
+    /// Renders the provisioning URI as an SVG QR code.
+    #[wasm_bindgen(js_name = provisioningQrSvg)]
+    pub fn provisioning_qr_svg(&self, issuer: &str, user: &str) -> Result<String, JsError> {
+        self.inner
+            .provisioning_qr(issuer, user)
+            .and_then(|qr| qr.svg())
+            .map_err(to_js_error)
+    }
+}
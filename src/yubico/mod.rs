@@ -0,0 +1,193 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! Offline validation of Yubico OTPs, enabled with the `yubico` feature.
+//!
+//! In Yubico OTP mode a YubiKey types its modhex public ID followed by a 32 characters modhex token: 16 bytes
+//! encrypted with AES-128 holding the private ID, the usage and session counters, a timestamp, random bits and a
+//! CRC. A [`YubicoKey`] holds the AES key and private ID shared with a YubiKey and decrypts its OTPs; a
+//! [`YubicoValidator`] additionally accepts each OTP once, by requiring its counter to be greater than the last
+//! accepted one in a [`YubicoCounterStore`].
+//!
+//! ```
+//! use rusotp::yubico::{MemoryYubicoCounterStore, YubicoKey, YubicoValidator};
+//!
+//! let key = YubicoKey::new("ctcucvbcbbbd", "8792ebfe26cc", "ecde18dbe76fbd0c33330f1c354871db").unwrap();
+//! let validator = YubicoValidator::new(MemoryYubicoCounterStore::new());
+//!
+//! let otp = "ctcucvbcbbbdjrrjlijkbttcfbtbggdbinghlitkvulu";
+//! let token = validator.verify(&key, otp).unwrap().unwrap();
+//! assert_eq!(token.counter.usage, 19);
+//! assert!(validator.verify(&key, otp).unwrap().is_none());
+//! ```
+
+mod modhex;
+mod store;
+mod token;
+
+pub use modhex::{modhex_decode, modhex_encode};
+pub use store::{MemoryYubicoCounterStore, YubicoCounterStore};
+pub use token::{YubicoCounter, YubicoToken};
+
+use crate::{OtpResult, Secret, YubicoError};
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, KeyInit};
+use aes::Aes128;
+use std::fmt::{Debug, Formatter};
+
+/// The length, in modhex characters, of the encrypted token ending an OTP.
+const TOKEN_LENGTH: usize = 32;
+
+/// The longest public ID, in modhex characters.
+const MAX_PUBLIC_ID_LENGTH: usize = 32;
+
+/// Returns the public ID of a Yubico OTP, identifying the YubiKey which typed it, e.g. to look up its key.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the lowercase modhex public ID, empty if the YubiKey has none, or a `YubicoError` if
+/// the OTP is not a modhex string of 32 to 64 characters.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::yubico::public_id;
+///
+/// assert_eq!(public_id("ctcucvbcbbbdjrrjlijkbttcfbtbggdbinghlitkvulu").unwrap(), "ctcucvbcbbbd");
+/// ```
+pub fn public_id(otp: &str) -> OtpResult<String> {
+    let (public_id, _) = split(otp)?;
+    Ok(public_id.to_ascii_lowercase())
+}
+
+/// The AES key and private ID shared with a YubiKey, decrypting the OTPs of its public ID.
+#[derive(Clone)]
+pub struct YubicoKey {
+    public_id: String,
+    private_id: [u8; 6],
+    cipher: Aes128,
+}
+
+impl Debug for YubicoKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("YubicoKey")
+            .field("public_id", &self.public_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl YubicoKey {
+    /// Creates the key of a YubiKey from the values written by the personalization tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_id` - The modhex public ID, up to 32 characters.
+    /// * `private_id` - The private ID in hexadecimal, 6 bytes.
+    /// * `aes_key` - The AES-128 key in hexadecimal, 16 bytes.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the key, or a `YubicoError` if a value is malformed or has the wrong length.
+    pub fn new(public_id: &str, private_id: &str, aes_key: &str) -> OtpResult<Self> {
+        if public_id.len() > MAX_PUBLIC_ID_LENGTH {
+            return Err(error("public ID is longer than 32 characters"));
+        }
+        modhex_decode(public_id)?;
+        let private_id: [u8; 6] = Secret::new_from_hex(private_id)
+            .ok()
+            .and_then(|secret| secret.get().try_into().ok())
+            .ok_or_else(|| error("private ID must be 6 bytes in hexadecimal"))?;
+        let aes_key = Secret::new_from_hex(aes_key)
+            .ok()
+            .filter(|secret| secret.0.len() == 16)
+            .ok_or_else(|| error("AES key must be 16 bytes in hexadecimal"))?;
+
+        Ok(Self {
+            public_id: public_id.to_ascii_lowercase(),
+            private_id,
+            cipher: Aes128::new(GenericArray::from_slice(&aes_key.0)),
+        })
+    }
+
+    /// Returns the lowercase modhex public ID of the YubiKey.
+    pub fn public_id(&self) -> &str {
+        &self.public_id
+    }
+
+    /// Decrypts an OTP, without checking that it was not used before.
+    ///
+    /// # Arguments
+    ///
+    /// * `otp` - The OTP typed by the YubiKey, in either letter case.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the token, `None` if the OTP has another public ID, a wrong CRC (it was encrypted
+    /// with another key) or another private ID, or a `YubicoError` if it is not a modhex string of 32 to 64
+    /// characters.
+    pub fn decrypt(&self, otp: &str) -> OtpResult<Option<YubicoToken>> {
+        let (public_id, encrypted) = split(otp)?;
+        if !public_id.eq_ignore_ascii_case(&self.public_id) {
+            return Ok(None);
+        }
+        let mut block = GenericArray::clone_from_slice(&modhex_decode(encrypted)?);
+        self.cipher.decrypt_block(&mut block);
+
+        Ok(YubicoToken::parse(public_id, &block.into()).filter(|token| token.private_id == self.private_id))
+    }
+}
+
+/// Verifies Yubico OTPs and accepts each of them once, backed by a [`YubicoCounterStore`].
+#[derive(Debug)]
+pub struct YubicoValidator<S: YubicoCounterStore> {
+    store: S,
+}
+
+impl<S: YubicoCounterStore> YubicoValidator<S> {
+    /// Creates a validator recording the accepted counters in `store`.
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Returns the store of the counters.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Verifies an OTP with `key` and records its counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the YubiKey, usually looked up with the [`public_id`] of the OTP.
+    /// * `otp` - The OTP typed by the YubiKey.
+    ///
+    /// # Returns
+    ///
+    /// An `OtpResult` containing the token if the OTP is valid and newer than the last accepted one, `None` if it is
+    /// invalid or was already used (its counter is not greater than the last accepted one), or a `YubicoError` if it
+    /// is malformed, or the error of the store.
+    pub fn verify(&self, key: &YubicoKey, otp: &str) -> OtpResult<Option<YubicoToken>> {
+        match key.decrypt(otp)? {
+            Some(token) if self.store.advance(&token.public_id, token.counter)? => Ok(Some(token)),
+            _ => Ok(None),
+        }
+    }
+}
+
+fn split(otp: &str) -> OtpResult<(&str, &str)> {
+    let otp = otp.trim();
+    if !(TOKEN_LENGTH..=TOKEN_LENGTH + MAX_PUBLIC_ID_LENGTH).contains(&otp.len()) || !otp.is_ascii() {
+        return Err(error("OTP must be 32 to 64 modhex characters"));
+    }
+    let (public_id, encrypted) = otp.split_at(otp.len() - TOKEN_LENGTH);
+    modhex_decode(public_id)?;
+    Ok((public_id, encrypted))
+}
+
+fn error(message: &str) -> Box<dyn crate::OtpError> {
+    Box::new(YubicoError(message.to_string()))
+}
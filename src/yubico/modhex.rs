@@ -0,0 +1,68 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::{OtpResult, YubicoError};
+
+/// The modhex digits, standing for `0` to `f`; YubiKeys type them as keys that have the same position on most
+/// keyboard layouts.
+const ALPHABET: &[u8; 16] = b"cbdefghijklnrtuv";
+
+/// Decodes a modhex string, ignoring letter case.
+///
+/// # Arguments
+///
+/// * `modhex` - The modhex string, of even length.
+///
+/// # Returns
+///
+/// An `OtpResult` containing the decoded bytes, or a `YubicoError` if the string has an odd length or a character
+/// outside of the modhex alphabet.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::yubico::modhex_decode;
+///
+/// assert_eq!(modhex_decode("ctcucvbcbbbd").unwrap(), vec![0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12]);
+/// ```
+pub fn modhex_decode(modhex: &str) -> OtpResult<Vec<u8>> {
+    if modhex.len() % 2 != 0 {
+        return Err(Box::new(YubicoError("modhex string has an odd length".to_string())));
+    }
+    let digit = |c: u8| {
+        ALPHABET
+            .iter()
+            .position(|&d| d == c.to_ascii_lowercase())
+            .map(|value| value as u8)
+            .ok_or_else(|| Box::new(YubicoError(format!("invalid modhex character `{}`", c as char))) as _)
+    };
+    modhex
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| Ok(digit(pair[0])? << 4 | digit(pair[1])?))
+        .collect()
+}
+
+/// Encodes bytes as a lowercase modhex string.
+///
+/// # Example
+///
+/// ```
+/// use rusotp::yubico::modhex_encode;
+///
+/// assert_eq!(modhex_encode(&[0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12]), "ctcucvbcbbbd");
+/// ```
+pub fn modhex_encode(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|byte| [ALPHABET[(byte >> 4) as usize], ALPHABET[(byte & 0xf) as usize]])
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod modhex_test;
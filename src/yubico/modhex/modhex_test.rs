@@ -0,0 +1,42 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::yubico::{modhex_decode, modhex_encode};
+
+#[test]
+fn every_byte_should_round_trip() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    assert_eq!(modhex_decode(&modhex_encode(&bytes)).unwrap(), bytes);
+}
+
+#[test]
+fn digits_should_map_to_nibbles_in_order() {
+    assert_eq!(modhex_encode(&[0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]), "cbdefghijklnrtuv");
+}
+
+#[test]
+fn decode_should_ignore_letter_case() {
+    assert_eq!(modhex_decode("CBDEFG").unwrap(), vec![0x01, 0x23, 0x45]);
+}
+
+#[test]
+fn decode_should_reject_odd_length() {
+    let error = modhex_decode("cbd").unwrap_err();
+    assert_eq!(error.to_string(), "Yubico OTP error: modhex string has an odd length");
+}
+
+#[test]
+fn decode_should_reject_hex_digits() {
+    let error = modhex_decode("cb0a").unwrap_err();
+    assert_eq!(error.to_string(), "Yubico OTP error: invalid modhex character `0`");
+}
+
+#[test]
+fn empty_string_should_decode_to_nothing() {
+    assert!(modhex_decode("").unwrap().is_empty());
+    assert_eq!(modhex_encode(&[]), "");
+}
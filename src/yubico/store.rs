@@ -0,0 +1,60 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use crate::yubico::YubicoCounter;
+use crate::{OtpResult, YubicoError};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Persistence of the last accepted counter of each YubiKey, making OTPs single use.
+///
+/// `advance` must be atomic: when the same OTP is presented twice concurrently, exactly one of them may see `true`.
+/// With a database this is an `UPDATE ... WHERE public_id = ? AND (usage, session) < (?, ?)` (or an insert for the
+/// first OTP) checking the number of affected rows.
+pub trait YubicoCounterStore {
+    /// Returns the last accepted counter of `public_id`, `None` if no OTP of it was accepted yet.
+    fn last(&self, public_id: &str) -> OtpResult<Option<YubicoCounter>>;
+
+    /// Records `counter` for `public_id` if it is greater than the last accepted one, returning `false` otherwise.
+    fn advance(&self, public_id: &str, counter: YubicoCounter) -> OtpResult<bool>;
+}
+
+/// A [`YubicoCounterStore`] keeping the counters in memory, for tests and single process services.
+#[derive(Debug, Default)]
+pub struct MemoryYubicoCounterStore {
+    counters: Mutex<HashMap<String, YubicoCounter>>,
+}
+
+impl MemoryYubicoCounterStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> OtpResult<std::sync::MutexGuard<'_, HashMap<String, YubicoCounter>>> {
+        self.counters
+            .lock()
+            .map_err(|_| Box::new(YubicoError("store lock poisoned".to_string())) as _)
+    }
+}
+
+impl YubicoCounterStore for MemoryYubicoCounterStore {
+    fn last(&self, public_id: &str) -> OtpResult<Option<YubicoCounter>> {
+        Ok(self.lock()?.get(public_id).copied())
+    }
+
+    fn advance(&self, public_id: &str, counter: YubicoCounter) -> OtpResult<bool> {
+        let mut counters = self.lock()?;
+        match counters.get(public_id) {
+            Some(last) if *last >= counter => Ok(false),
+            _ => {
+                counters.insert(public_id.to_string(), counter);
+                Ok(true)
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+/// The CRC-16 (ISO 13239) of a token including its own checksum.
+const CRC_RESIDUE: u16 = 0xf0b8;
+
+/// The bits of the usage counter; the highest one is a flag.
+const USAGE_COUNTER_MASK: u16 = 0x7fff;
+
+/// The position of an OTP in the sequence of a YubiKey, which only moves forward.
+///
+/// Counters are ordered by usage counter, then session counter.
+///
+/// # Fields
+///
+/// * `usage` - The usage counter, incremented at every power-up of the YubiKey (15 bits).
+/// * `session` - The session counter, incremented at every OTP since power-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct YubicoCounter {
+    pub usage: u16,
+    pub session: u8,
+}
+
+/// The decrypted content of a Yubico OTP.
+///
+/// # Fields
+///
+/// * `public_id` - The modhex public ID prefixing the OTP, identifying the YubiKey.
+/// * `private_id` - The secret ID encrypted in the token, 6 bytes.
+/// * `counter` - The usage and session counters.
+/// * `timestamp` - The 8 Hz timer of the YubiKey, started at power-up with a random value (24 bits).
+/// * `random` - Random bits making each token unique.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YubicoToken {
+    pub public_id: String,
+    pub private_id: [u8; 6],
+    pub counter: YubicoCounter,
+    pub timestamp: u32,
+    pub random: u16,
+}
+
+impl YubicoToken {
+    /// Reads a decrypted token, returning `None` if its CRC does not match.
+    pub(crate) fn parse(public_id: &str, plain: &[u8; 16]) -> Option<YubicoToken> {
+        if crc16(plain) != CRC_RESIDUE {
+            return None;
+        }
+        let mut private_id = [0; 6];
+        private_id.copy_from_slice(&plain[..6]);
+        Some(YubicoToken {
+            public_id: public_id.to_ascii_lowercase(),
+            private_id,
+            counter: YubicoCounter {
+                usage: u16::from_le_bytes([plain[6], plain[7]]) & USAGE_COUNTER_MASK,
+                session: plain[11],
+            },
+            timestamp: u32::from_le_bytes([plain[8], plain[9], plain[10], 0]),
+            random: u16::from_le_bytes([plain[12], plain[13]]),
+        })
+    }
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xffff;
+    for &byte in data {
+        crc ^= u16::from(byte);
+        for _ in 0..8 {
+            let carry = crc & 1;
+            crc >>= 1;
+            if carry != 0 {
+                crc ^= 0x8408;
+            }
+        }
+    }
+    crc
+}
@@ -0,0 +1,234 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "async")]
+
+use rusotp::async_verifier::{AsyncOtpStore, AsyncVerifier, MemoryAsyncOtpStore, StoreFuture};
+use rusotp::{OtpResult, ReplayedOtpError, Secret, StoreError, VerifyOutcome, HOTP, TOTP};
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+const USER: &str = "user@email.mail";
+
+/// Runs a future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// A future which is pending once before completing, like a query waiting for the database.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            context.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// A store answering after yielding to the executor, or failing, or loading nothing as if each verification raced
+/// with another one.
+#[derive(Default)]
+struct SlowStore {
+    inner: MemoryAsyncOtpStore,
+    failing: bool,
+    stale: bool,
+}
+
+impl AsyncOtpStore for SlowStore {
+    fn load<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<u64>> {
+        Box::pin(async move {
+            YieldOnce(false).await;
+            if self.failing {
+                return Err(Box::new(StoreError("connection lost".to_string())) as _);
+            }
+            if self.stale {
+                return Ok(None);
+            }
+            self.inner.load(key).await
+        })
+    }
+
+    fn advance<'a>(&'a self, key: &'a str, next: u64) -> StoreFuture<'a, bool> {
+        Box::pin(async move {
+            YieldOnce(false).await;
+            self.inner.advance(key, next).await
+        })
+    }
+}
+
+fn secret() -> Secret {
+    Secret::new_from_str("12345678901234567890").unwrap()
+}
+
+fn assert_send<T: Send>(value: T) -> T {
+    value
+}
+
+#[test]
+fn verify_hotp_should_accept_each_counter_once() {
+    let verifier = AsyncVerifier::new(MemoryAsyncOtpStore::new());
+    let hotp = HOTP::default(secret());
+
+    block_on(async {
+        let outcome = verifier.verify_hotp(USER, &hotp, "287082", 5).await.unwrap();
+        assert_eq!(
+            outcome,
+            Some(VerifyOutcome {
+                counter: 1,
+                drift: 1,
+                next: 2
+            })
+        );
+        assert_eq!(verifier.verify_hotp(USER, &hotp, "287082", 5).await.unwrap(), None);
+        assert_eq!(verifier.verify_hotp(USER, &hotp, "755224", 5).await.unwrap(), None);
+        assert!(verifier.verify_hotp(USER, &hotp, "359152", 0).await.unwrap().is_some());
+        assert_eq!(verifier.store().load(USER).await.unwrap(), Some(3));
+    });
+}
+
+#[test]
+fn verify_hotp_should_not_record_rejected_otps() {
+    let verifier = AsyncVerifier::new(MemoryAsyncOtpStore::new());
+    let hotp = HOTP::default(secret());
+
+    block_on(async {
+        assert_eq!(verifier.verify_hotp(USER, &hotp, "000000", 5).await.unwrap(), None);
+        assert_eq!(verifier.store().load(USER).await.unwrap(), None);
+        assert!(verifier
+            .verify_hotp("other", &hotp, "755224", 0)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(verifier.store().load(USER).await.unwrap(), None);
+    });
+}
+
+#[test]
+fn verify_totp_at_should_track_the_last_used_step() {
+    let verifier = AsyncVerifier::new(MemoryAsyncOtpStore::new());
+    let totp = TOTP::default(secret());
+    let previous = totp.generate_at(60).unwrap();
+    let current = totp.generate_at(90).unwrap();
+
+    block_on(async {
+        let outcome = verifier.verify_totp_at(USER, &totp, &current, 95, 1, 1).await.unwrap();
+        assert_eq!(
+            outcome,
+            Some(VerifyOutcome {
+                counter: 3,
+                drift: 0,
+                next: 4
+            })
+        );
+        assert_eq!(verifier.verify_totp_at(USER, &totp, &current, 100, 1, 1).await.unwrap(), None);
+        assert_eq!(
+            verifier
+                .verify_totp_at(USER, &totp, &previous, 100, 1, 1)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(verifier.store().load(USER).await.unwrap(), Some(4));
+
+        let next = totp.generate_at(120).unwrap();
+        assert!(verifier
+            .verify_totp_at(USER, &totp, &next, 121, 1, 1)
+            .await
+            .unwrap()
+            .is_some());
+    });
+}
+
+#[test]
+fn verify_totp_should_use_the_current_time() {
+    let verifier = AsyncVerifier::new(MemoryAsyncOtpStore::new());
+    let totp = TOTP::default(secret());
+    let otp = totp.generate().unwrap();
+
+    block_on(async {
+        assert!(verifier.verify_totp(USER, &totp, &otp, 1, 0).await.unwrap().is_some());
+        assert_eq!(verifier.verify_totp(USER, &totp, &otp, 1, 0).await.unwrap(), None);
+    });
+}
+
+#[test]
+fn verifier_should_wait_for_the_store() {
+    let verifier = AsyncVerifier::new(SlowStore::default());
+    let hotp = HOTP::default(secret());
+
+    let outcome = block_on(assert_send(verifier.verify_hotp(USER, &hotp, "755224", 0))).unwrap();
+    assert_eq!(outcome.map(|outcome| outcome.next), Some(1));
+    assert_eq!(block_on(verifier.verify_hotp(USER, &hotp, "755224", 0)).unwrap(), None);
+}
+
+#[test]
+fn verifier_should_return_store_errors() {
+    let verifier = AsyncVerifier::new(SlowStore {
+        failing: true,
+        ..Default::default()
+    });
+    let totp = TOTP::default(secret());
+    let otp = totp.generate_at(90).unwrap();
+
+    let result: OtpResult<_> = block_on(verifier.verify_totp_at(USER, &totp, &otp, 90, 0, 0));
+    assert_eq!(result.unwrap_err().to_string(), "Store error: connection lost");
+}
+
+#[test]
+fn verifier_should_report_otps_recorded_concurrently() {
+    let verifier = AsyncVerifier::new(SlowStore {
+        stale: true,
+        ..Default::default()
+    });
+    let hotp = HOTP::default(secret());
+
+    assert!(block_on(verifier.verify_hotp(USER, &hotp, "287082", 5))
+        .unwrap()
+        .is_some());
+    let error = block_on(verifier.verify_hotp(USER, &hotp, "287082", 5)).unwrap_err();
+    assert_eq!(error.to_string(), ReplayedOtpError(1).to_string());
+    assert_eq!(block_on(verifier.verify_hotp(USER, &hotp, "000000", 5)).unwrap(), None);
+}
+
+#[test]
+fn memory_store_should_only_advance() {
+    let store = MemoryAsyncOtpStore::new();
+
+    block_on(async {
+        assert!(store.advance(USER, 5).await.unwrap());
+        assert!(!store.advance(USER, 5).await.unwrap());
+        assert!(!store.advance(USER, 4).await.unwrap());
+        assert!(store.advance(USER, 6).await.unwrap());
+        assert_eq!(store.load(USER).await.unwrap(), Some(6));
+        assert_eq!(store.load("other").await.unwrap(), None);
+    });
+}
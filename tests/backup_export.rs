@@ -0,0 +1,162 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "backup")]
+
+use rusotp::backup::{export_aegis, export_andotp, export_uris, import_aegis, import_andotp, import_uris, BackupEntry};
+use rusotp::{Algorithm, OtpAccount, OtpKind, Radix, Secret, HOTP, TOTP};
+use std::num::{NonZeroU64, NonZeroU8};
+
+const AEGIS_PLAIN: &str = include_str!("fixtures/backup/aegis_plain.json");
+
+fn secret() -> Secret {
+    Secret::new_from_str("12345678901234567890").unwrap()
+}
+
+fn entries() -> Vec<BackupEntry> {
+    let hotp = HOTP::new(Algorithm::SHA256, secret(), NonZeroU8::new(8).unwrap(), Radix::new(10).unwrap());
+    let totp = TOTP::new(
+        Algorithm::SHA512,
+        secret(),
+        NonZeroU8::new(7).unwrap(),
+        Radix::new(10).unwrap(),
+        NonZeroU64::new(60).unwrap(),
+    );
+    vec![
+        BackupEntry {
+            account: OtpAccount {
+                issuer: "rusotp".to_string(),
+                name: "user@email.mail".to_string(),
+                otp: OtpKind::TOTP(TOTP::default(secret())),
+            },
+            tags: vec!["Personal".to_string(), "Mail".to_string()],
+            note: Some("personal account".to_string()),
+        },
+        BackupEntry {
+            account: OtpAccount {
+                issuer: "Example".to_string(),
+                name: "counter".to_string(),
+                otp: OtpKind::HOTP(hotp, 5),
+            },
+            tags: vec!["Personal".to_string()],
+            note: None,
+        },
+        BackupEntry {
+            account: OtpAccount {
+                issuer: String::new(),
+                name: "no issuer".to_string(),
+                otp: OtpKind::TOTP(totp),
+            },
+            tags: Vec::new(),
+            note: None,
+        },
+    ]
+}
+
+fn without_notes(entries: Vec<BackupEntry>) -> Vec<BackupEntry> {
+    entries
+        .into_iter()
+        .map(|entry| BackupEntry { note: None, ..entry })
+        .collect()
+}
+
+fn without_metadata(entries: Vec<BackupEntry>) -> Vec<BackupEntry> {
+    entries
+        .into_iter()
+        .map(|entry| BackupEntry {
+            tags: Vec::new(),
+            note: None,
+            ..entry
+        })
+        .collect()
+}
+
+#[test]
+fn export_aegis_should_round_trip() {
+    let json = export_aegis(&entries()).unwrap();
+    let import = import_aegis(&json, None).unwrap();
+
+    assert!(import.errors.is_empty());
+    assert_eq!(import.entries, entries());
+}
+
+#[test]
+fn export_aegis_should_be_deterministic() {
+    assert_eq!(export_aegis(&entries()).unwrap(), export_aegis(&entries()).unwrap());
+}
+
+#[test]
+fn export_aegis_should_write_groups_once() {
+    let json = export_aegis(&entries()).unwrap();
+    assert_eq!(json.matches("\"name\": \"Personal\"").count(), 1);
+}
+
+#[test]
+fn export_aegis_should_keep_imported_fixture() {
+    let imported = import_aegis(AEGIS_PLAIN, None).unwrap().entries;
+    let import = import_aegis(&export_aegis(&imported).unwrap(), None).unwrap();
+
+    assert_eq!(import.entries, imported);
+}
+
+#[test]
+fn export_andotp_should_round_trip() {
+    let json = export_andotp(&entries()).unwrap();
+    let import = import_andotp(&json).unwrap();
+
+    assert!(import.errors.is_empty());
+    assert_eq!(import.entries, without_notes(entries()));
+}
+
+#[test]
+fn export_uris_should_round_trip() {
+    let text = export_uris(&entries()).unwrap();
+    let import = import_uris(&text);
+
+    assert_eq!(text.lines().count(), 3);
+    assert!(import.errors.is_empty());
+    assert_eq!(import.entries, without_metadata(entries()));
+}
+
+#[test]
+fn import_uris_should_report_invalid_lines() {
+    let import = import_uris("# exported accounts\n\nhttps://example.com\n");
+
+    assert!(import.entries.is_empty());
+    assert_eq!(import.errors.len(), 1);
+    assert_eq!(import.errors[0].index, 2);
+    assert_eq!(import.errors[0].name, "https://…");
+}
+
+#[test]
+fn import_uris_should_not_report_secrets() {
+    let import = import_uris(
+        "otpauth://totp/rusotp%3Auser?secret=GEZDGNBVGY3TQOJQ&period=0\notpauth-migration://offline?data=GEZDGNBVGY3TQOJQ\n",
+    );
+
+    assert_eq!(import.errors.len(), 2);
+    assert_eq!(import.errors[0].name, "rusotp:user");
+    assert_eq!(import.errors[1].name, "otpauth-migration://…");
+}
+
+#[test]
+fn export_should_reject_unsupported_radix() {
+    let hotp = HOTP::new(Algorithm::SHA1, secret(), NonZeroU8::new(6).unwrap(), Radix::new(16).unwrap());
+    let entries = [BackupEntry {
+        account: OtpAccount {
+            issuer: "rusotp".to_string(),
+            name: "hex".to_string(),
+            otp: OtpKind::HOTP(hotp, 0),
+        },
+        tags: Vec::new(),
+        note: None,
+    }];
+
+    assert_eq!(export_aegis(&entries).unwrap_err().to_string(), "16 must be 10");
+    assert_eq!(export_andotp(&entries).unwrap_err().to_string(), "16 must be 10");
+    assert_eq!(export_uris(&entries).unwrap_err().to_string(), "16 must be 10");
+}
@@ -0,0 +1,155 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "backup")]
+
+use rusotp::backup::{import_2fas, import_aegis, import_andotp, import_freeotp_plus, BackupImport};
+use rusotp::{Algorithm, OtpAccount, OtpKind, Radix, Secret, HOTP, TOTP};
+use std::num::NonZeroU8;
+
+const AEGIS_PLAIN: &str = include_str!("fixtures/backup/aegis_plain.json");
+const AEGIS_ENCRYPTED: &str = include_str!("fixtures/backup/aegis_encrypted.json");
+const ANDOTP: &str = include_str!("fixtures/backup/andotp.json");
+const TWOFAS: &str = include_str!("fixtures/backup/twofas.2fas");
+const FREEOTP_PLUS: &str = include_str!("fixtures/backup/freeotp_plus.json");
+
+fn secret() -> Secret {
+    Secret::new_from_str("12345678901234567890").unwrap()
+}
+
+fn totp_account() -> OtpAccount {
+    OtpAccount {
+        issuer: "rusotp".to_string(),
+        name: "user@email.mail".to_string(),
+        otp: OtpKind::TOTP(TOTP::default(secret())),
+    }
+}
+
+fn hotp_account() -> OtpAccount {
+    let hotp = HOTP::new(Algorithm::SHA256, secret(), NonZeroU8::new(8).unwrap(), Radix::new(10).unwrap());
+    OtpAccount {
+        issuer: "Example".to_string(),
+        name: "counter".to_string(),
+        otp: OtpKind::HOTP(hotp, 5),
+    }
+}
+
+fn assert_accounts(import: &BackupImport) {
+    assert_eq!(import.entries.len(), 2);
+    assert_eq!(import.entries[0].account, totp_account());
+    assert_eq!(import.entries[1].account, hotp_account());
+}
+
+#[test]
+fn import_aegis_should_read_plain_export() {
+    let import = import_aegis(AEGIS_PLAIN, None).unwrap();
+
+    assert_accounts(&import);
+    assert_eq!(import.entries[0].tags, vec!["Personal".to_string()]);
+    assert_eq!(import.entries[0].note.as_deref(), Some("personal account"));
+    assert_eq!(import.entries[1].note, None);
+}
+
+#[test]
+fn import_aegis_should_report_unsupported_entries() {
+    let import = import_aegis(AEGIS_PLAIN, None).unwrap();
+
+    assert_eq!(import.errors.len(), 1);
+    assert_eq!(import.errors[0].index, 2);
+    assert_eq!(import.errors[0].name, "gamer");
+    assert_eq!(import.errors[0].error.to_string(), "Backup error: unsupported OTP type `steam`");
+}
+
+#[test]
+fn import_aegis_should_decrypt_encrypted_export() {
+    let import = import_aegis(AEGIS_ENCRYPTED, Some("rusotp")).unwrap();
+
+    assert_accounts(&import);
+    assert_eq!(import.entries[0].tags, vec!["Personal".to_string()]);
+    assert_eq!(import.errors.len(), 1);
+}
+
+#[test]
+fn import_aegis_should_reject_wrong_password() {
+    let result = import_aegis(AEGIS_ENCRYPTED, Some("wrong"));
+    assert_eq!(result.unwrap_err().to_string(), "Backup error: wrong password");
+}
+
+#[test]
+fn import_aegis_should_require_password_for_encrypted_export() {
+    let result = import_aegis(AEGIS_ENCRYPTED, None);
+    assert_eq!(result.unwrap_err().to_string(), "Backup error: the Aegis export is encrypted, a password is required");
+}
+
+#[test]
+fn import_aegis_should_reject_oversized_password_slot() {
+    for (field, value) in [
+        (r#""n": 1024"#, r#""n": 2097152"#),
+        (r#""r": 8"#, r#""r": 64"#),
+        (r#""p": 1"#, r#""p": 32"#),
+    ] {
+        let result = import_aegis(&AEGIS_ENCRYPTED.replace(field, value), Some("rusotp"));
+        assert_eq!(result.unwrap_err().to_string(), "Backup error: invalid password slot");
+    }
+}
+
+#[test]
+fn import_aegis_should_reject_other_files() {
+    assert!(import_aegis(ANDOTP, None).is_err());
+    assert!(import_aegis("not json", None).is_err());
+}
+
+#[test]
+fn import_andotp_should_read_backup() {
+    let import = import_andotp(ANDOTP).unwrap();
+
+    assert_accounts(&import);
+    assert_eq!(import.entries[0].tags, vec!["Personal".to_string()]);
+    assert_eq!(import.errors.len(), 1);
+    assert_eq!(import.errors[0].name, "gamer");
+    assert_eq!(import.errors[0].error.to_string(), "Backup error: unsupported OTP type `STEAM`");
+}
+
+#[test]
+fn import_2fas_should_read_backup() {
+    let import = import_2fas(TWOFAS).unwrap();
+
+    assert_accounts(&import);
+    assert_eq!(import.entries[0].tags, vec!["Personal".to_string()]);
+    assert!(import.entries[1].tags.is_empty());
+    assert_eq!(import.errors.len(), 1);
+    assert_eq!(import.errors[0].index, 2);
+    assert_eq!(import.errors[0].name, "Steam");
+}
+
+#[test]
+fn import_2fas_should_reject_encrypted_backup() {
+    let json = r#"{"services":[],"groups":[],"schemaVersion":4,"servicesEncrypted":"abc:def:ghi"}"#;
+    assert_eq!(import_2fas(json).unwrap_err().to_string(), "Backup error: encrypted 2FAS backups are not supported");
+}
+
+#[test]
+fn import_freeotp_plus_should_read_backup() {
+    let import = import_freeotp_plus(FREEOTP_PLUS).unwrap();
+
+    assert_accounts(&import);
+    assert_eq!(import.errors.len(), 1);
+    assert_eq!(import.errors[0].name, "md5");
+    assert_eq!(import.errors[0].error.to_string(), "Backup error: unsupported algorithm `MD5`");
+}
+
+#[test]
+fn import_should_report_malformed_entries() {
+    let json = r#"[{"issuer":"rusotp","label":"no secret","digits":6,"type":"TOTP","algorithm":"SHA1"},
+        {"secret":"!!!","issuer":"rusotp","label":"bad secret","digits":6,"type":"TOTP","algorithm":"SHA1"}]"#;
+    let import = import_andotp(json).unwrap();
+
+    assert!(import.entries.is_empty());
+    assert_eq!(import.errors.len(), 2);
+    assert_eq!(import.errors[0].name, "no secret");
+    assert_eq!(import.errors[1].error.to_string(), "Invalid secret");
+}
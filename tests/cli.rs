@@ -0,0 +1,182 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+fn rusotp(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rusotp"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap().trim_end().to_string()
+}
+
+#[test]
+fn generate_should_print_hotp() {
+    let output = rusotp(&["generate", "--type", "hotp", "--counter", "2"], SECRET);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "359152");
+}
+
+#[test]
+fn generate_should_print_totp_as_json() {
+    let output = rusotp(&["--json", "generate", "--timestamp", "59", "--length", "8"], SECRET);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), r#"{"otp":"94287082","timestamp":59,"valid_for":1}"#);
+}
+
+#[test]
+fn generate_should_read_hex_secret_from_env() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rusotp"))
+        .args([
+            "generate",
+            "--secret-env",
+            "RUSOTP_TEST_SECRET",
+            "--secret-encoding",
+            "hex",
+            "--algorithm",
+            "sha512",
+            "--timestamp",
+            "59",
+            "--length",
+            "8",
+        ])
+        .env(
+            "RUSOTP_TEST_SECRET",
+            "31323334353637383930313233343536373839303132333435363738393031323334353637383930313233343536373839303132333435363738393031323334",
+        )
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "90693936");
+}
+
+#[test]
+fn verify_should_exit_with_rejected_code() {
+    let valid = rusotp(&["verify", "359152", "--type", "hotp", "--counter", "2"], SECRET);
+    assert_eq!(valid.status.code(), Some(0));
+    assert_eq!(stdout(&valid), "valid");
+
+    let invalid = rusotp(&["--json", "verify", "000000", "--type", "hotp", "--counter", "2"], SECRET);
+    assert_eq!(invalid.status.code(), Some(1));
+    assert_eq!(stdout(&invalid), r#"{"counter":null,"valid":false}"#);
+}
+
+#[test]
+fn verify_should_count_totp_drift_in_time_steps() {
+    // 287082 is the OTP of time step 1, from timestamp 30 to 59.
+    let behind = rusotp(&["--json", "verify", "287082", "--timestamp", "65", "--drift-behind", "1"], SECRET);
+    assert_eq!(behind.status.code(), Some(0));
+    assert_eq!(stdout(&behind), r#"{"timestamp":30,"valid":true}"#);
+
+    let ahead = rusotp(&["verify", "287082", "--timestamp", "29", "--drift-ahead", "1"], SECRET);
+    assert_eq!(ahead.status.code(), Some(0));
+
+    let outside = rusotp(&["verify", "287082", "--timestamp", "95", "--drift-behind", "1"], SECRET);
+    assert_eq!(outside.status.code(), Some(1));
+}
+
+#[test]
+fn verify_should_reject_totp_of_the_after_time_step() {
+    let rejected = rusotp(&["verify", "287082", "--timestamp", "45", "--after", "40"], SECRET);
+    assert_eq!(rejected.status.code(), Some(1));
+
+    let accepted = rusotp(&["verify", "287082", "--timestamp", "45", "--after", "29"], SECRET);
+    assert_eq!(accepted.status.code(), Some(0));
+}
+
+#[test]
+fn uri_should_print_full_provisioning_uri() {
+    let output = rusotp(
+        &[
+            "uri",
+            "--issuer",
+            "rusotp",
+            "--name",
+            "user@email.mail",
+            "--algorithm",
+            "sha256",
+        ],
+        SECRET,
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "otpauth://totp/rusotp%3Auser%40email.mail?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=rusotp&algorithm=SHA256&digits=6&period=30"
+    );
+}
+
+#[test]
+fn qr_should_print_svg() {
+    let output = rusotp(&["qr", "--issuer", "rusotp", "--name", "rusotp", "--format", "svg"], SECRET);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("<svg"));
+}
+
+#[test]
+fn parse_uri_should_print_account_as_json() {
+    let output = rusotp(
+        &[
+            "--json",
+            "parse-uri",
+            "otpauth://hotp/rusotp%3Arusotp?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&counter=2&issuer=rusotp",
+        ],
+        "",
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        r#"[{"algorithm":"SHA1","counter":2,"digits":6,"issuer":"rusotp","name":"rusotp","radix":10,"secret":"GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ","type":"hotp"}]"#
+    );
+}
+
+#[test]
+fn import_and_export_should_round_trip_uri_list() {
+    let list = "# accounts\notpauth://totp/rusotp%3Auser%40email.mail?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=rusotp&algorithm=SHA512&digits=8&period=60\n\n";
+
+    let imported = rusotp(&["import"], list);
+    assert_eq!(imported.status.code(), Some(0));
+
+    let exported = rusotp(&["export", "--format", "uris"], &stdout(&imported));
+    assert_eq!(exported.status.code(), Some(0));
+    assert_eq!(stdout(&exported), list.lines().nth(1).unwrap());
+}
+
+#[test]
+fn errors_should_map_to_exit_codes() {
+    let invalid_secret = rusotp(&["--json", "generate", "--secret-encoding", "hex"], "zz");
+    assert_eq!(invalid_secret.status.code(), Some(3));
+    assert_eq!(
+        String::from_utf8(invalid_secret.stderr).unwrap().trim_end(),
+        r#"{"error":"invalid_input","message":"Invalid secret"}"#
+    );
+
+    let missing_file = rusotp(&["import", "/nonexistent/rusotp/accounts.txt"], "");
+    assert_eq!(missing_file.status.code(), Some(4));
+
+    let invalid_usage = rusotp(&["generate", "--radix", "40"], SECRET);
+    assert_eq!(invalid_usage.status.code(), Some(2));
+}
@@ -0,0 +1,165 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use rusotp::{Algorithm, OtpEvent, OtpEventSource, Radix, Secret, HOTP, TOTP};
+use std::future::Future;
+use std::num::{NonZeroU64, NonZeroU8};
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::{Duration, Instant};
+
+// RFC 4226 (Appendix D) HOTP values of the first counters.
+const HOTP_CODES: [&str; 10] = [
+    "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583", "399871", "520489",
+];
+
+fn secret() -> Secret {
+    Secret::new_from_str("12345678901234567890").unwrap()
+}
+
+/// Runs a future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+fn hotp_codes_should_match_rfc_samples() {
+    let hotp = HOTP::default(secret());
+    let codes: Vec<_> = hotp.codes(0..10).map(Result::unwrap).collect();
+    let expected: Vec<_> = HOTP_CODES
+        .iter()
+        .enumerate()
+        .map(|(counter, otp)| (counter as u64, otp.to_string()))
+        .collect();
+
+    assert_eq!(codes, expected);
+}
+
+#[test]
+fn hotp_codes_should_accept_any_counters() {
+    let hotp = HOTP::default(secret());
+
+    assert_eq!(
+        hotp.codes(8..=9)
+            .map(Result::unwrap)
+            .map(|(counter, _)| counter)
+            .collect::<Vec<_>>(),
+        [8, 9]
+    );
+    assert_eq!(hotp.codes([9, 0]).next().unwrap().unwrap(), (9, HOTP_CODES[9].to_string()));
+    assert_eq!(hotp.codes(5..5).count(), 0);
+}
+
+#[test]
+fn hotp_codes_should_record_generations() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorder = events.clone();
+    let hotp = HOTP::default(secret())
+        .with_event_sink(Arc::new(move |event: &OtpEvent| recorder.lock().unwrap().push(*event)));
+
+    assert_eq!(hotp.codes(3..5).count(), 2);
+    assert_eq!(
+        *events.lock().unwrap(),
+        [3, 4].map(|counter| OtpEvent::Generated {
+            source: OtpEventSource::HOTP,
+            counter
+        })
+    );
+}
+
+#[test]
+fn totp_codes_between_should_cover_every_step() {
+    let totp = TOTP::default(secret());
+    let codes: Vec<_> = totp.codes_between(29, 299).map(Result::unwrap).collect();
+
+    assert_eq!(codes.len(), 10);
+    for (step, otp) in codes {
+        assert_eq!(otp, HOTP_CODES[step as usize]);
+        assert_eq!(otp, totp.generate_at(step * 30).unwrap());
+    }
+}
+
+#[test]
+fn totp_codes_between_should_match_rfc_samples() {
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        secret(),
+        NonZeroU8::new(8).unwrap(),
+        Radix::default(),
+        NonZeroU64::new(30).unwrap(),
+    );
+    let codes: Vec<_> = totp.codes_between(1111111109, 1111111111).map(Result::unwrap).collect();
+
+    assert_eq!(codes, [(37037036, "07081804".to_string()), (37037037, "14050471".to_string())]);
+}
+
+#[test]
+fn totp_codes_between_should_be_empty_if_end_is_before_start() {
+    let totp = TOTP::default(secret());
+
+    assert_eq!(totp.codes_between(90, 59).count(), 0);
+    assert_eq!(totp.codes_between(60, 60).count(), 1);
+}
+
+#[test]
+fn totp_next_code_should_be_the_code_of_the_next_step() {
+    let totp = TOTP::default(secret());
+    let (step, otp) = totp.next_code().unwrap();
+    let current = totp.current_step();
+
+    assert!(step == current + 1 || step == current);
+    assert_eq!(otp, totp.codes_between(step * 30, step * 30).next().unwrap().unwrap().1);
+}
+
+#[test]
+fn totp_watch_should_emit_a_code_at_each_step_boundary() {
+    let totp =
+        TOTP::new(Algorithm::SHA1, secret(), NonZeroU8::new(6).unwrap(), Radix::default(), NonZeroU64::new(1).unwrap());
+    let started = Instant::now();
+    let codes: Vec<_> = totp.watch().take(2).map(Result::unwrap).collect();
+
+    assert_eq!(codes[1].0, codes[0].0 + 1);
+    assert_eq!(codes[1].1, totp.generate_at(codes[1].0).unwrap());
+    assert!(started.elapsed() <= Duration::from_millis(1500));
+}
+
+#[test]
+fn totp_watch_async_should_sleep_until_each_step_boundary() {
+    let totp =
+        TOTP::new(Algorithm::SHA1, secret(), NonZeroU8::new(6).unwrap(), Radix::default(), NonZeroU64::new(1).unwrap());
+    let sleeps = Arc::new(Mutex::new(Vec::new()));
+    let recorder = sleeps.clone();
+    let mut watch = totp.watch_async(move |delay: Duration| {
+        recorder.lock().unwrap().push(delay);
+        async move { std::thread::sleep(delay) }
+    });
+
+    let (first, second) = block_on(async { (watch.next().await.unwrap(), watch.next().await.unwrap()) });
+
+    assert_eq!(second.0, first.0 + 1);
+    assert_eq!(second.1, totp.generate_at(second.0).unwrap());
+    let sleeps = sleeps.lock().unwrap();
+    assert!(!sleeps.is_empty());
+    assert!(sleeps.iter().all(|delay| *delay <= Duration::from_secs(1)));
+}
@@ -0,0 +1,93 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use hmac::{Hmac, Mac};
+use rusotp::{Algorithm, AlgorithmTrait, Radix, Secret, HOTP, TOTP};
+use sha2::Sha256;
+use std::num::{NonZeroU64, NonZeroU8};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// An HMAC-SHA256 computed outside of rusotp, counting its invocations like an HSM session would.
+#[derive(Debug, Clone, Default)]
+struct ExternalHmac {
+    calls: Arc<AtomicUsize>,
+}
+
+impl AlgorithmTrait for ExternalHmac {
+    fn to_string(&self) -> String {
+        "EXTERNAL-SHA256".to_string()
+    }
+
+    fn from_string(name: String) -> Option<Self> {
+        (name == "EXTERNAL-SHA256").then(ExternalHmac::default)
+    }
+
+    fn hash(&self, key: &[u8], message: &[u8]) -> Result<Vec<u8>, String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(|e| e.to_string())?;
+        mac.update(message);
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+}
+
+/// Returns a MAC too short for the dynamic truncation.
+struct Truncated;
+
+impl AlgorithmTrait for Truncated {
+    fn to_string(&self) -> String {
+        "TRUNCATED".to_string()
+    }
+
+    fn from_string(_: String) -> Option<Self> {
+        None
+    }
+
+    fn hash(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>, String> {
+        Ok(vec![0; 16])
+    }
+}
+
+fn secret() -> Secret {
+    Secret::new_from_str("12345678901234567890123456789012").unwrap()
+}
+
+#[test]
+fn hotp_should_use_custom_algorithm() {
+    let algorithm = ExternalHmac::default();
+    let hotp = HOTP::new(algorithm.clone(), secret(), NonZeroU8::new(8).unwrap(), Radix::default());
+    let builtin = HOTP::new(Algorithm::SHA256, secret(), NonZeroU8::new(8).unwrap(), Radix::default());
+
+    let otp = hotp.generate(7).unwrap();
+
+    assert_eq!(otp, builtin.generate(7).unwrap());
+    assert_eq!(hotp.verify(&otp, 5, 2).unwrap(), Some(7));
+    assert_eq!(algorithm.calls.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn totp_should_use_custom_algorithm() {
+    let totp = TOTP::new(
+        ExternalHmac::default(),
+        secret(),
+        NonZeroU8::new(8).unwrap(),
+        Radix::default(),
+        NonZeroU64::new(30).unwrap(),
+    );
+
+    assert_eq!(totp.generate_at(59).unwrap(), "46119246");
+    assert_eq!(totp.verify_at("46119246", 59, None, 0, 0).unwrap(), Some(59));
+}
+
+#[test]
+fn short_mac_should_fail_generation() {
+    let hotp = HOTP::new(Truncated, secret(), NonZeroU8::new(6).unwrap(), Radix::default());
+
+    let error = hotp.generate(0).unwrap_err();
+
+    assert_eq!(error.to_string(), "HMAC of TRUNCATED must be at least 20 bytes long");
+}
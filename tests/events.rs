@@ -0,0 +1,258 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+use rusotp::{OtpEvent, OtpEventSink, OtpEventSource, Secret, HOTP, TOTP};
+use std::sync::{Arc, Mutex};
+
+const SECRET: &str = "12345678901234567890";
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<OtpEvent>>);
+
+impl OtpEventSink for Recorder {
+    fn record(&self, event: &OtpEvent) {
+        self.0.lock().unwrap().push(*event);
+    }
+}
+
+impl Recorder {
+    fn take(&self) -> Vec<OtpEvent> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+fn hotp() -> (HOTP, Arc<Recorder>) {
+    let recorder = Arc::new(Recorder::default());
+    let hotp = HOTP::default(Secret::new_from_str(SECRET).unwrap()).with_event_sink(recorder.clone());
+    (hotp, recorder)
+}
+
+fn totp() -> (TOTP, Arc<Recorder>) {
+    let recorder = Arc::new(Recorder::default());
+    let totp = TOTP::default(Secret::new_from_str(SECRET).unwrap()).with_event_sink(recorder.clone());
+    (totp, recorder)
+}
+
+#[test]
+fn hotp_generate_should_record_counter() {
+    let (hotp, recorder) = hotp();
+
+    hotp.generate(4).unwrap();
+
+    assert_eq!(
+        recorder.take(),
+        vec![OtpEvent::Generated {
+            source: OtpEventSource::HOTP,
+            counter: 4
+        }]
+    );
+}
+
+#[test]
+fn hotp_verify_should_record_matched_counter_and_drift() {
+    let (hotp, recorder) = hotp();
+
+    assert_eq!(hotp.verify("359152", 0, 5).unwrap(), Some(2));
+
+    assert_eq!(
+        recorder.take(),
+        vec![OtpEvent::Verified {
+            source: OtpEventSource::HOTP,
+            counter: 2,
+            drift: 2
+        }]
+    );
+}
+
+#[test]
+fn hotp_verify_should_record_rejection() {
+    let (hotp, recorder) = hotp();
+
+    assert_eq!(hotp.verify("000000", 3, 2).unwrap(), None);
+
+    assert_eq!(
+        recorder.take(),
+        vec![OtpEvent::Rejected {
+            source: OtpEventSource::HOTP,
+            counter: 3
+        }]
+    );
+}
+
+#[test]
+fn hotp_verify_should_record_replay_of_previous_counter() {
+    let (hotp, recorder) = hotp();
+
+    assert_eq!(hotp.verify("287082", 2, 0).unwrap(), None);
+
+    assert_eq!(
+        recorder.take(),
+        vec![OtpEvent::Replayed {
+            source: OtpEventSource::HOTP,
+            counter: 1
+        }]
+    );
+}
+
+#[test]
+fn hotp_resync_should_record_counter_of_second_code() {
+    let (hotp, recorder) = hotp();
+
+    assert_eq!(hotp.resync("399871", "520489", 1, 10).unwrap(), Some(9));
+    assert_eq!(hotp.resync("520489", "399871", 1, 10).unwrap(), None);
+
+    assert_eq!(
+        recorder.take(),
+        vec![
+            OtpEvent::Resynced {
+                source: OtpEventSource::HOTP,
+                counter: 9,
+                drift: 8
+            },
+            OtpEvent::Rejected {
+                source: OtpEventSource::HOTP,
+                counter: 1
+            }
+        ]
+    );
+}
+
+#[test]
+fn totp_generate_at_should_record_time_step() {
+    let (totp, recorder) = totp();
+
+    totp.generate_at(59).unwrap();
+
+    assert_eq!(
+        recorder.take(),
+        vec![OtpEvent::Generated {
+            source: OtpEventSource::TOTP,
+            counter: 1
+        }]
+    );
+}
+
+#[test]
+fn totp_verify_at_should_record_matched_time_step_and_drift() {
+    let (totp, recorder) = totp();
+    let otp = totp.generate_at(90).unwrap();
+    recorder.take();
+
+    assert_eq!(totp.verify_at(&otp, 150, None, 0, 60).unwrap(), Some(90));
+
+    assert_eq!(
+        recorder.take(),
+        vec![OtpEvent::Verified {
+            source: OtpEventSource::TOTP,
+            counter: 3,
+            drift: -2
+        }]
+    );
+}
+
+#[test]
+fn totp_verify_at_should_record_replay_before_after() {
+    let (totp, recorder) = totp();
+    let otp = totp.generate_at(120).unwrap();
+    recorder.take();
+
+    assert_eq!(totp.verify_at(&otp, 150, Some(150), 0, 30).unwrap(), None);
+    assert_eq!(totp.verify_at("000000", 150, Some(150), 0, 30).unwrap(), None);
+
+    assert_eq!(
+        recorder.take(),
+        vec![
+            OtpEvent::Replayed {
+                source: OtpEventSource::TOTP,
+                counter: 4
+            },
+            OtpEvent::Rejected {
+                source: OtpEventSource::TOTP,
+                counter: 5
+            }
+        ]
+    );
+}
+
+#[test]
+fn totp_verify_window_at_should_record_replay_before_next() {
+    let (totp, recorder) = totp();
+    let otp = totp.generate_at(120).unwrap();
+    recorder.take();
+
+    let outcome = totp.verify_window_at(&otp, 150, None, 1, 0).unwrap().unwrap();
+    assert_eq!(totp.verify_window_at(&otp, 150, Some(outcome.next), 1, 0).unwrap(), None);
+
+    assert_eq!(
+        recorder.take(),
+        vec![
+            OtpEvent::Verified {
+                source: OtpEventSource::TOTP,
+                counter: 4,
+                drift: -1
+            },
+            OtpEvent::Replayed {
+                source: OtpEventSource::TOTP,
+                counter: 4
+            }
+        ]
+    );
+}
+
+#[test]
+fn totp_resync_at_should_record_drift() {
+    let (totp, recorder) = totp();
+    let first = totp.generate_at(3000).unwrap();
+    let second = totp.generate_at(3030).unwrap();
+    recorder.take();
+
+    assert_eq!(totp.resync_at(&first, &second, 3600, None, 50).unwrap(), Some(101));
+    assert_eq!(totp.resync_at(&first, &second, 3600, Some(3030), 50).unwrap(), None);
+
+    assert_eq!(
+        recorder.take(),
+        vec![
+            OtpEvent::Resynced {
+                source: OtpEventSource::TOTP,
+                counter: 101,
+                drift: -19
+            },
+            OtpEvent::Rejected {
+                source: OtpEventSource::TOTP,
+                counter: 120
+            }
+        ]
+    );
+}
+
+#[test]
+fn events_should_not_contain_code() {
+    let recorder = Arc::new(Mutex::new(Vec::new()));
+    let recorded = recorder.clone();
+    let hotp =
+        HOTP::default(Secret::new_from_str(SECRET).unwrap()).with_event_sink(Arc::new(move |event: &OtpEvent| {
+            recorded.lock().unwrap().push(format!("{:?} {}", event, event))
+        }));
+
+    let otp = hotp.generate(0).unwrap();
+    hotp.verify(&otp, 0, 0).unwrap();
+
+    for event in recorder.lock().unwrap().iter() {
+        assert!(!event.contains(&otp), "{}", event);
+    }
+}
+
+#[test]
+fn clones_should_share_sink_and_equal_ignores_it() {
+    let (hotp, recorder) = hotp();
+    let clone = hotp.clone();
+
+    clone.generate(0).unwrap();
+
+    assert_eq!(recorder.take().len(), 1);
+    assert_eq!(hotp, HOTP::default(Secret::new_from_str(SECRET).unwrap()));
+}
@@ -147,3 +147,26 @@ fn test_hotp_from_uri_null_fail() {
     .stdout("")
     .stderr("URI is null");
 }
+
+#[test]
+#[cfg(not(any(target_os = "windows")))]
+fn test_hotp_provisioning_qr_svg() {
+    assert_cxx! {
+        #include <stdio.h>
+        #include <string.h>
+        #include "contrib/rusotp.hpp"
+
+        int main() {
+            HotpConfig config = {"SHA1", "12345678901234567890", 6, 10};
+            unsigned long counter = 2;
+
+            StringResult svg = hotp_provisioning_qr_svg(config, "rusotp", "rusotp", counter);
+            printf("%s", svg.success && strstr(svg.data, "<svg") != NULL ? "true" : "false");
+
+            return 0;
+        }
+    }
+    .success()
+    .stdout("true")
+    .stderr("");
+}
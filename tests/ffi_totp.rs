@@ -171,3 +171,25 @@ fn test_totp_from_uri_null_fail() {
     .stdout("")
     .stderr("URI is null");
 }
+
+#[test]
+#[cfg(not(any(target_os = "windows")))]
+fn test_totp_provisioning_qr_svg() {
+    assert_cxx! {
+        #include <stdio.h>
+        #include <string.h>
+        #include "contrib/rusotp.hpp"
+
+        int main() {
+            TotpConfig config = {"SHA1", "12345678901234567890", 6, 10, 30};
+
+            StringResult svg = totp_provisioning_qr_svg(config, "rusotp", "user@email.mail");
+            printf("%s", svg.success && strstr(svg.data, "<svg") != NULL ? "true" : "false");
+
+            return 0;
+        }
+    }
+    .success()
+    .stdout("true")
+    .stderr("");
+}
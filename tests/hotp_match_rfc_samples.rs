@@ -5,7 +5,8 @@
 //
 // See the file LICENSE for details.
 
-use rusotp::{Secret, HOTP};
+use rusotp::{Algorithm, Radix, Secret, HOTP};
+use std::num::NonZeroU8;
 
 #[test]
 fn otp_should_match_with_rfc_samples() {
@@ -31,3 +32,77 @@ fn otp_should_match_with_rfc_samples() {
         assert_eq!(result.unwrap(), *otp);
     });
 }
+
+fn assert_samples(algorithm: Algorithm, samples: [&str; 10]) {
+    let hotp = HOTP::new(
+        algorithm,
+        Secret::new_from_str("12345678901234567890").unwrap(),
+        NonZeroU8::new(6).unwrap(),
+        Radix::new(10).unwrap(),
+    );
+    for (counter, otp) in samples.iter().enumerate() {
+        assert_eq!(hotp.generate(counter as u64).unwrap(), *otp, "{:?} counter {}", algorithm, counter);
+    }
+}
+
+#[test]
+fn otp_should_match_known_answers_using_sha224_and_sha384() {
+    assert_samples(
+        Algorithm::SHA224,
+        [
+            "893239", "812810", "291286", "303565", "943696", "550023", "794718", "708553", "957603", "641410",
+        ],
+    );
+    assert_samples(
+        Algorithm::SHA384,
+        [
+            "502125", "080675", "698930", "317355", "431422", "665174", "446854", "165020", "926248", "408529",
+        ],
+    );
+}
+
+#[cfg(feature = "sha3")]
+#[test]
+fn otp_should_match_known_answers_using_sha3() {
+    assert_samples(
+        Algorithm::SHA3_224,
+        [
+            "228979", "587200", "707840", "715454", "507008", "350139", "612540", "242963", "205677", "552455",
+        ],
+    );
+    assert_samples(
+        Algorithm::SHA3_256,
+        [
+            "170828", "902588", "810314", "848987", "384821", "243804", "545314", "717116", "040202", "519923",
+        ],
+    );
+    assert_samples(
+        Algorithm::SHA3_384,
+        [
+            "133113", "687604", "536980", "315264", "362922", "207298", "731749", "268675", "714617", "726483",
+        ],
+    );
+    assert_samples(
+        Algorithm::SHA3_512,
+        [
+            "342230", "625483", "819892", "919174", "115067", "925537", "902057", "079366", "307759", "433164",
+        ],
+    );
+}
+
+#[cfg(feature = "blake2")]
+#[test]
+fn otp_should_match_known_answers_using_blake2() {
+    assert_samples(
+        Algorithm::BLAKE2B512,
+        [
+            "737565", "409498", "661680", "066333", "366265", "228116", "423720", "121163", "570614", "140122",
+        ],
+    );
+    assert_samples(
+        Algorithm::BLAKE2S256,
+        [
+            "054669", "899568", "117963", "367182", "146105", "847004", "768381", "766022", "167012", "214931",
+        ],
+    );
+}
@@ -120,3 +120,35 @@ fn provisioning_uri_should_fail_with_radix_more_than_10() {
     assert!(result.is_err(), "Expected an error");
     assert_eq!(result.err().unwrap().to_string(), UnsupportedRadixError(11).to_string());
 }
+
+#[test]
+#[cfg(feature = "qr")]
+fn provisioning_qr_should_match_provisioning_uri() {
+    let hotp_tool = HOTP::new(
+        Algorithm::SHA1,
+        Secret::new_from_str("12345678901234567890").unwrap(),
+        NonZeroU8::new(6).unwrap(),
+        Radix::new(RADIX).unwrap(),
+    );
+
+    let qr = hotp_tool.provisioning_qr("test", "test", 0).unwrap();
+
+    assert_eq!(qr.uri(), hotp_tool.provisioning_uri("test", "test", 0).unwrap());
+    assert!(qr.svg().unwrap().contains("<svg"));
+}
+
+#[test]
+#[cfg(feature = "qr")]
+fn provisioning_qr_should_fail_with_sha256() {
+    let hotp_tool = HOTP::new(
+        Algorithm::SHA256,
+        Secret::new_from_str("12345678901234567890").unwrap(),
+        NonZeroU8::new(6).unwrap(),
+        Radix::new(RADIX).unwrap(),
+    );
+
+    let result = hotp_tool.provisioning_qr("test", "test", 0);
+
+    assert!(result.is_err(), "Expected an error");
+    assert_eq!(result.err().unwrap().to_string(), UnsupportedAlgorithmError(Algorithm::SHA256).to_string());
+}
@@ -6,7 +6,7 @@
 // See the file LICENSE for details.
 
 use itertools::iproduct;
-use rusotp::{Algorithm, Radix, Secret, HOTP};
+use rusotp::{Algorithm, Radix, Secret, VerifyOutcome, HOTP};
 use std::num::{NonZero, NonZeroU8};
 
 const ALGORITHM: Algorithm = Algorithm::SHA256;
@@ -74,3 +74,58 @@ fn otp_get_verified_with_retries() {
             assert!(result.unwrap().is_some(), "Expected a successful verification");
         });
 }
+
+#[test]
+fn verify_window_should_return_counter_drift_and_next() {
+    let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+
+    assert_eq!(
+        hotp.verify_window("755224", 0, 0).unwrap(),
+        Some(VerifyOutcome {
+            counter: 0,
+            drift: 0,
+            next: 1
+        })
+    );
+    assert_eq!(
+        hotp.verify_window("520489", 4, 10).unwrap(),
+        Some(VerifyOutcome {
+            counter: 9,
+            drift: 5,
+            next: 10
+        })
+    );
+}
+
+#[test]
+fn verify_window_should_reject_counters_outside_the_window() {
+    let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+
+    assert_eq!(hotp.verify_window("520489", 4, 4).unwrap(), None);
+    assert_eq!(hotp.verify_window("520489", 10, 10).unwrap(), None);
+    assert_eq!(hotp.verify_window("52048", 9, 0).unwrap(), None);
+}
+
+#[test]
+fn verify_window_should_reject_the_last_counter() {
+    let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    let otp = hotp.generate(u64::MAX).unwrap();
+
+    assert_eq!(hotp.verify_window(&otp, u64::MAX - 1, 5).unwrap(), None);
+    assert_eq!(hotp.verify_window(&otp, u64::MAX, 0).unwrap(), None);
+
+    let otp = hotp.generate(u64::MAX - 1).unwrap();
+    let outcome = hotp.verify_window(&otp, u64::MAX - 2, 5).unwrap().unwrap();
+    assert_eq!((outcome.counter, outcome.drift, outcome.next), (u64::MAX - 1, 1, u64::MAX));
+}
+
+#[test]
+fn resync_should_stop_at_the_last_counter() {
+    let hotp = HOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    let first = hotp.generate(u64::MAX - 1).unwrap();
+    let second = hotp.generate(u64::MAX).unwrap();
+
+    assert_eq!(hotp.resync(&first, &second, u64::MAX - 5, 100).unwrap(), Some(u64::MAX));
+    assert_eq!(hotp.resync(&second, &first, u64::MAX - 5, 100).unwrap(), None);
+    assert_eq!(hotp.resync(&first, &second, u64::MAX, 100).unwrap(), None);
+}
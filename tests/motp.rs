@@ -0,0 +1,125 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "motp")]
+
+use rusotp::{MOtp, OtpEvent, OtpEventSource, Secret};
+use std::sync::{Arc, Mutex};
+
+const SECRET: &str = "1234567890abcdef";
+const PIN: &str = "1234";
+
+fn motp() -> MOtp {
+    MOtp::new(Secret::new_from_str(SECRET).unwrap(), PIN)
+}
+
+#[test]
+fn generated_codes_should_match_md5_of_step_secret_and_pin() {
+    let motp = motp();
+    let samples = [
+        (0, "e3a63b"),
+        (59, "5a83b4"),
+        (1111111109, "934350"),
+        (1234567890, "f52dc6"),
+        (2000000000, "6f8c42"),
+    ];
+    for (timestamp, code) in samples {
+        assert_eq!(motp.generate_at(timestamp).unwrap(), code);
+    }
+}
+
+#[test]
+fn codes_should_stay_the_same_within_a_ten_second_step() {
+    let motp = motp();
+    assert_eq!(motp.generate_at(1111111100).unwrap(), motp.generate_at(1111111109).unwrap());
+    assert_ne!(motp.generate_at(1111111109).unwrap(), motp.generate_at(1111111110).unwrap());
+}
+
+#[test]
+fn pin_should_change_the_code() {
+    let other = MOtp::new(Secret::new_from_str(SECRET).unwrap(), "4321");
+    assert_ne!(motp().generate_at(1111111109).unwrap(), other.generate_at(1111111109).unwrap());
+    assert_ne!(motp(), other);
+}
+
+#[test]
+fn verify_should_accept_codes_within_the_window() {
+    let motp = motp();
+    assert_eq!(motp.verify_at("934350", 1111111109, None, 0, 0).unwrap(), Some(1111111109));
+    assert_eq!(motp.verify_at("934350", 1111111280, None, 0, 180).unwrap(), Some(1111111100));
+    assert_eq!(motp.verify_at("934350", 1111110920, None, 180, 0).unwrap(), Some(1111111100));
+}
+
+#[test]
+fn verify_should_ignore_letter_case() {
+    assert_eq!(motp().verify_at("F52DC6", 1234567890, None, 0, 0).unwrap(), Some(1234567890));
+}
+
+#[test]
+fn verify_should_reject_codes_outside_the_window() {
+    let motp = motp();
+    assert_eq!(motp.verify_at("934350", 1111111300, None, 180, 180).unwrap(), None);
+    assert_eq!(motp.verify_at("93435", 1111111109, None, 180, 180).unwrap(), None);
+    assert_eq!(motp.verify_at("000000", 1111111109, None, 180, 180).unwrap(), None);
+}
+
+#[test]
+fn verify_should_reject_codes_before_after() {
+    let motp = motp();
+    assert_eq!(
+        motp.verify_at("934350", 1111111200, Some(1111111150), 180, 180)
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        motp.verify_at("934350", 1111111200, Some(1111111105), 180, 180)
+            .unwrap(),
+        Some(1111111105)
+    );
+}
+
+#[test]
+fn verify_should_fail_when_drift_behind_or_after_exceed_the_timestamp() {
+    let motp = motp();
+    assert_eq!(
+        motp.verify_at("934350", 100, None, 0, 100).unwrap_err().to_string(),
+        "100 must be less than `at` (100)"
+    );
+    assert!(motp.verify_at("934350", 100, Some(200), 0, 0).is_err());
+}
+
+#[test]
+fn events_should_report_time_steps() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let motp = motp().with_event_sink(Arc::new(move |event: &OtpEvent| recorded.lock().unwrap().push(*event)));
+
+    motp.verify_at("934350", 1111111200, None, 180, 180).unwrap();
+    motp.verify_at("934350", 1111111200, Some(1111111150), 180, 180)
+        .unwrap();
+    motp.verify_at("000000", 1111111200, None, 0, 0).unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(
+        *events,
+        vec![
+            OtpEvent::Verified {
+                source: OtpEventSource::MOTP,
+                counter: 111111110,
+                drift: -10,
+            },
+            OtpEvent::Replayed {
+                source: OtpEventSource::MOTP,
+                counter: 111111110,
+            },
+            OtpEvent::Rejected {
+                source: OtpEventSource::MOTP,
+                counter: 111111120,
+            },
+        ]
+    );
+}
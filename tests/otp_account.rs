@@ -0,0 +1,89 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "uri")]
+
+use rusotp::{OtpAccount, OtpKind, Secret, HOTP, TOTP};
+
+const MIGRATION_URI: &str = "otpauth-migration://offline?data=Cj4KFDEyMzQ1Njc4OTAxMjM0NTY3ODkwEhZydXNvdHA6dXNlckBlbWFpbC5tYWlsGgZydXNvdHAgASgBMAI4AAo2ChQxMjM0NTY3ODkwMTIzNDU2Nzg5MBIOY291bnRlckBydXNvdHAaBnJ1c290cCABKAEwATgCEAEYASAA";
+
+#[test]
+fn from_uri_should_parse_totp_account() {
+    let uri = "otpauth://totp/rusotp%3Auser%40email.mail?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&issuer=rusotp";
+    let account = OtpAccount::from_uri(uri).unwrap();
+
+    assert_eq!(account.issuer, "rusotp");
+    assert_eq!(account.name, "user@email.mail");
+    assert_eq!(account.otp, OtpKind::TOTP(TOTP::default(Secret::new_from_str("12345678901234567890").unwrap())));
+}
+
+#[test]
+fn from_uri_should_parse_hotp_account_with_counter() {
+    let uri = "otpauth://hotp/rusotp%3Arusotp?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&counter=2&issuer=rusotp";
+    let account = OtpAccount::from_uri(uri).unwrap();
+
+    assert_eq!(account.issuer, "rusotp");
+    assert_eq!(account.name, "rusotp");
+    assert_eq!(account.otp, OtpKind::HOTP(HOTP::default(Secret::new_from_str("12345678901234567890").unwrap()), 2));
+}
+
+#[test]
+fn from_uri_should_prefer_issuer_parameter() {
+    let uri = "otpauth://totp/user?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&issuer=Example%20Inc";
+    let account = OtpAccount::from_uri(uri).unwrap();
+
+    assert_eq!(account.issuer, "Example Inc");
+    assert_eq!(account.name, "user");
+}
+
+#[test]
+fn from_uri_should_fail_with_unknown_type() {
+    let result = OtpAccount::from_uri("otpauth://motp/user?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq");
+    assert_eq!(result.err().unwrap().to_string(), "Invalid URI: unsupported OTP type `motp`");
+}
+
+#[test]
+fn from_uri_should_fail_with_unknown_scheme() {
+    let result = OtpAccount::from_uri("https://rusotp/user?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq");
+    assert_eq!(result.err().unwrap().to_string(), "Invalid URI: unsupported scheme `https`");
+}
+
+#[test]
+fn from_migration_uri_should_fail_with_unknown_scheme_without_reporting_the_uri() {
+    let result = OtpAccount::from_migration_uri("otpauth://totp/user?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq");
+    assert_eq!(result.err().unwrap().to_string(), "Invalid URI: unsupported scheme `otpauth`");
+}
+
+#[test]
+fn from_migration_uri_should_parse_all_accounts() {
+    let accounts = OtpAccount::from_migration_uri(MIGRATION_URI).unwrap();
+
+    assert_eq!(accounts.len(), 2);
+    assert_eq!(accounts[0].issuer, "rusotp");
+    assert_eq!(accounts[0].name, "user@email.mail");
+    assert_eq!(accounts[0].otp, OtpKind::TOTP(TOTP::default(Secret::new_from_str("12345678901234567890").unwrap())));
+    assert_eq!(accounts[1].name, "counter@rusotp");
+    match &accounts[1].otp {
+        OtpKind::HOTP(hotp, counter) => assert_eq!(hotp.generate(*counter).unwrap(), "359152"),
+        otp => panic!("Expected an HOTP account, got {:?}", otp),
+    }
+}
+
+#[test]
+fn from_migration_uri_should_fail_with_md5() {
+    let result =
+        OtpAccount::from_migration_uri("otpauth-migration://offline?data=ChsKBDEyMzQSA21kNRoGcnVzb3RwIAQoATACOAA%3D");
+    assert_eq!(result.err().unwrap().to_string(), "Invalid URI: unsupported algorithm in `md5`");
+}
+
+#[test]
+fn parse_should_accept_both_schemes() {
+    let uri = "otpauth://totp/rusotp%3Auser%40email.mail?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&issuer=rusotp";
+
+    assert_eq!(OtpAccount::parse(uri).unwrap().len(), 1);
+    assert_eq!(OtpAccount::parse(MIGRATION_URI).unwrap().len(), 2);
+}
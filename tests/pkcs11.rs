@@ -0,0 +1,141 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "pkcs11")]
+
+// Tests using a token are ignored by default; run them with `-- --include-ignored` and RUSOTP_PKCS11_MODULE pointing to
+// a PKCS#11 module, e.g. SoftHSM with a token initialized with
+// `softhsm2-util --init-token --free --label rusotp --pin 1234 --so-pin 4321`.
+
+use rusotp::pkcs11::{Pkcs11Module, Pkcs11Session};
+use rusotp::{Algorithm, Radix, Secret, HOTP, TOTP};
+use std::num::{NonZeroU64, NonZeroU8};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECRET: &str = "12345678901234567890";
+
+fn module() -> &'static Pkcs11Module {
+    static MODULE: OnceLock<Pkcs11Module> = OnceLock::new();
+    MODULE.get_or_init(|| {
+        let path = std::env::var("RUSOTP_PKCS11_MODULE").expect("RUSOTP_PKCS11_MODULE must be set");
+        unsafe { Pkcs11Module::load(path) }.unwrap()
+    })
+}
+
+fn token() -> String {
+    std::env::var("RUSOTP_PKCS11_TOKEN").unwrap_or_else(|_| "rusotp".to_string())
+}
+
+fn session() -> Pkcs11Session {
+    let pin = std::env::var("RUSOTP_PKCS11_PIN").unwrap_or_else(|_| "1234".to_string());
+    module().login(&token(), &pin).unwrap()
+}
+
+fn unique_label(name: &str) -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    format!("rusotp-test-{}-{}", name, nanos)
+}
+
+#[test]
+fn loading_missing_module_should_fail() {
+    let result = unsafe { Pkcs11Module::load("/nonexistent/libpkcs11.so") };
+    let error = result.unwrap_err().to_string();
+    assert!(error.starts_with("PKCS#11 error: cannot load module"), "{}", error);
+}
+
+#[test]
+#[ignore = "requires RUSOTP_PKCS11_MODULE"]
+fn hotp_with_token_key_should_match_rfc_samples() {
+    let session = session();
+    let key = session
+        .import_key(&unique_label("hotp"), &mut Secret::new_from_str(SECRET).unwrap())
+        .unwrap();
+    let hotp = HOTP::new(Algorithm::SHA1, key, NonZeroU8::new(6).unwrap(), Radix::default());
+
+    let expected = ["755224", "287082", "359152", "969429", "338314"];
+    for (counter, code) in expected.iter().enumerate() {
+        assert_eq!(hotp.generate(counter as u64).unwrap(), *code);
+    }
+    assert_eq!(hotp.verify("969429", 0, 5).unwrap(), Some(3));
+}
+
+#[test]
+#[ignore = "requires RUSOTP_PKCS11_MODULE"]
+fn totp_with_token_key_should_match_secret_totp() {
+    let session = session();
+    let key = session
+        .import_key(&unique_label("totp"), &mut Secret::new_from_str(SECRET).unwrap())
+        .unwrap();
+    let length = NonZeroU8::new(8).unwrap();
+    let interval = NonZeroU64::new(30).unwrap();
+    let token_totp = TOTP::new(Algorithm::SHA256, key, length, Radix::default(), interval);
+    let secret_totp =
+        TOTP::new(Algorithm::SHA256, Secret::new_from_str(SECRET).unwrap(), length, Radix::default(), interval);
+
+    for timestamp in [59, 1111111109, 2000000000] {
+        assert_eq!(token_totp.generate_at(timestamp).unwrap(), secret_totp.generate_at(timestamp).unwrap());
+    }
+}
+
+#[test]
+#[ignore = "requires RUSOTP_PKCS11_MODULE"]
+fn key_should_be_found_by_label() {
+    let session = session();
+    let label = unique_label("lookup");
+    let mut secret = Secret::new_from_str(SECRET).unwrap();
+    let imported = session.import_key(&label, &mut secret).unwrap();
+    assert!(secret.0.is_empty());
+
+    let found = session.key(&label).unwrap();
+    assert_eq!(found.label(), label);
+    assert_eq!(found, imported);
+}
+
+#[test]
+#[ignore = "requires RUSOTP_PKCS11_MODULE"]
+fn missing_key_should_fail() {
+    let session = session();
+    let error = session.key(&unique_label("missing")).unwrap_err().to_string();
+    assert!(error.contains("no secret key labelled"), "{}", error);
+}
+
+#[test]
+#[ignore = "requires RUSOTP_PKCS11_MODULE"]
+fn unknown_token_should_fail() {
+    let module = module();
+    let error = module.login("no-such-token", "1234").unwrap_err().to_string();
+    assert!(error.contains("no token labelled `no-such-token`"), "{}", error);
+}
+
+#[test]
+#[ignore = "requires RUSOTP_PKCS11_MODULE"]
+fn algorithm_without_mechanism_should_fail() {
+    #[derive(Clone)]
+    struct Custom;
+
+    impl rusotp::AlgorithmTrait for Custom {
+        fn to_string(&self) -> String {
+            "CUSTOM".to_string()
+        }
+
+        fn from_string(_name: String) -> Option<Self> {
+            None
+        }
+
+        fn hash(&self, _key: &[u8], _message: &[u8]) -> Result<Vec<u8>, String> {
+            unreachable!()
+        }
+    }
+
+    let session = session();
+    let key = session
+        .import_key(&unique_label("custom"), &mut Secret::new_from_str(SECRET).unwrap())
+        .unwrap();
+    let hotp = HOTP::new(Custom, key, NonZeroU8::new(6).unwrap(), Radix::default());
+    assert!(hotp.generate(0).is_err());
+}
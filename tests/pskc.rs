@@ -0,0 +1,162 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "pskc")]
+
+use rusotp::pskc::PskcContainer;
+use rusotp::{Algorithm, OtpKind, Radix, Secret, HOTP};
+use std::num::NonZeroU8;
+
+const FIGURE_2: &str = include_str!("fixtures/pskc/rfc6030_figure2.xml");
+const FIGURE_3: &str = include_str!("fixtures/pskc/rfc6030_figure3.xml");
+const FIGURE_6: &str = include_str!("fixtures/pskc/rfc6030_figure6.xml");
+const ENCRYPTED_HMAC_SHA256: &str = include_str!("fixtures/pskc/encrypted_hmac_sha256.xml");
+
+fn pre_shared_key() -> Vec<u8> {
+    Secret::new_from_hex("12345678901234567890123456789012").unwrap().get()
+}
+
+fn rfc_hotp() -> OtpKind {
+    let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    OtpKind::HOTP(HOTP::new(Algorithm::SHA1, secret, NonZeroU8::new(8).unwrap(), Radix::new(10).unwrap()), 0)
+}
+
+#[test]
+fn parse_should_read_rfc6030_figure_2() {
+    let container = PskcContainer::parse(FIGURE_2, None).unwrap();
+
+    assert_eq!(container.id.as_deref(), Some("exampleID1"));
+    assert!(container.errors.is_empty());
+    let key = &container.keys[0];
+    assert_eq!(key.id, "12345678");
+    assert_eq!(key.issuer.as_deref(), Some("Issuer"));
+    assert_eq!(key.user_id.as_deref(), Some("UID=jsmith,DC=example-bank,DC=net"));
+    assert_eq!(key.manufacturer.as_deref(), Some("Manufacturer"));
+    assert_eq!(key.serial_no.as_deref(), Some("987654321"));
+    assert_eq!(key.otp, rfc_hotp());
+}
+
+#[test]
+fn parse_should_reject_alphanumeric_encoding() {
+    let container = PskcContainer::parse(&FIGURE_2.replace("DECIMAL", "ALPHANUMERIC"), None).unwrap();
+
+    assert!(container.keys.is_empty());
+    assert_eq!(container.errors[0].error.to_string(), "PSKC error: unsupported encoding `ALPHANUMERIC`");
+}
+
+#[test]
+fn parse_should_generate_rfc4226_values() {
+    let container = PskcContainer::parse(FIGURE_2, None).unwrap();
+
+    if let OtpKind::HOTP(hotp, counter) = &container.keys[0].otp {
+        assert_eq!(hotp.generate(*counter).unwrap(), "84755224");
+    } else {
+        panic!("expected a HOTP key");
+    }
+}
+
+#[test]
+fn parse_should_skip_pin_key_of_rfc6030_figure_3() {
+    let container = PskcContainer::parse(FIGURE_3, None).unwrap();
+
+    assert_eq!(container.keys.len(), 1);
+    assert_eq!(container.keys[0].otp, rfc_hotp());
+    assert_eq!(container.errors.len(), 1);
+    assert_eq!(container.errors[0].index, 1);
+    assert_eq!(container.errors[0].id, "123456781");
+    assert_eq!(
+        container.errors[0].error.to_string(),
+        "PSKC error: unsupported algorithm `urn:ietf:params:xml:ns:keyprov:pskc:pin`"
+    );
+}
+
+#[test]
+fn parse_should_decrypt_rfc6030_figure_6() {
+    let container = PskcContainer::parse(FIGURE_6, Some(&pre_shared_key())).unwrap();
+
+    assert!(container.errors.is_empty());
+    assert_eq!(container.keys[0].otp, rfc_hotp());
+}
+
+#[test]
+fn parse_should_require_pre_shared_key() {
+    let result = PskcContainer::parse(FIGURE_6, None);
+    assert_eq!(result.unwrap_err().to_string(), "PSKC error: the container is encrypted, a pre-shared key is required");
+}
+
+#[test]
+fn parse_should_reject_wrong_pre_shared_key() {
+    let key = Secret::new_from_hex("00000000000000000000000000000000").unwrap().get();
+    assert!(PskcContainer::parse(FIGURE_6, Some(&key)).is_err());
+}
+
+#[test]
+fn parse_should_reject_tampered_value() {
+    let xml = FIGURE_6.replace("Su+NvtQfmvfJzF6bmQiJqoLRExc=", "AAAAAAAAAAAAAAAAAAAAAAAAAAA=");
+    let container = PskcContainer::parse(&xml, Some(&pre_shared_key())).unwrap();
+
+    assert!(container.keys.is_empty());
+    assert_eq!(container.errors[0].error.to_string(), "PSKC error: MAC verification failed");
+}
+
+#[test]
+fn parse_should_read_totp_with_hmac_sha256_mac() {
+    let container = PskcContainer::parse(ENCRYPTED_HMAC_SHA256, Some(&pre_shared_key())).unwrap();
+
+    assert!(container.errors.is_empty());
+    let OtpKind::TOTP(totp) = &container.keys[0].otp else {
+        panic!("expected a TOTP key");
+    };
+    assert_eq!(totp.algorithm(), Algorithm::SHA256);
+    assert_eq!(totp.interval().get(), 30);
+    assert_eq!(totp.generate_at(59).unwrap(), "46119246");
+}
+
+#[test]
+fn parse_should_decrypt_counter() {
+    let container = PskcContainer::parse(ENCRYPTED_HMAC_SHA256, Some(&pre_shared_key())).unwrap();
+
+    let OtpKind::HOTP(hotp, counter) = &container.keys[1].otp else {
+        panic!("expected a HOTP key");
+    };
+    assert_eq!(*counter, 42);
+    assert_eq!(hotp.length().get(), 6);
+}
+
+#[test]
+fn into_account_should_name_account_after_user() {
+    let container = PskcContainer::parse(FIGURE_2, None).unwrap();
+    let account = container.keys.into_iter().next().unwrap().into_account();
+
+    assert_eq!(account.issuer, "Issuer");
+    assert_eq!(account.name, "UID=jsmith,DC=example-bank,DC=net");
+}
+
+#[test]
+fn into_account_should_fall_back_to_serial_number() {
+    let container = PskcContainer::parse(FIGURE_6, Some(&pre_shared_key())).unwrap();
+    let account = container.keys.into_iter().next().unwrap().into_account();
+
+    assert_eq!(account.label(), "Issuer:987654321");
+}
+
+#[test]
+fn parse_should_reject_other_documents() {
+    assert_eq!(
+        PskcContainer::parse("<KeyContainer Version=\"1.0\"/>", None)
+            .unwrap_err()
+            .to_string(),
+        "PSKC error: not a PSKC key container"
+    );
+    assert_eq!(
+        PskcContainer::parse(&FIGURE_2.replace("Version=\"1.0\"", "Version=\"2.0\""), None)
+            .unwrap_err()
+            .to_string(),
+        "PSKC error: unsupported PSKC version"
+    );
+    assert!(PskcContainer::parse("not xml", None).is_err());
+}
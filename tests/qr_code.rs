@@ -0,0 +1,79 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "qr")]
+
+use rusotp::{QrCodeError, QrColor, QrErrorCorrection, QrRenderer};
+use std::num::NonZeroU32;
+
+const URI: &str = "otpauth://totp/rusotp%3Auser%40email.mail?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&issuer=rusotp";
+
+#[test]
+fn svg_should_use_configured_colors_and_module_size() {
+    let svg = QrRenderer::new()
+        .with_module_size(NonZeroU32::new(3).unwrap())
+        .with_colors(QrColor(0x12, 0x34, 0x56), QrColor(0xfe, 0xdc, 0xba))
+        .svg(URI)
+        .unwrap();
+
+    assert!(svg.starts_with("<?xml"));
+    assert!(svg.contains("#123456"));
+    assert!(svg.contains("#fedcba"));
+}
+
+#[test]
+fn svg_should_grow_with_error_correction_level() {
+    let low = QrRenderer::new()
+        .with_error_correction(QrErrorCorrection::Low)
+        .svg(URI)
+        .unwrap();
+    let high = QrRenderer::new()
+        .with_error_correction(QrErrorCorrection::High)
+        .svg(URI)
+        .unwrap();
+
+    assert!(high.len() > low.len());
+}
+
+#[test]
+fn terminal_should_use_half_blocks_and_quiet_zone() {
+    let with_quiet_zone = QrRenderer::new().terminal(URI).unwrap();
+    let without_quiet_zone = QrRenderer::new().with_quiet_zone(false).terminal(URI).unwrap();
+
+    assert!(with_quiet_zone.contains('▀') || with_quiet_zone.contains('▄'));
+    assert!(with_quiet_zone.lines().next().unwrap().trim().is_empty());
+    assert!(with_quiet_zone.lines().count() > without_quiet_zone.lines().count());
+}
+
+#[test]
+fn oversized_data_should_fail() {
+    let data = "A".repeat(8000);
+    let result = QrRenderer::new().svg(&data);
+
+    assert!(result.is_err(), "Expected an error");
+    assert_eq!(result.err().unwrap().to_string(), QrCodeError("data too long".to_string()).to_string());
+}
+
+#[test]
+#[cfg(feature = "qr-image")]
+fn png_should_be_rendered_in_memory() {
+    let png = QrRenderer::new()
+        .with_module_size(NonZeroU32::new(2).unwrap())
+        .png(URI)
+        .unwrap();
+
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+}
+
+#[test]
+#[cfg(feature = "qr-image")]
+fn save_should_fail_with_unknown_extension() {
+    let path = std::env::temp_dir().join("rusotp_qr_code.unknown");
+    let result = QrRenderer::new().save(URI, &path);
+
+    assert!(result.is_err(), "Expected an error");
+}
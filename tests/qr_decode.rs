@@ -0,0 +1,66 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "qr-decode")]
+
+use rusotp::{decode_qr_bytes, decode_qr_file, OtpKind, QrRenderer, Secret, HOTP, TOTP};
+
+fn secret() -> Secret {
+    Secret::new_from_str("12345678901234567890").unwrap()
+}
+
+#[test]
+fn decode_qr_bytes_should_read_rendered_totp() {
+    let png = TOTP::default(secret())
+        .provisioning_qr("rusotp", "user@email.mail")
+        .unwrap()
+        .png()
+        .unwrap();
+
+    let accounts = decode_qr_bytes(&png).unwrap();
+
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].issuer, "rusotp");
+    assert_eq!(accounts[0].name, "user@email.mail");
+    assert_eq!(accounts[0].otp, OtpKind::TOTP(TOTP::default(secret())));
+}
+
+#[test]
+fn decode_qr_file_should_read_rendered_hotp() {
+    let path = std::env::temp_dir().join("rusotp_qr_decode_hotp.png");
+    HOTP::default(secret())
+        .provisioning_qr("rusotp", "rusotp", 2)
+        .unwrap()
+        .save(&path)
+        .unwrap();
+
+    let accounts = decode_qr_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(accounts[0].otp, OtpKind::HOTP(HOTP::default(secret()), 2));
+}
+
+#[test]
+fn decode_qr_bytes_should_read_migration_payload() {
+    let uri = "otpauth-migration://offline?data=Cj4KFDEyMzQ1Njc4OTAxMjM0NTY3ODkwEhZydXNvdHA6dXNlckBlbWFpbC5tYWlsGgZydXNvdHAgASgBMAI4AAo2ChQxMjM0NTY3ODkwMTIzNDU2Nzg5MBIOY291bnRlckBydXNvdHAaBnJ1c290cCABKAEwATgCEAEYASAA";
+    let png = QrRenderer::new().png(uri).unwrap();
+
+    assert_eq!(decode_qr_bytes(&png).unwrap().len(), 2);
+}
+
+#[test]
+fn decode_qr_bytes_should_fail_without_otpauth_code() {
+    let png = QrRenderer::new().png("https://github.com/eendroroy/rusotp").unwrap();
+
+    let result = decode_qr_bytes(&png);
+    assert_eq!(result.err().unwrap().to_string(), "QR code error: no otpauth QR code found");
+}
+
+#[test]
+fn decode_qr_bytes_should_fail_with_invalid_image() {
+    assert!(decode_qr_bytes(b"not an image").is_err());
+}
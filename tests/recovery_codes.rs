@@ -0,0 +1,177 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "recovery")]
+
+use rusotp::recovery::{
+    HashedRecoveryCode, MemoryRecoveryCodeStore, RecoveryCodeConfig, RecoveryCodeStore, RecoveryCodes,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::thread;
+
+const USER: &str = "user@email.mail";
+
+fn codes() -> RecoveryCodes<MemoryRecoveryCodeStore> {
+    RecoveryCodes::new(MemoryRecoveryCodeStore::new(), RecoveryCodeConfig::default()).unwrap()
+}
+
+#[test]
+fn generate_should_return_distinct_formatted_codes() {
+    let codes = codes();
+    let plain = codes.generate(USER).unwrap();
+
+    assert_eq!(plain.len(), 10);
+    assert_eq!(plain.iter().collect::<HashSet<_>>().len(), 10);
+    assert!(plain
+        .iter()
+        .all(|code| code.len() == 9 && code.chars().nth(4) == Some('-')));
+    assert_eq!(codes.remaining(USER).unwrap(), 10);
+}
+
+#[test]
+fn generate_should_store_hashes_only() {
+    let codes = codes();
+    let plain = codes.generate(USER).unwrap();
+
+    let stored: Vec<String> = codes
+        .store()
+        .load(USER)
+        .unwrap()
+        .iter()
+        .map(|code| code.to_string())
+        .collect();
+    assert_eq!(stored.len(), 10);
+    for code in &plain {
+        assert!(stored
+            .iter()
+            .all(|hash| !hash.contains(code.as_str()) && !hash.contains(&code.replace('-', ""))));
+    }
+}
+
+#[test]
+fn verify_should_consume_code_once() {
+    let codes = codes();
+    let plain = codes.generate(USER).unwrap();
+
+    assert!(codes.verify(USER, &plain[3]).unwrap());
+    assert!(!codes.verify(USER, &plain[3]).unwrap());
+    assert_eq!(codes.remaining(USER).unwrap(), 9);
+    assert!(codes.verify(USER, &plain[4]).unwrap());
+    assert_eq!(codes.remaining(USER).unwrap(), 8);
+}
+
+#[test]
+fn verify_should_ignore_case_separator_and_whitespace() {
+    let codes = codes();
+    let plain = codes.generate(USER).unwrap();
+
+    assert!(codes
+        .verify(USER, &format!(" {} ", plain[0].replace('-', "").to_lowercase()))
+        .unwrap());
+}
+
+#[test]
+fn verify_should_reject_unknown_code_and_user() {
+    let codes = codes();
+    let plain = codes.generate(USER).unwrap();
+
+    assert!(!codes.verify(USER, "AAAA-AAAA").unwrap());
+    assert!(!codes.verify("other", &plain[0]).unwrap());
+    assert_eq!(codes.remaining(USER).unwrap(), 10);
+    assert_eq!(codes.remaining("other").unwrap(), 0);
+}
+
+#[test]
+fn generate_should_replace_previous_codes() {
+    let codes = codes();
+    let old = codes.generate(USER).unwrap();
+    codes.generate(USER).unwrap();
+
+    assert!(!codes.verify(USER, &old[0]).unwrap());
+    assert_eq!(codes.remaining(USER).unwrap(), 10);
+}
+
+#[test]
+fn verify_should_accept_code_once_across_threads() {
+    let codes = Arc::new(codes());
+    let code = codes.generate(USER).unwrap().remove(0);
+
+    let accepted = (0..8)
+        .map(|_| {
+            let codes = Arc::clone(&codes);
+            let code = code.clone();
+            thread::spawn(move || codes.verify(USER, &code).unwrap())
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(|accepted| *accepted)
+        .count();
+
+    assert_eq!(accepted, 1);
+}
+
+#[test]
+fn config_should_shape_codes() {
+    let config = RecoveryCodeConfig {
+        count: 4,
+        alphabet: "0123456789".to_string(),
+        groups: 3,
+        group_length: 3,
+        separator: ' ',
+    };
+    let codes = RecoveryCodes::new(MemoryRecoveryCodeStore::new(), config).unwrap();
+    let plain = codes.generate(USER).unwrap();
+
+    assert_eq!(plain.len(), 4);
+    assert!(plain
+        .iter()
+        .all(|code| code.len() == 11 && code.split(' ').all(|group| group.len() == 3)));
+    assert!(codes.verify(USER, &plain[0].replace(' ', "")).unwrap());
+}
+
+#[test]
+fn new_should_reject_invalid_config() {
+    let config = RecoveryCodeConfig {
+        alphabet: "AB-".to_string(),
+        ..Default::default()
+    };
+    let result = RecoveryCodes::new(MemoryRecoveryCodeStore::new(), config);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Recovery code error: the alphabet must not contain whitespace or the separator"
+    );
+}
+
+#[test]
+fn hashed_code_should_round_trip_through_string() {
+    let codes = codes();
+    codes.generate(USER).unwrap();
+    let stored = codes.store().load(USER).unwrap();
+
+    let parsed: Vec<HashedRecoveryCode> = stored.iter().map(|code| code.to_string().parse().unwrap()).collect();
+    assert_eq!(parsed, stored);
+    assert!(stored[0].to_string().starts_with("$argon2id$v=19$m=19456,t=2,p=1$"));
+    assert!("not-a-hash".parse::<HashedRecoveryCode>().is_err());
+}
+
+#[test]
+fn hashed_code_should_reject_other_algorithms_and_excessive_parameters() {
+    let salt_and_hash = "$c2FsdHNhbHRzYWx0c2FsdA$Jlq9e8cSRT1TJeKxkMthe2rUz1hPh8HwNqyGXgnvi0c";
+    for params in [
+        "$argon2i$v=19$m=19456,t=2,p=1",
+        "$argon2id$v=19$m=4194304,t=2,p=1",
+        "$argon2id$v=19$m=19456,t=1000,p=1",
+    ] {
+        let text = format!("{}{}", params, salt_and_hash);
+        assert!(text.parse::<HashedRecoveryCode>().is_err(), "{}", text);
+    }
+    assert!(format!("$argon2id$v=19$m=19456,t=2,p=1{}", salt_and_hash)
+        .parse::<HashedRecoveryCode>()
+        .is_ok());
+}
@@ -18,3 +18,35 @@ fn should_fail_to_create_secret() {
     assert!(Secret::new_from_str("").is_err());
     assert_eq!(Secret::new_from_str("").err().unwrap().to_string(), SecretError.to_string());
 }
+
+#[test]
+#[cfg(feature = "uri")]
+fn should_create_secret_from_base32() {
+    let expected = Secret::new_from_str("12345678901234567890").unwrap();
+
+    assert_eq!(Secret::new_from_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(), expected);
+    assert_eq!(Secret::new_from_base32("gezdgnbvgy3tqojqgezdgnbvgy3tqojq").unwrap(), expected);
+    assert_eq!(Secret::new_from_base32("gezd-gnbv gy3t-qojq").unwrap().get(), "1234567890".as_bytes());
+    assert_eq!(Secret::new_from_base32("GEZDGNA").unwrap().get(), "1234".as_bytes());
+    assert_eq!(expected.to_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+}
+
+#[test]
+#[cfg(feature = "uri")]
+fn should_fail_to_create_secret_from_invalid_base32() {
+    assert!(Secret::new_from_base32("").is_err());
+    assert!(Secret::new_from_base32("GEZDGNA1").is_err());
+}
+
+#[test]
+fn should_create_secret_from_hex() {
+    assert_eq!(Secret::new_from_hex("31323334").unwrap().get(), "1234".as_bytes());
+    assert_eq!(Secret::new_from_hex("DEADbeef").unwrap().get(), [0xde, 0xad, 0xbe, 0xef]);
+}
+
+#[test]
+fn should_fail_to_create_secret_from_invalid_hex() {
+    assert!(Secret::new_from_hex("").is_err());
+    assert!(Secret::new_from_hex("123").is_err());
+    assert!(Secret::new_from_hex("zz").is_err());
+}
@@ -0,0 +1,344 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "server")]
+
+use rusotp::{Secret, HOTP, TOTP};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+const TOKEN: &str = "s3cr3t-t0k3n";
+
+/// A `rusotp-server` listening on a free localhost port, killed when dropped.
+struct TestServer {
+    child: Child,
+    address: String,
+}
+
+impl TestServer {
+    fn start(args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rusotp-server"))
+            .args(["--listen", "127.0.0.1:0", "--token", TOKEN])
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let address = line.trim().strip_prefix("listening on http://").unwrap().to_string();
+        Self { child, address }
+    }
+
+    /// Sends a request with the bearer token, returning the status, the `Retry-After` header and the JSON body.
+    fn request(&self, method: &str, path: &str, body: Option<Value>) -> (u16, Option<String>, Value) {
+        self.request_with_token(method, path, Some(TOKEN), body.map(|body| body.to_string()).unwrap_or_default())
+    }
+
+    /// Sends a request with the given bearer token, if any, and raw body.
+    fn request_with_token(
+        &self,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: String,
+    ) -> (u16, Option<String>, Value) {
+        let authorization = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n{}Content-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            self.address,
+            authorization,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head[9..12].parse().unwrap();
+        let retry_after = head
+            .lines()
+            .find_map(|line| line.strip_prefix("Retry-After: "))
+            .map(str::to_string);
+        let body = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(body).unwrap()
+        };
+        (status, retry_after, body)
+    }
+
+    fn enroll(&self, body: Value) -> Value {
+        let (status, _, body) = self.request("POST", "/accounts", Some(body));
+        assert_eq!(status, 201, "{}", body);
+        body
+    }
+
+    fn verify(&self, id: &str, code: &str) -> (u16, Option<String>, Value) {
+        self.request("POST", &format!("/accounts/{}/verify", id), Some(json!({ "code": code })))
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn hotp() -> HOTP {
+    HOTP::default(Secret::new_from_base32(SECRET).unwrap())
+}
+
+fn totp() -> TOTP {
+    TOTP::default(Secret::new_from_base32(SECRET).unwrap())
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[test]
+fn health_should_answer_ok() {
+    let server = TestServer::start(&[]);
+
+    assert_eq!(server.request("GET", "/health", None), (200, None, json!({ "status": "ok" })));
+}
+
+#[test]
+fn enroll_should_return_secret_uri_and_qr_code() {
+    let server = TestServer::start(&["--issuer", "Example"]);
+
+    let body = server.enroll(json!({ "id": "alice", "name": "alice@example.com" }));
+
+    let secret = body["secret"].as_str().unwrap();
+    assert_eq!(body["id"], "alice");
+    assert_eq!(Secret::new_from_base32(secret).unwrap().get().len(), 20);
+    assert_eq!(
+        body["uri"],
+        format!(
+            "otpauth://totp/Example%3Aalice%40example.com?secret={}&issuer=Example&algorithm=SHA1&digits=6&period=30",
+            secret
+        )
+    );
+    assert!(body["qr_svg"].as_str().unwrap().starts_with("<?xml"));
+}
+
+#[test]
+fn enroll_should_reject_enrolled_id() {
+    let server = TestServer::start(&[]);
+    server.enroll(json!({ "id": "alice" }));
+
+    let (status, _, body) = server.request("POST", "/accounts", Some(json!({ "id": "alice" })));
+
+    assert_eq!(status, 409);
+    assert_eq!(body["error"], "conflict");
+}
+
+#[test]
+fn enroll_should_map_otp_errors_to_bad_request() {
+    let server = TestServer::start(&[]);
+
+    let (status, _, body) = server.request("POST", "/accounts", Some(json!({ "id": "alice", "secret": "1!" })));
+
+    assert_eq!(status, 400);
+    assert_eq!(body["error"], "bad_request");
+    assert!(!body["message"].as_str().unwrap().is_empty());
+}
+
+#[test]
+fn verify_should_accept_totp_once() {
+    let server = TestServer::start(&[]);
+    server.enroll(json!({ "id": "alice", "secret": SECRET }));
+    let code = totp().generate_at(now()).unwrap();
+
+    assert_eq!(server.verify("alice", &code), (200, None, json!({ "valid": true })));
+
+    let (status, _, body) = server.verify("alice", &code);
+    assert_eq!(status, 409);
+    assert_eq!(body["error"], "replayed");
+}
+
+#[test]
+fn verify_should_advance_hotp_counter() {
+    let server = TestServer::start(&["--look-ahead", "5"]);
+    server.enroll(json!({ "id": "bob", "type": "hotp", "secret": SECRET }));
+
+    assert_eq!(server.verify("bob", &hotp().generate(0).unwrap()).2, json!({ "valid": true }));
+    assert_eq!(server.verify("bob", &hotp().generate(0).unwrap()).0, 409);
+    assert_eq!(server.verify("bob", &hotp().generate(4).unwrap()).2, json!({ "valid": true }));
+    assert_eq!(server.verify("bob", &hotp().generate(11).unwrap()).2, json!({ "valid": false }));
+}
+
+#[test]
+fn verify_should_lock_account_after_failures() {
+    let server = TestServer::start(&["--max-failures", "3", "--backoff", "0", "--lockout", "60"]);
+    server.enroll(json!({ "id": "bob", "type": "hotp", "secret": SECRET }));
+
+    for _ in 0..3 {
+        assert_eq!(server.verify("bob", "000000").2, json!({ "valid": false }));
+    }
+    let (status, retry_after, body) = server.verify("bob", &hotp().generate(0).unwrap());
+
+    assert_eq!(status, 429);
+    assert_eq!(retry_after.as_deref(), Some("60"));
+    assert_eq!(body["error"], "rate_limited");
+    assert_eq!(body["retry_after"], 60);
+}
+
+#[test]
+fn verify_should_back_off_after_failure() {
+    let server = TestServer::start(&["--backoff", "30"]);
+    server.enroll(json!({ "id": "bob", "type": "hotp", "secret": SECRET }));
+
+    assert_eq!(server.verify("bob", "000000").2, json!({ "valid": false }));
+    let (status, retry_after, _) = server.verify("bob", &hotp().generate(0).unwrap());
+
+    assert_eq!(status, 429);
+    assert!(matches!(retry_after.as_deref(), Some("29" | "30")));
+}
+
+#[test]
+fn resync_should_find_hotp_counter() {
+    let server = TestServer::start(&[]);
+    server.enroll(json!({ "id": "bob", "type": "hotp", "secret": SECRET }));
+    let codes = [hotp().generate(50).unwrap(), hotp().generate(51).unwrap()];
+
+    let (status, _, body) = server.request("POST", "/accounts/bob/resync", Some(json!({ "codes": codes })));
+
+    assert_eq!(status, 200);
+    assert_eq!(body, json!({ "synchronized": true, "counter": 52 }));
+    assert_eq!(server.verify("bob", &hotp().generate(52).unwrap()).2, json!({ "valid": true }));
+}
+
+#[test]
+fn resync_should_measure_totp_drift() {
+    let server = TestServer::start(&[]);
+    server.enroll(json!({ "id": "alice", "secret": SECRET }));
+    let step = now() / 30;
+    let codes = [
+        totp().generate_at((step + 5) * 30).unwrap(),
+        totp().generate_at((step + 6) * 30).unwrap(),
+    ];
+
+    let (_, _, body) = server.request("POST", "/accounts/alice/resync", Some(json!({ "codes": codes })));
+
+    assert_eq!(body, json!({ "synchronized": true, "drift": 6 }));
+    let code = totp().generate_at((step + 7) * 30).unwrap();
+    assert_eq!(server.verify("alice", &code).2, json!({ "valid": true }));
+}
+
+#[test]
+fn resync_should_reject_unrelated_codes() {
+    let server = TestServer::start(&[]);
+    server.enroll(json!({ "id": "bob", "type": "hotp", "secret": SECRET }));
+    let codes = [hotp().generate(50).unwrap(), hotp().generate(60).unwrap()];
+
+    let (_, _, body) = server.request("POST", "/accounts/bob/resync", Some(json!({ "codes": codes })));
+
+    assert_eq!(body, json!({ "synchronized": false }));
+}
+
+#[test]
+fn delete_should_remove_account() {
+    let server = TestServer::start(&[]);
+    server.enroll(json!({ "id": "alice" }));
+
+    assert_eq!(server.request("DELETE", "/accounts/alice", None), (204, None, Value::Null));
+    assert_eq!(server.verify("alice", "123456").0, 404);
+    assert_eq!(server.request("DELETE", "/accounts/alice", None).0, 404);
+}
+
+#[test]
+fn unknown_endpoints_should_be_rejected() {
+    let server = TestServer::start(&[]);
+
+    assert_eq!(server.request("GET", "/accounts/alice/verify", None).0, 405);
+    assert_eq!(server.request("GET", "/metrics", None).2["error"], "not_found");
+    assert_eq!(server.request("POST", "/accounts", None).2["error"], "bad_request");
+}
+
+#[test]
+fn file_store_should_keep_accounts_across_restarts() {
+    let path = std::env::temp_dir().join(format!("rusotp-server-{}.json", std::process::id()));
+    let store = path.to_str().unwrap();
+    {
+        let server = TestServer::start(&["--store", store]);
+        server.enroll(json!({ "id": "bob", "type": "hotp", "secret": SECRET }));
+        assert_eq!(server.verify("bob", &hotp().generate(0).unwrap()).2, json!({ "valid": true }));
+    }
+
+    let server = TestServer::start(&["--store", store]);
+    assert_eq!(server.verify("bob", &hotp().generate(0).unwrap()).0, 409);
+    assert_eq!(server.verify("bob", &hotp().generate(1).unwrap()).2, json!({ "valid": true }));
+
+    drop(server);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn administrative_endpoints_should_require_the_token() {
+    let server = TestServer::start(&[]);
+    server.enroll(json!({ "id": "alice", "secret": SECRET }));
+    let enroll = json!({ "id": "bob" }).to_string();
+    let resync = json!({ "codes": ["755224", "287082"] }).to_string();
+
+    for token in [None, Some("wrong"), Some("s3cr3t-t0k3n-longer")] {
+        let requests = [
+            ("POST", "/accounts", enroll.clone()),
+            ("POST", "/accounts/alice/resync", resync.clone()),
+            ("DELETE", "/accounts/alice", String::new()),
+        ];
+        for (method, path, body) in requests {
+            let (status, _, body) = server.request_with_token(method, path, token, body);
+            assert_eq!(status, 401, "{} {} {:?}", method, path, token);
+            assert_eq!(body["error"], "unauthorized");
+        }
+    }
+    assert_eq!(server.request_with_token("GET", "/health", None, String::new()).0, 200);
+    let code = json!({ "code": totp().generate().unwrap() }).to_string();
+    assert_eq!(
+        server
+            .request_with_token("POST", "/accounts/alice/verify", None, code)
+            .0,
+        200
+    );
+}
+
+#[test]
+fn enroll_should_reject_malformed_secrets_and_keep_serving() {
+    let server = TestServer::start(&[]);
+
+    for secret in ["!!!", "A", "ABC"] {
+        let (status, _, body) = server.request("POST", "/accounts", Some(json!({ "id": "alice", "secret": secret })));
+        assert_eq!(status, 400, "{}", body);
+    }
+    assert_eq!(server.request("GET", "/health", None).0, 200);
+}
+
+#[test]
+fn requests_should_reject_oversized_bodies() {
+    let server = TestServer::start(&[]);
+    let body = json!({ "id": "alice", "name": "x".repeat(100 * 1024) }).to_string();
+
+    let (status, _, body) = server.request_with_token("POST", "/accounts", Some(TOKEN), body);
+    assert_eq!(status, 413);
+    assert_eq!(body["error"], "payload_too_large");
+    assert_eq!(server.request("GET", "/health", None).0, 200);
+}
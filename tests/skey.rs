@@ -0,0 +1,158 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "skey")]
+
+use rusotp::skey::{MemorySKeyStore, SKey, SKeyAlgorithm, SKeyOtp, SKeyState, SKeyStore, SKeyVerifier};
+
+// RFC 2289 (Appendix C): pass-phrase, seed, sequence number, hexadecimal and words.
+const MD5_VECTORS: [(&str, &str, u32, &str, &str); 9] = [
+    ("This is a test.", "TeSt", 0, "9E876134D90499DD", "INCH SEA ANNE LONG AHEM TOUR"),
+    ("This is a test.", "TeSt", 1, "7965E05436F5029F", "EASE OIL FUM CURE AWRY AVIS"),
+    ("This is a test.", "TeSt", 99, "50FE1962C4965880", "BAIL TUFT BITS GANG CHEF THY"),
+    ("AbCdEfGhIjK", "alpha1", 0, "87066DD9644BF206", "FULL PEW DOWN ONCE MORT ARC"),
+    ("AbCdEfGhIjK", "alpha1", 1, "7CD34C1040ADD14B", "FACT HOOF AT FIST SITE KENT"),
+    ("AbCdEfGhIjK", "alpha1", 99, "5AA37A81F212146C", "BODE HOP JAKE STOW JUT RAP"),
+    ("OTP's are good", "correct", 0, "F205753943DE4CF9", "ULAN NEW ARMY FUSE SUIT EYED"),
+    ("OTP's are good", "correct", 1, "DDCDAC956F234937", "SKIM CULT LOB SLAM POE HOWL"),
+    ("OTP's are good", "correct", 99, "B203E28FA525BE47", "LONG IVY JULY AJAR BOND LEE"),
+];
+
+const SHA1_VECTORS: [(&str, &str, u32, &str, &str); 9] = [
+    ("This is a test.", "TeSt", 0, "BB9E6AE1979D8FF4", "MILT VARY MAST OK SEES WENT"),
+    ("This is a test.", "TeSt", 1, "63D936639734385B", "CART OTTO HIVE ODE VAT NUT"),
+    ("This is a test.", "TeSt", 99, "87FEC7768B73CCF9", "GAFF WAIT SKID GIG SKY EYED"),
+    ("AbCdEfGhIjK", "alpha1", 0, "AD85F658EBE383C9", "LEST OR HEEL SCOT ROB SUIT"),
+    ("AbCdEfGhIjK", "alpha1", 1, "D07CE229B5CF119B", "RITE TAKE GELD COST TUNE RECK"),
+    ("AbCdEfGhIjK", "alpha1", 99, "27BC71035AAF3DC6", "MAY STAR TIN LYON VEDA STAN"),
+    ("OTP's are good", "correct", 0, "D51F3E99BF8E6F0B", "RUST WELT KICK FELL TAIL FRAU"),
+    ("OTP's are good", "correct", 1, "82AEB52D943774E4", "FLIT DOSE ALSO MEW DRUM DEFY"),
+    ("OTP's are good", "correct", 99, "4F296A74FE1567EC", "AURA ALOE HURL WING BERG WAIT"),
+];
+
+fn skey() -> SKey {
+    SKey::new(SKeyAlgorithm::MD5, "This is a test.", "TeSt").unwrap()
+}
+
+#[test]
+fn generate_should_match_rfc_vectors() {
+    for (algorithm, vectors) in [(SKeyAlgorithm::MD5, MD5_VECTORS), (SKeyAlgorithm::SHA1, SHA1_VECTORS)] {
+        for (passphrase, seed, sequence, hex, words) in vectors {
+            let otp = SKey::new(algorithm, passphrase, seed).unwrap().generate(sequence);
+            assert_eq!(otp.to_hex(), hex, "{} {} {}", algorithm, seed, sequence);
+            assert_eq!(otp.to_words(), words, "{} {} {}", algorithm, seed, sequence);
+            assert_eq!(SKeyOtp::parse(hex).unwrap(), otp);
+            assert_eq!(SKeyOtp::parse(words).unwrap(), otp);
+        }
+    }
+}
+
+#[test]
+fn sequence_should_list_the_otps_in_the_order_they_are_used() {
+    let skey = skey();
+    let list = skey.sequence(100);
+    assert_eq!(list.len(), 100);
+    assert_eq!(list[0], (99, skey.generate(99)));
+    assert_eq!(list[98], (1, skey.generate(1)));
+    assert_eq!(list[99], (0, skey.generate(0)));
+    assert!(skey.sequence(0).is_empty());
+}
+
+#[test]
+fn parse_should_reject_malformed_otps() {
+    for otp in [
+        "",
+        "9E876134D90499D",
+        "9E876134D90499DDA",
+        "9E876134D90499DG",
+        "INCH SEA ANNE LONG AHEM TOUT",
+    ] {
+        assert!(SKeyOtp::parse(otp).is_err(), "{}", otp);
+    }
+}
+
+#[test]
+fn new_should_reject_invalid_passphrases_and_seeds() {
+    assert!(SKey::new(SKeyAlgorithm::MD5, "too short", "TeSt").is_err());
+    assert!(SKey::new(SKeyAlgorithm::MD5, "This is a test.", "").is_err());
+    assert!(SKey::new(SKeyAlgorithm::MD5, "This is a test.", "seed with space").is_err());
+    assert!(SKey::new(SKeyAlgorithm::MD5, "This is a test.", "abcdefghijklmnopq").is_err());
+    assert_eq!(SKey::new(SKeyAlgorithm::MD5, "This is a test.", "TeSt").unwrap().seed(), "test");
+}
+
+#[test]
+fn debug_should_not_leak_the_chain() {
+    let debug = format!("{:?}", skey());
+    assert!(!debug.contains("initial"));
+    assert!(debug.contains("test"));
+}
+
+#[test]
+fn state_should_accept_the_previous_otp_only() {
+    let skey = skey();
+    let state = skey.state(100);
+    assert_eq!(state.challenge().unwrap(), "otp-md5 99 test");
+
+    assert_eq!(state.verify(&skey.generate(98).to_hex()).unwrap(), None);
+    assert_eq!(state.verify(&skey.generate(100).to_hex()).unwrap(), None);
+
+    let next = state
+        .verify(&skey.generate(99).to_words().to_lowercase())
+        .unwrap()
+        .unwrap();
+    assert_eq!(next, skey.state(99));
+    assert_eq!(next.challenge().unwrap(), "otp-md5 98 test");
+    assert!(state.verify("not an otp").is_err());
+}
+
+#[test]
+fn state_should_be_exhausted_after_sequence_zero() {
+    let skey = skey();
+    let state = skey.state(1).verify(&skey.generate(0).to_hex()).unwrap().unwrap();
+    assert_eq!(state.sequence, 0);
+    assert_eq!(state.challenge(), None);
+    let reused = SKeyState {
+        last: skey.generate(0),
+        ..state
+    };
+    assert_eq!(reused.verify(&skey.generate(0).to_hex()).unwrap(), None);
+}
+
+#[test]
+fn verifier_should_accept_each_otp_once() {
+    let skey = skey();
+    let verifier = SKeyVerifier::new(MemorySKeyStore::new());
+    verifier.store().save("root", &skey.state(3)).unwrap();
+
+    for (sequence, otp) in skey.sequence(3) {
+        assert_eq!(verifier.challenge("root").unwrap().unwrap(), format!("otp-md5 {} test", sequence));
+        assert!(verifier.verify("root", &otp.to_words()).unwrap());
+        assert!(!verifier.verify("root", &otp.to_words()).unwrap());
+    }
+    assert_eq!(verifier.challenge("root").unwrap(), None);
+    assert_eq!(verifier.store().load("root").unwrap(), Some(skey.state(0)));
+}
+
+#[test]
+fn verifier_should_reject_unknown_users() {
+    let verifier = SKeyVerifier::new(MemorySKeyStore::new());
+    assert_eq!(verifier.challenge("root").unwrap(), None);
+    assert!(!verifier.verify("root", &skey().generate(0).to_hex()).unwrap());
+}
+
+#[test]
+fn store_should_only_advance_the_same_sequence() {
+    let skey = skey();
+    let store = MemorySKeyStore::new();
+    store.save("root", &skey.state(10)).unwrap();
+
+    let other = SKey::new(SKeyAlgorithm::MD5, "This is a test.", "other").unwrap();
+    assert!(!store.advance("root", &other.state(9)).unwrap());
+    assert!(!store.advance("root", &skey.state(10)).unwrap());
+    assert!(store.advance("root", &skey.state(9)).unwrap());
+    assert!(!store.advance("nobody", &skey.state(9)).unwrap());
+}
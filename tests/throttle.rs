@@ -0,0 +1,195 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "std")]
+
+use rusotp::throttle::{Throttle, ThrottleConfig};
+use rusotp::{Secret, HOTP, TOTP};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const USER: &str = "user@email.mail";
+
+/// A throttle whose clock starts at 1000 and is moved by the returned handle.
+fn throttle(config: ThrottleConfig) -> (Throttle, Arc<AtomicU64>) {
+    let time = Arc::new(AtomicU64::new(1_000));
+    let clock = time.clone();
+    (Throttle::with_clock(config, move || clock.load(Ordering::SeqCst)), time)
+}
+
+fn hotp() -> HOTP {
+    HOTP::default(Secret::new_from_str("12345678901234567890").unwrap())
+}
+
+fn retry_after(throttle: &Throttle, key: &str) -> Option<u64> {
+    throttle.check(key).err().and_then(|error| error.retry_after())
+}
+
+#[test]
+fn verify_hotp_should_return_matched_counter() {
+    let (throttle, _) = throttle(ThrottleConfig::default());
+
+    assert_eq!(throttle.verify_hotp(USER, &hotp(), "359152", 0, 5).unwrap(), Some(2));
+    assert_eq!(retry_after(&throttle, USER), None);
+}
+
+#[test]
+fn verify_totp_should_use_clock() {
+    let (throttle, time) = throttle(ThrottleConfig::default());
+    let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    time.store(59, Ordering::SeqCst);
+
+    assert_eq!(throttle.verify_totp(USER, &totp, "287082", None, 0, 0).unwrap(), Some(59));
+}
+
+#[test]
+fn failures_should_back_off_exponentially() {
+    let (throttle, time) = throttle(ThrottleConfig::default());
+
+    for expected in [1, 2, 4, 8] {
+        assert_eq!(throttle.verify_hotp(USER, &hotp(), "000000", 0, 0).unwrap(), None);
+        assert_eq!(retry_after(&throttle, USER), Some(expected));
+
+        let error = throttle.verify_hotp(USER, &hotp(), "755224", 0, 0).unwrap_err();
+        assert_eq!(error.retry_after(), Some(expected));
+
+        time.fetch_add(expected, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn failures_should_lock_account() {
+    let (throttle, time) = throttle(ThrottleConfig {
+        backoff: 0,
+        ..Default::default()
+    });
+
+    for _ in 0..4 {
+        assert_eq!(throttle.record_failure(USER), 0);
+    }
+    assert_eq!(throttle.record_failure(USER), 900);
+    assert_eq!(retry_after(&throttle, USER), Some(900));
+
+    time.fetch_add(600, Ordering::SeqCst);
+    assert_eq!(retry_after(&throttle, USER), Some(300));
+
+    time.fetch_add(300, Ordering::SeqCst);
+    assert_eq!(throttle.verify_hotp(USER, &hotp(), "755224", 0, 0).unwrap(), Some(0));
+}
+
+#[test]
+fn failures_should_count_from_zero_after_lockout() {
+    let (throttle, time) = throttle(ThrottleConfig::default());
+
+    for _ in 0..5 {
+        throttle.record_failure(USER);
+    }
+    time.fetch_add(900, Ordering::SeqCst);
+
+    assert_eq!(throttle.record_failure(USER), 1);
+}
+
+#[test]
+fn success_should_reset_failures() {
+    let (throttle, time) = throttle(ThrottleConfig::default());
+    for _ in 0..3 {
+        throttle.record_failure(USER);
+    }
+    time.fetch_add(4, Ordering::SeqCst);
+
+    assert_eq!(throttle.verify_hotp(USER, &hotp(), "755224", 0, 0).unwrap(), Some(0));
+    assert_eq!(throttle.record_failure(USER), 1);
+}
+
+#[test]
+fn idle_accounts_should_be_forgotten() {
+    let (throttle, time) = throttle(ThrottleConfig::default());
+    assert_eq!(throttle.record_failure(USER), 1);
+    assert_eq!(throttle.record_failure(USER), 2);
+
+    time.fetch_add(2 + 60, Ordering::SeqCst);
+    for key in 0..10_000 {
+        throttle.record_failure(&key.to_string());
+    }
+    assert_eq!(throttle.record_failure(USER), 4);
+
+    time.fetch_add(4 + 900, Ordering::SeqCst);
+    for key in 10_000..30_000 {
+        throttle.record_failure(&key.to_string());
+    }
+    assert_eq!(throttle.record_failure(USER), 1);
+}
+
+#[test]
+fn accounts_should_be_throttled_independently() {
+    let (throttle, _) = throttle(ThrottleConfig::default());
+
+    throttle.record_failure(USER);
+
+    assert_eq!(retry_after(&throttle, USER), Some(1));
+    assert_eq!(retry_after(&throttle, "other@email.mail"), None);
+}
+
+#[test]
+fn attempt_should_not_verify_throttled_account() {
+    let (throttle, _) = throttle(ThrottleConfig::default());
+    throttle.record_failure(USER);
+
+    let result = throttle.attempt(USER, || -> rusotp::OtpResult<Option<()>> { panic!("verified while throttled") });
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn attempt_should_not_count_verification_errors() {
+    let (throttle, time) = throttle(ThrottleConfig::default());
+    let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    time.store(10, Ordering::SeqCst);
+
+    let error = throttle.verify_totp(USER, &totp, "000000", None, 0, 30).unwrap_err();
+
+    assert_eq!(error.retry_after(), None);
+    assert_eq!(retry_after(&throttle, USER), None);
+}
+
+#[test]
+fn zero_max_failures_should_never_lock() {
+    let (throttle, _) = throttle(ThrottleConfig {
+        max_failures: 0,
+        backoff: 0,
+        ..Default::default()
+    });
+
+    for _ in 0..100 {
+        assert_eq!(throttle.record_failure(USER), 0);
+    }
+    assert!(throttle.check(USER).is_ok());
+}
+
+#[test]
+fn concurrent_guesses_should_not_exceed_max_failures() {
+    let (throttle, _) = throttle(ThrottleConfig {
+        max_failures: 3,
+        backoff: 0,
+        ..Default::default()
+    });
+    let throttle = Arc::new(throttle);
+
+    let rejected = (0..8)
+        .map(|_| {
+            let throttle = throttle.clone();
+            thread::spawn(move || matches!(throttle.verify_hotp(USER, &hotp(), "000000", 0, 0), Ok(None)))
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(|rejected| *rejected)
+        .count();
+
+    assert_eq!(rejected, 3);
+}
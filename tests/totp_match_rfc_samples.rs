@@ -6,7 +6,7 @@
 // See the file LICENSE for details.
 
 use rusotp::{Algorithm, Radix, Secret, TOTP};
-use std::num::NonZero;
+use std::num::{NonZero, NonZeroU64, NonZeroU8};
 
 #[test]
 fn otp_should_match_with_rfc_samples() {
@@ -44,3 +44,44 @@ fn otp_should_match_with_rfc_samples() {
         assert_eq!(result.unwrap(), *otp);
     });
 }
+
+/// Seeds of `length` bytes cycling through the digits, as in the RFC 6238 samples.
+fn seed(length: usize) -> Secret {
+    Secret::new_from_vec(b"1234567890".iter().cycle().take(length).copied().collect())
+}
+
+fn assert_samples(algorithm: Algorithm, seed_length: usize, samples: [&str; 6]) {
+    let totp = TOTP::new(
+        algorithm,
+        seed(seed_length),
+        NonZeroU8::new(8).unwrap(),
+        Radix::new(10).unwrap(),
+        NonZeroU64::new(30).unwrap(),
+    );
+    let timestamps = [59, 1111111109, 1111111111, 1234567890, 2000000000, 20000000000];
+    for (timestamp, otp) in timestamps.iter().zip(samples) {
+        assert_eq!(totp.generate_at(*timestamp).unwrap(), otp, "{:?} at {}", algorithm, timestamp);
+    }
+}
+
+#[test]
+fn otp_should_match_known_answers_using_sha224_and_sha384() {
+    assert_samples(Algorithm::SHA224, 28, ["32201820", "82019503", "98501000", "25631841", "97691324", "81596645"]);
+    assert_samples(Algorithm::SHA384, 48, ["12260385", "93607533", "30635264", "29066410", "45679189", "33578931"]);
+}
+
+#[cfg(feature = "sha3")]
+#[test]
+fn otp_should_match_known_answers_using_sha3() {
+    assert_samples(Algorithm::SHA3_224, 28, ["34555154", "90576225", "48377628", "89594376", "14985105", "93538472"]);
+    assert_samples(Algorithm::SHA3_256, 32, ["03503818", "00384900", "32359471", "06893637", "49355738", "23378950"]);
+    assert_samples(Algorithm::SHA3_384, 48, ["05907816", "12057122", "37189926", "19242393", "73274072", "79358293"]);
+    assert_samples(Algorithm::SHA3_512, 64, ["01892432", "25574199", "52419195", "84520349", "39414928", "29527821"]);
+}
+
+#[cfg(feature = "blake2")]
+#[test]
+fn otp_should_match_known_answers_using_blake2() {
+    assert_samples(Algorithm::BLAKE2B512, 64, ["27034836", "85075912", "20552396", "73852443", "22799301", "75435901"]);
+    assert_samples(Algorithm::BLAKE2S256, 32, ["91948900", "68091993", "87470861", "96504322", "28968055", "09671488"]);
+}
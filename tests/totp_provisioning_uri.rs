@@ -163,3 +163,37 @@ fn provisioning_uri_should_fail_with_interval_less_than_30() {
     assert!(result.is_err(), "Expected an error");
     assert_eq!(result.err().unwrap().to_string(), UnsupportedIntervalError(29).to_string());
 }
+
+#[test]
+#[cfg(feature = "qr")]
+fn provisioning_qr_should_match_provisioning_uri() {
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        Secret::new_from_str("12345678901234567890").unwrap(),
+        NonZero::new(LENGTH).unwrap(),
+        Radix::new(RADIX).unwrap(),
+        NonZero::new(INTERVAL).unwrap(),
+    );
+
+    let qr = totp.provisioning_qr(ISSUER, NAME).unwrap();
+
+    assert_eq!(qr.uri(), totp.provisioning_uri(ISSUER, NAME).unwrap());
+    assert!(qr.svg().unwrap().contains("<svg"));
+}
+
+#[test]
+#[cfg(feature = "qr")]
+fn provisioning_qr_should_fail_with_sha256() {
+    let totp = TOTP::new(
+        Algorithm::SHA256,
+        Secret::new_from_str("12345678901234567890").unwrap(),
+        NonZero::new(LENGTH).unwrap(),
+        Radix::new(RADIX).unwrap(),
+        NonZero::new(INTERVAL).unwrap(),
+    );
+
+    let result = totp.provisioning_qr(ISSUER, NAME);
+
+    assert!(result.is_err(), "Expected an error");
+    assert_eq!(result.err().unwrap().to_string(), UnsupportedAlgorithmError(Algorithm::SHA256).to_string());
+}
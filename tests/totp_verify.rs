@@ -5,7 +5,7 @@
 //
 // See the file LICENSE for details.
 
-use rusotp::{AfterError, Algorithm, DriftBehindError, Radix, Secret, TOTP};
+use rusotp::{AfterError, Algorithm, DriftBehindError, Radix, Secret, VerifyOutcome, TOTP};
 use std::num::NonZero;
 
 const ALGORITHM: Algorithm = Algorithm::SHA256;
@@ -206,3 +206,92 @@ fn should_verify_with_drift_ahead() {
 
     assert!(verify.unwrap().is_some(), "OTP should be verified");
 }
+
+#[test]
+fn verify_window_at_should_return_time_step_drift_and_next() {
+    let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    let otp = totp.generate_at(1111111109).unwrap();
+
+    assert_eq!(
+        totp.verify_window_at(&otp, 1111111109, None, 0, 0).unwrap(),
+        Some(VerifyOutcome {
+            counter: 37037036,
+            drift: 0,
+            next: 37037037
+        })
+    );
+    assert_eq!(
+        totp.verify_window_at(&otp, 1111111109 + 60, None, 2, 0).unwrap(),
+        Some(VerifyOutcome {
+            counter: 37037036,
+            drift: -2,
+            next: 37037037
+        })
+    );
+    assert_eq!(
+        totp.verify_window_at(&otp, 1111111109 - 30, None, 0, 1).unwrap(),
+        Some(VerifyOutcome {
+            counter: 37037036,
+            drift: 1,
+            next: 37037037
+        })
+    );
+}
+
+#[test]
+fn verify_window_at_should_reject_steps_outside_the_window() {
+    let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    let otp = totp.generate_at(1111111109).unwrap();
+
+    assert_eq!(totp.verify_window_at(&otp, 1111111109 + 60, None, 1, 1).unwrap(), None);
+    assert_eq!(totp.verify_window_at(&otp, 1111111109 - 60, None, 1, 1).unwrap(), None);
+    assert_eq!(totp.verify_window_at(&otp[1..], 1111111109, None, 1, 1).unwrap(), None);
+}
+
+#[test]
+fn verify_window_at_should_reject_steps_before_next() {
+    let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    let otp = totp.generate_at(1111111109).unwrap();
+
+    let outcome = totp.verify_window_at(&otp, 1111111109, None, 1, 1).unwrap().unwrap();
+    assert_eq!(
+        totp.verify_window_at(&otp, 1111111109, Some(outcome.next), 1, 1)
+            .unwrap(),
+        None
+    );
+    assert_eq!(totp.verify_window_at(&otp, 1111111109, Some(u64::MAX), 1, 1).unwrap(), None);
+}
+
+#[test]
+fn verify_window_at_should_saturate_at_the_first_step() {
+    let totp = TOTP::default(Secret::new_from_str("12345678901234567890").unwrap());
+    let otp = totp.generate_at(0).unwrap();
+
+    let outcome = totp.verify_window_at(&otp, 30, None, 5, 0).unwrap().unwrap();
+    assert_eq!((outcome.counter, outcome.drift), (0, -1));
+}
+
+#[test]
+fn resync_at_should_stop_at_the_last_step() {
+    let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    let totp = TOTP::new(ALGORITHM, secret, NonZero::new(LENGTH).unwrap(), Radix::default(), NonZero::new(1).unwrap());
+    let first = totp.generate_at(u64::MAX - 1).unwrap();
+    let second = totp.generate_at(u64::MAX).unwrap();
+
+    assert_eq!(totp.resync_at(&first, &second, u64::MAX, None, 5).unwrap(), Some(u64::MAX));
+    assert_eq!(totp.resync_at(&second, &first, u64::MAX, None, 5).unwrap(), None);
+    assert_eq!(totp.resync_at(&first, &second, u64::MAX, Some(u64::MAX), 5).unwrap(), None);
+}
+
+#[test]
+fn verify_window_at_should_reject_the_last_step() {
+    let secret = Secret::new_from_str("12345678901234567890").unwrap();
+    let totp = TOTP::new(ALGORITHM, secret, NonZero::new(LENGTH).unwrap(), Radix::default(), NonZero::new(1).unwrap());
+    let otp = totp.generate_at(u64::MAX).unwrap();
+
+    assert_eq!(totp.verify_window_at(&otp, u64::MAX, None, 1, 1).unwrap(), None);
+
+    let otp = totp.generate_at(u64::MAX - 1).unwrap();
+    let outcome = totp.verify_window_at(&otp, u64::MAX, None, 1, 1).unwrap().unwrap();
+    assert_eq!((outcome.counter, outcome.drift, outcome.next), (u64::MAX - 1, -1, u64::MAX));
+}
@@ -0,0 +1,231 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "vault")]
+
+use rusotp::vault::{Vault, VaultEntry, VaultKdf, VAULT_VERSION};
+use rusotp::{OtpAccount, OtpKind, Secret, HOTP, TOTP};
+use std::fs;
+use std::path::PathBuf;
+
+const PASSWORD: &str = "correct horse battery staple";
+
+const KDF: VaultKdf = VaultKdf {
+    memory_kib: 64,
+    iterations: 1,
+    parallelism: 1,
+};
+
+fn vault_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rusotp-{}-{}.vault", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn totp_entry(issuer: &str, name: &str) -> VaultEntry {
+    VaultEntry::new(OtpAccount {
+        issuer: issuer.to_string(),
+        name: name.to_string(),
+        otp: OtpKind::TOTP(TOTP::default(Secret::new_from_str("12345678901234567890").unwrap())),
+    })
+}
+
+fn hotp_entry(issuer: &str, name: &str, counter: u64) -> VaultEntry {
+    VaultEntry::new(OtpAccount {
+        issuer: issuer.to_string(),
+        name: name.to_string(),
+        otp: OtpKind::HOTP(HOTP::default(Secret::new_from_str("12345678901234567890").unwrap()), counter),
+    })
+}
+
+#[test]
+fn vault_should_round_trip_entries() {
+    let path = vault_path("round-trip");
+    let mut vault = Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+    vault
+        .add(
+            totp_entry("rusotp", "user@email.mail")
+                .with_icon("rusotp.png")
+                .with_tags(vec!["work".to_string()]),
+        )
+        .unwrap();
+    vault.add(hotp_entry("", "counter", 7)).unwrap();
+    vault.save().unwrap();
+
+    let opened = Vault::open(&path, PASSWORD).unwrap();
+    assert_eq!(opened.entries(), vault.entries());
+    assert_eq!(opened.get("rusotp:user@email.mail").unwrap().icon.as_deref(), Some("rusotp.png"));
+    assert_eq!(opened.get("counter").unwrap().account.otp, hotp_entry("", "counter", 7).account.otp);
+    assert!(!path.with_extension("vault.tmp").exists());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn vault_should_persist_counter_updates() {
+    let path = vault_path("counter");
+    let mut vault = Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+    vault.add(hotp_entry("rusotp", "counter", 0)).unwrap();
+    if let OtpKind::HOTP(_, counter) = &mut vault.get_mut("rusotp:counter").unwrap().account.otp {
+        *counter += 1;
+    }
+    vault.save().unwrap();
+
+    let opened = Vault::open(&path, PASSWORD).unwrap();
+    assert_eq!(opened.entries()[0], hotp_entry("rusotp", "counter", 1));
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn create_should_fail_if_file_exists() {
+    let path = vault_path("exists");
+    Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+
+    let result = Vault::create_with_kdf(&path, PASSWORD, KDF);
+    assert!(result.unwrap_err().to_string().ends_with("already exists"));
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn open_should_fail_with_wrong_password() {
+    let path = vault_path("wrong-password");
+    Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+
+    let result = Vault::open(&path, "wrong");
+    assert_eq!(result.unwrap_err().to_string(), "Vault error: wrong password or corrupted vault");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn open_should_fail_for_future_version() {
+    let path = vault_path("future");
+    Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+    let content = fs::read_to_string(&path)
+        .unwrap()
+        .replace(&format!("\"version\": {}", VAULT_VERSION), &format!("\"version\": {}", VAULT_VERSION + 1));
+    fs::write(&path, content).unwrap();
+
+    let result = Vault::open(&path, PASSWORD);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        format!("Vault error: unsupported format version {}", VAULT_VERSION + 1)
+    );
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn open_should_reject_excessive_kdf_parameters() {
+    let path = vault_path("kdf");
+    Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+    let content = fs::read_to_string(&path)
+        .unwrap()
+        .replace(&format!("\"memory_kib\": {}", KDF.memory_kib), "\"memory_kib\": 4294967295");
+    fs::write(&path, content).unwrap();
+
+    let result = Vault::open(&path, PASSWORD);
+    assert_eq!(result.unwrap_err().to_string(), "Vault error: key derivation parameters too large");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn debug_should_not_print_the_key() {
+    let path = vault_path("debug");
+    let vault = Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+
+    let debug = format!("{:?}", vault);
+    assert!(debug.starts_with("Vault { path: "), "{}", debug);
+    assert!(!debug.contains("key"), "{}", debug);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn open_should_fail_for_other_files() {
+    let path = vault_path("other");
+    fs::write(&path, "{}").unwrap();
+
+    let result = Vault::open(&path, PASSWORD);
+    assert_eq!(result.unwrap_err().to_string(), "Vault error: not a rusotp vault");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn add_should_reject_duplicate_label() {
+    let path = vault_path("duplicate");
+    let mut vault = Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+    vault.add(totp_entry("rusotp", "user")).unwrap();
+
+    let result = vault.add(totp_entry("rusotp", "user"));
+    assert_eq!(result.unwrap_err().to_string(), "Vault error: `rusotp:user` already exists");
+    assert_eq!(vault.entries().len(), 1);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn remove_should_return_entry() {
+    let path = vault_path("remove");
+    let mut vault = Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+    vault.add(totp_entry("rusotp", "user")).unwrap();
+
+    assert_eq!(vault.remove("rusotp:user").unwrap(), totp_entry("rusotp", "user"));
+    assert!(vault.entries().is_empty());
+    assert_eq!(vault.remove("rusotp:user").unwrap_err().to_string(), "Vault error: `rusotp:user` not found");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rename_should_update_label() {
+    let path = vault_path("rename");
+    let mut vault = Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+    vault.add(totp_entry("rusotp", "user")).unwrap();
+    vault.add(totp_entry("rusotp", "other")).unwrap();
+
+    vault.rename("rusotp:user", "Example", "admin").unwrap();
+    assert!(vault.get("rusotp:user").is_none());
+    assert!(vault.get("Example:admin").is_some());
+
+    let result = vault.rename("Example:admin", "rusotp", "other");
+    assert_eq!(result.unwrap_err().to_string(), "Vault error: `rusotp:other` already exists");
+    assert!(vault.get("Example:admin").is_some());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn change_password_should_apply_on_save() {
+    let path = vault_path("change-password");
+    let mut vault = Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+    vault.add(totp_entry("rusotp", "user")).unwrap();
+    vault.change_password("new password").unwrap();
+    vault.save().unwrap();
+
+    assert!(Vault::open(&path, PASSWORD).is_err());
+    assert_eq!(Vault::open(&path, "new password").unwrap().entries().len(), 1);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn save_should_restrict_file_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = vault_path("permissions");
+    Vault::create_with_kdf(&path, PASSWORD, KDF).unwrap();
+
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+    fs::remove_file(&path).unwrap();
+}
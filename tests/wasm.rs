@@ -0,0 +1,61 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+//! Run with `wasm-pack test --node -- --no-default-features --features wasm --test wasm`.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use rusotp::wasm::{HotpJs, TotpJs};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn hotp_should_match_rfc_sample() {
+    let hotp = HotpJs::new("SHA1", "12345678901234567890", 6, 10).unwrap();
+
+    assert_eq!(hotp.generate(1).unwrap(), "287082");
+    assert_eq!(hotp.verify("287082", 0, 1).unwrap(), Some(1));
+    assert_eq!(hotp.verify("287082", 2, 0).unwrap(), None);
+}
+
+#[wasm_bindgen_test]
+fn hotp_provisioning_uri_should_round_trip() {
+    let hotp = HotpJs::new("SHA1", "12345678901234567890", 6, 10).unwrap();
+    let uri = hotp.provisioning_uri("rusotp", "user@email.mail", 1).unwrap();
+
+    assert_eq!(HotpJs::from_uri(&uri).unwrap().generate(1).unwrap(), "287082");
+    assert!(hotp
+        .provisioning_qr_svg("rusotp", "user@email.mail", 1)
+        .unwrap()
+        .contains("<svg"));
+}
+
+#[wasm_bindgen_test]
+fn totp_should_match_rfc_sample() {
+    let totp = TotpJs::new("SHA1", "12345678901234567890", 8, 10, 30).unwrap();
+
+    assert_eq!(totp.generate_at(59).unwrap(), "94287082");
+    assert_eq!(totp.verify_at("94287082", 59, None, 0, 0).unwrap(), Some(59));
+}
+
+#[wasm_bindgen_test]
+fn totp_should_use_javascript_clock() {
+    let totp = TotpJs::new("SHA1", "12345678901234567890", 6, 10, 30).unwrap();
+    let otp = totp.generate().unwrap();
+
+    assert_eq!(otp.len(), 6);
+    assert!(totp.verify(&otp, None, 30, 30).unwrap().is_some());
+}
+
+#[wasm_bindgen_test]
+fn totp_provisioning_qr_svg_should_render() {
+    let totp = TotpJs::new("SHA1", "12345678901234567890", 6, 10, 30).unwrap();
+
+    assert!(totp
+        .provisioning_qr_svg("rusotp", "user@email.mail")
+        .unwrap()
+        .contains("<svg"));
+}
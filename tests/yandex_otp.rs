@@ -0,0 +1,121 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "yandex")]
+
+use rusotp::{Secret, YandexOtp};
+
+// Published example vectors of Yandex Key, as used by the Aegis authenticator test suite.
+const SAMPLES: [(&str, &str, u64, &str); 5] = [
+    ("5239", "6SB2IKNM6OBZPAVBVTOHDKS4FAAAAAAADFUTQMBTRY", 1641559648, "umozdicq"),
+    ("7586", "LA2V6KMCGYMWWVEW64RNP3JA3IAAAAAAHTSG4HRZPI", 1581064020, "oactmacq"),
+    ("7586", "LA2V6KMCGYMWWVEW64RNP3JA3IAAAAAAHTSG4HRZPI", 1581090810, "wemdwrix"),
+    ("5210481216086702", "JBGSAU4G7IEZG6OY4UAXX62JU4AAAAAAHTSG4HXU3M", 1581091469, "dfrpywob"),
+    ("5210481216086702", "JBGSAU4G7IEZG6OY4UAXX62JU4AAAAAAHTSG4HXU3M", 1581093059, "vunyprpd"),
+];
+
+fn yandex(pin: &str, secret: &str) -> YandexOtp {
+    YandexOtp::new(Secret::new_from_base32(secret).unwrap(), pin).unwrap()
+}
+
+#[test]
+fn generated_codes_should_match_published_samples() {
+    for (pin, secret, timestamp, code) in SAMPLES {
+        assert_eq!(yandex(pin, secret).generate_at(timestamp).unwrap(), code);
+    }
+}
+
+#[test]
+fn sixteen_byte_secret_should_generate_the_same_codes() {
+    for (pin, secret, timestamp, code) in SAMPLES {
+        let mut key = Secret::new_from_base32(secret).unwrap().get();
+        key.truncate(16);
+        let yandex = YandexOtp::new(Secret::new_from_vec(key.clone()), pin).unwrap();
+        assert_eq!(yandex.generate_at(timestamp).unwrap(), code);
+        assert_eq!(yandex.secret().0, key);
+    }
+}
+
+#[test]
+fn wrong_checksum_should_be_rejected() {
+    let mut secret = Secret::new_from_base32(SAMPLES[1].1).unwrap().get();
+    secret[3] ^= 1;
+    let result = YandexOtp::new(Secret::new_from_vec(secret), "7586");
+    assert_eq!(result.unwrap_err().to_string(), "Invalid secret");
+}
+
+#[test]
+fn secret_of_another_length_should_be_rejected() {
+    let result = YandexOtp::new(Secret::new_from_str("12345678901234567890").unwrap(), "7586");
+    assert_eq!(result.unwrap_err().to_string(), "Invalid secret");
+}
+
+#[test]
+fn invalid_pin_should_be_rejected() {
+    let secret = Secret::new_from_base32(SAMPLES[1].1).unwrap();
+    for pin in ["123", "12345678901234567", "12a4"] {
+        let result = YandexOtp::new(secret.clone(), pin);
+        assert_eq!(result.unwrap_err().to_string(), "Invalid PIN: must be 4 to 16 digits");
+    }
+}
+
+#[test]
+fn pin_should_change_the_code() {
+    let (_, secret, timestamp, code) = SAMPLES[1];
+    assert_ne!(yandex("7587", secret).generate_at(timestamp).unwrap(), code);
+}
+
+#[test]
+fn verify_should_accept_codes_within_the_window() {
+    let (pin, secret, timestamp, code) = SAMPLES[2];
+    let yandex = yandex(pin, secret);
+    assert_eq!(yandex.verify_at(code, timestamp, None, 0, 0).unwrap(), Some(timestamp));
+    assert_eq!(yandex.verify_at(code, timestamp + 30, None, 0, 30).unwrap(), Some(1581090810));
+    assert_eq!(yandex.verify_at(&code.to_uppercase(), timestamp, None, 0, 0).unwrap(), Some(timestamp));
+}
+
+#[test]
+fn verify_should_reject_codes_outside_the_window_or_before_after() {
+    let (pin, secret, timestamp, code) = SAMPLES[2];
+    let yandex = yandex(pin, secret);
+    assert_eq!(yandex.verify_at(code, timestamp + 60, None, 30, 30).unwrap(), None);
+    assert_eq!(
+        yandex
+            .verify_at(code, timestamp + 30, Some(timestamp + 30), 30, 30)
+            .unwrap(),
+        None
+    );
+    assert_eq!(yandex.verify_at("aaaaaaaa", timestamp, None, 30, 30).unwrap(), None);
+    assert_eq!(yandex.verify_at("wemdwri", timestamp, None, 30, 30).unwrap(), None);
+}
+
+#[cfg(feature = "uri")]
+mod uri {
+    use rusotp::YandexOtp;
+
+    const URI: &str = "otpauth://yaotp/user?secret=LA2V6KMCGYMWWVEW64RNP3JA3IAAAAAAHTSG4HRZPI&name=user&pin_length=4";
+
+    #[test]
+    fn from_uri_should_import_the_secret() {
+        let yandex = YandexOtp::from_uri(URI, "7586").unwrap();
+        assert_eq!(yandex.generate_at(1581064020).unwrap(), "oactmacq");
+    }
+
+    #[test]
+    fn from_uri_should_check_the_pin_length() {
+        let result = YandexOtp::from_uri(URI, "75860");
+        assert_eq!(result.unwrap_err().to_string(), "Invalid PIN: must be 4 digits");
+    }
+
+    #[test]
+    fn from_uri_should_reject_other_uris() {
+        let uri = "otpauth://totp/user?secret=LA2V6KMCGYMWWVEW64RNP3JA3IAAAAAAHTSG4HRZPI";
+        let result = YandexOtp::from_uri(uri, "7586");
+        assert_eq!(result.unwrap_err().to_string(), "Invalid URI: expected an `otpauth://yaotp/` URI");
+        assert!(YandexOtp::from_uri("otpauth://yaotp/user?name=user", "7586").is_err());
+    }
+}
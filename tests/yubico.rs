@@ -0,0 +1,156 @@
+// Copyright (c) Indrajit Roy
+//
+// This file is licensed under the Affero General Public License version 3 or
+// any later version.
+//
+// See the file LICENSE for details.
+
+#![cfg(feature = "yubico")]
+
+use rusotp::yubico::{
+    public_id, MemoryYubicoCounterStore, YubicoCounter, YubicoCounterStore, YubicoKey, YubicoToken, YubicoValidator,
+};
+
+const PUBLIC_ID: &str = "ctcucvbcbbbd";
+const PRIVATE_ID: &str = "8792ebfe26cc";
+const AES_KEY: &str = "ecde18dbe76fbd0c33330f1c354871db";
+
+// OTPs of one YubiKey in the order it typed them.
+const FIRST: &str = "ctcucvbcbbbdjrrjlijkbttcfbtbggdbinghlitkvulu";
+const SECOND: &str = "ctcucvbcbbbdjruhjnceeihncicgivkcvnncrrchhibl";
+const AFTER_POWER_UP: &str = "ctcucvbcbbbdcijcerbbncrflljfkncbchunrrfthihe";
+const WITH_FLAG: &str = "ctcucvbcbbbdiuhjnlijeiffckfndhfbtehliilkhvfv";
+
+fn key() -> YubicoKey {
+    YubicoKey::new(PUBLIC_ID, PRIVATE_ID, AES_KEY).unwrap()
+}
+
+#[test]
+fn decrypt_should_extract_the_token_fields() {
+    let token = key().decrypt(FIRST).unwrap().unwrap();
+    assert_eq!(
+        token,
+        YubicoToken {
+            public_id: PUBLIC_ID.to_string(),
+            private_id: [0x87, 0x92, 0xeb, 0xfe, 0x26, 0xcc],
+            counter: YubicoCounter { usage: 19, session: 0 },
+            timestamp: 0x3f8e0a,
+            random: 0xb971,
+        }
+    );
+}
+
+#[test]
+fn decrypt_should_ignore_letter_case_and_whitespace() {
+    let token = key()
+        .decrypt(&format!(" {}\n", SECOND.to_uppercase()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(token.counter, YubicoCounter { usage: 19, session: 1 });
+    assert_eq!(token.public_id, PUBLIC_ID);
+}
+
+#[test]
+fn usage_counter_should_not_include_the_flag_bit() {
+    let token = key().decrypt(WITH_FLAG).unwrap().unwrap();
+    assert_eq!(token.counter, YubicoCounter { usage: 21, session: 0 });
+}
+
+#[test]
+fn decrypt_should_reject_another_key() {
+    let other = YubicoKey::new(PUBLIC_ID, PRIVATE_ID, "00112233445566778899aabbccddeeff").unwrap();
+    assert!(other.decrypt(FIRST).unwrap().is_none());
+}
+
+#[test]
+fn decrypt_should_reject_another_private_id() {
+    let other = YubicoKey::new(PUBLIC_ID, "000000000000", AES_KEY).unwrap();
+    assert!(other.decrypt(FIRST).unwrap().is_none());
+}
+
+#[test]
+fn decrypt_should_reject_another_public_id() {
+    let other = YubicoKey::new("cccccccccccb", PRIVATE_ID, AES_KEY).unwrap();
+    assert!(other.decrypt(FIRST).unwrap().is_none());
+}
+
+#[test]
+fn decrypt_should_reject_a_tampered_token() {
+    let tampered = FIRST.replacen("jrrj", "jrrk", 1);
+    assert!(key().decrypt(&tampered).unwrap().is_none());
+}
+
+#[test]
+fn malformed_otps_should_fail() {
+    let key = key();
+    for otp in [
+        "",
+        &FIRST[..30],
+        &format!("{}{}", FIRST, FIRST),
+        &FIRST.replace('j', "0"),
+    ] {
+        assert!(key.decrypt(otp).is_err(), "{}", otp);
+    }
+}
+
+#[test]
+fn malformed_keys_should_fail() {
+    assert!(YubicoKey::new("ctcucvbcbbbz", PRIVATE_ID, AES_KEY).is_err());
+    assert!(YubicoKey::new(PUBLIC_ID, "8792ebfe26", AES_KEY).is_err());
+    assert!(YubicoKey::new(PUBLIC_ID, PRIVATE_ID, "ecde18dbe76fbd0c").is_err());
+}
+
+#[test]
+fn public_id_should_be_read_from_the_otp() {
+    assert_eq!(public_id(FIRST).unwrap(), PUBLIC_ID);
+    assert_eq!(public_id(&FIRST.to_uppercase()).unwrap(), PUBLIC_ID);
+    assert_eq!(public_id(&FIRST[12..]).unwrap(), "");
+    assert!(public_id("short").is_err());
+}
+
+#[test]
+fn validator_should_accept_each_otp_once() {
+    let validator = YubicoValidator::new(MemoryYubicoCounterStore::new());
+    let key = key();
+
+    assert!(validator.verify(&key, FIRST).unwrap().is_some());
+    assert!(validator.verify(&key, FIRST).unwrap().is_none());
+    assert!(validator.verify(&key, SECOND).unwrap().is_some());
+    assert_eq!(validator.store().last(PUBLIC_ID).unwrap(), Some(YubicoCounter { usage: 19, session: 1 }));
+}
+
+#[test]
+fn validator_should_reject_older_otps() {
+    let validator = YubicoValidator::new(MemoryYubicoCounterStore::new());
+    let key = key();
+
+    assert!(validator.verify(&key, AFTER_POWER_UP).unwrap().is_some());
+    assert!(validator.verify(&key, SECOND).unwrap().is_none());
+    assert!(validator.verify(&key, FIRST).unwrap().is_none());
+    assert!(validator.verify(&key, WITH_FLAG).unwrap().is_some());
+}
+
+#[test]
+fn validator_should_not_record_invalid_otps() {
+    let validator = YubicoValidator::new(MemoryYubicoCounterStore::new());
+    let other = YubicoKey::new(PUBLIC_ID, PRIVATE_ID, "00112233445566778899aabbccddeeff").unwrap();
+
+    assert!(validator.verify(&other, SECOND).unwrap().is_none());
+    assert_eq!(validator.store().last(PUBLIC_ID).unwrap(), None);
+    assert!(validator.verify(&key(), FIRST).unwrap().is_some());
+}
+
+#[test]
+fn counters_should_be_ordered_by_usage_then_session() {
+    let older = YubicoCounter {
+        usage: 19,
+        session: 255,
+    };
+    let newer = YubicoCounter { usage: 20, session: 0 };
+    assert!(older < newer);
+
+    let store = MemoryYubicoCounterStore::new();
+    assert!(store.advance(PUBLIC_ID, newer).unwrap());
+    assert!(!store.advance(PUBLIC_ID, older).unwrap());
+    assert!(!store.advance(PUBLIC_ID, newer).unwrap());
+}